@@ -7,10 +7,17 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::{Instant, SystemTime};
 use uuid::Uuid;
 
-use crate::models::{User, Channel};
+use crate::audio::SystemMonitor;
+use crate::audio::router::{
+    ChannelRoutingConfig, QualityMode, MAX_BITRATE_KBPS, MAX_LATENCY_TARGET_MS, MIN_BITRATE_KBPS, MIN_LATENCY_TARGET_MS,
+};
+use crate::models::{user::UserStatus, ChannelResponse, DetailedChannelResponse, ServerMessage, UserResponse};
+use crate::networking::WebSocketHandler;
 use crate::services::{UserService, ChannelService, AudioService};
+use crate::Error;
 
 /// Configuration avancée pour les APIs
 #[derive(Debug, Clone)]
@@ -38,7 +45,11 @@ pub struct AdvancedApiState {
     pub user_service: Arc<UserService>,
     pub channel_service: Arc<ChannelService>,
     pub audio_service: Arc<AudioService>,
+    pub websocket_handler: Arc<WebSocketHandler>,
     pub config: AdvancedApiConfig,
+    pub started_at: Instant,
+    /// Moniteur propre à l'API : ses relevés ne décalent pas la mesure CPU du collecteur
+    pub system_monitor: Arc<SystemMonitor>,
 }
 
 /// Paramètres de pagination
@@ -69,6 +80,15 @@ pub struct ChannelFilterQuery {
     include_private: Option<bool>,
 }
 
+/// Paramètres de recherche d'utilisateurs
+#[derive(Debug, Deserialize)]
+pub struct UserSearchQuery {
+    /// Texte recherché dans le nom d'utilisateur
+    q: Option<String>,
+    /// Nombre maximum de résultats
+    limit: Option<usize>,
+}
+
 /// Paramètres pour la configuration audio
 #[derive(Debug, Deserialize)]
 pub struct AudioConfigRequest {
@@ -76,6 +96,8 @@ pub struct AudioConfigRequest {
     pub channels: Option<u8>,
     pub bit_depth: Option<u8>,
     pub buffer_size: Option<usize>,
+    pub max_packet_size: Option<usize>,
+    pub loopback_mode: Option<bool>,
     pub latency_target_ms: Option<u64>,
     pub quality_mode: Option<String>,
 }
@@ -190,6 +212,90 @@ pub struct DetailedErrorResponse {
     pub timestamp: u64,
 }
 
+type ApiError = (StatusCode, Json<DetailedErrorResponse>);
+
+/// Construit une réponse d'erreur détaillée
+fn api_error(status: StatusCode, error_code: &str, error: impl ToString) -> ApiError {
+    let error = DetailedErrorResponse {
+        success: false,
+        error: error.to_string(),
+        error_code: error_code.to_string(),
+        details: None,
+        timestamp: unix_timestamp(),
+    };
+    (status, Json(error))
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn ensure_enabled(enabled: bool, feature: &str) -> Result<(), ApiError> {
+    if enabled {
+        Ok(())
+    } else {
+        Err(api_error(StatusCode::FORBIDDEN, "FEATURE_DISABLED", format!("{} endpoints are disabled", feature)))
+    }
+}
+
+fn find_user(state: &AdvancedApiState, user_id: &Uuid) -> Result<UserResponse, ApiError> {
+    state.user_service.get_user(user_id)
+        .map_err(|e| api_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND", e))
+}
+
+fn find_channel(state: &AdvancedApiState, channel_id: &Uuid) -> Result<DetailedChannelResponse, ApiError> {
+    state.channel_service.get_channel(channel_id)
+        .map_err(|e| api_error(StatusCode::NOT_FOUND, "CHANNEL_NOT_FOUND", e))
+}
+
+/// Configuration de routage effective d'un channel, avec sa capacité réelle
+fn channel_routing_config(state: &AdvancedApiState, channel_id: &Uuid) -> Result<ChannelRoutingConfig, ApiError> {
+    let channel = find_channel(state, channel_id)?;
+    let mut config = state.audio_service.get_channel_routing_config(channel_id);
+    config.max_users = channel.max_users;
+    Ok(config)
+}
+
+/// Trie selon l'ordre demandé puis découpe la page demandée
+fn paginate<T>(
+    mut items: Vec<T>,
+    pagination: &PaginationQuery,
+    max_limit: usize,
+    compare: impl Fn(&T, &T) -> std::cmp::Ordering,
+) -> Result<PaginatedResponse<T>, ApiError> {
+    let page = pagination.page.unwrap_or(1).max(1);
+    let limit = pagination.limit.unwrap_or(20).clamp(1, max_limit.max(1));
+
+    match pagination.order.as_deref().unwrap_or("asc") {
+        "asc" => items.sort_by(&compare),
+        "desc" => items.sort_by(|a, b| compare(b, a)),
+        other => return Err(api_error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_SORT_ORDER",
+            format!("Unknown sort order '{}', expected 'asc' or 'desc'", other),
+        )),
+    }
+
+    let total_items = items.len();
+    let total_pages = total_items.div_ceil(limit);
+    let data = items.into_iter().skip((page - 1) * limit).take(limit).collect();
+
+    Ok(PaginatedResponse {
+        data,
+        pagination: PaginationInfo {
+            current_page: page,
+            total_pages,
+            total_items,
+            items_per_page: limit,
+            has_next: page < total_pages,
+            has_previous: page > 1,
+        },
+    })
+}
+
 /// Crée le routeur pour les endpoints avancés
 pub fn create_advanced_router(state: AdvancedApiState) -> Router {
     Router::new()
@@ -232,283 +338,713 @@ pub fn create_advanced_router(state: AdvancedApiState) -> Router {
 pub async fn list_users_paginated(
     Query(pagination): Query<PaginationQuery>,
     State(state): State<AdvancedApiState>,
-) -> Result<Json<PaginatedResponse<serde_json::Value>>, (StatusCode, Json<DetailedErrorResponse>)> {
-    let page = pagination.page.unwrap_or(1).max(1);
-    let limit = pagination.limit.unwrap_or(20).min(state.config.max_pagination_limit);
-    
-    // Simulation pour la compilation
-    let total_items = 50; // Simulation
-    let total_pages = (total_items + limit - 1) / limit;
-    
-    let page_users = vec![]; // Simulation vide
-    
-    let response = PaginatedResponse {
-        data: page_users,
-        pagination: PaginationInfo {
-            current_page: page,
-            total_pages,
-            total_items,
-            items_per_page: limit,
-            has_next: page < total_pages,
-            has_previous: page > 1,
-        },
+) -> Result<Json<PaginatedResponse<UserResponse>>, (StatusCode, Json<DetailedErrorResponse>)> {
+    let users = state.user_service.list_users();
+
+    let response = match pagination.sort_by.as_deref().unwrap_or("username") {
+        "username" => paginate(users, &pagination, state.config.max_pagination_limit, |a, b| {
+            a.username.to_lowercase().cmp(&b.username.to_lowercase())
+        })?,
+        "status" => paginate(users, &pagination, state.config.max_pagination_limit, |a, b| {
+            format!("{:?}", a.status).cmp(&format!("{:?}", b.status))
+        })?,
+        other => return Err(api_error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_SORT_FIELD",
+            format!("Cannot sort users by '{}'", other),
+        )),
     };
-    
+
     Ok(Json(response))
 }
 
 /// PUT /advanced/users/:id - Met à jour un utilisateur
 pub async fn update_user(
-    Path(_user_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
-    Json(_request): Json<UserUpdateRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    // Simulation pour compilation
-    Ok(Json(serde_json::json!({"success": true, "message": "User updated"})))
+    Path(user_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
+    Json(request): Json<UserUpdateRequest>,
+) -> Result<Json<UserResponse>, (StatusCode, Json<DetailedErrorResponse>)> {
+    let user = find_user(&state, &user_id)?;
+
+    if request.display_name.is_some() {
+        return Err(api_error(StatusCode::BAD_REQUEST, "UNSUPPORTED_FIELD", "display_name is not supported"));
+    }
+
+    if let Some(username) = request.username {
+        if let Ok(existing) = state.user_service.get_user_by_username(&username) {
+            if existing.id != user_id {
+                return Err(api_error(
+                    StatusCode::CONFLICT,
+                    "USERNAME_TAKEN",
+                    format!("Username '{}' is already taken", username),
+                ));
+            }
+        }
+
+        state.user_service.rename_user(&user_id, username)
+            .map_err(|e| api_error(StatusCode::BAD_REQUEST, "INVALID_USERNAME", e))?;
+
+        if let Some(channel_id) = user.current_channel {
+            state.websocket_handler.notify_channel_updated(channel_id).await;
+        }
+    }
+
+    if let Some(is_active) = request.is_active {
+        let status = match (is_active, user.current_channel) {
+            (false, _) => UserStatus::Away,
+            (true, Some(_)) => UserStatus::InChannel,
+            (true, None) => UserStatus::Online,
+        };
+
+        state.user_service.update_user_status(&user_id, status.clone())
+            .map_err(|e| api_error(StatusCode::NOT_FOUND, "USER_NOT_FOUND", e))?;
+        let _ = state.websocket_handler.broadcast_to_all(
+            ServerMessage::UserStatusChanged { user_id, status },
+            None,
+        ).await;
+    }
+
+    Ok(Json(find_user(&state, &user_id)?))
 }
 
 /// DELETE /advanced/users/:id - Supprime un utilisateur
 pub async fn delete_user(
-    Path(_user_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<StatusCode, (StatusCode, Json<DetailedErrorResponse>)> {
-    // Simulation pour compilation
+    ensure_enabled(state.config.enable_admin_endpoints, "Admin")?;
+    find_user(&state, &user_id)?;
+
+    state.websocket_handler.force_disconnect(user_id).await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// GET /advanced/channels - Liste filtrée des channels
 pub async fn list_channels_filtered(
-    Query(_filter): Query<ChannelFilterQuery>,
+    Query(filter): Query<ChannelFilterQuery>,
     Query(pagination): Query<PaginationQuery>,
     State(state): State<AdvancedApiState>,
-) -> Result<Json<PaginatedResponse<serde_json::Value>>, (StatusCode, Json<DetailedErrorResponse>)> {
-    let page = pagination.page.unwrap_or(1).max(1);
-    let limit = pagination.limit.unwrap_or(20).min(state.config.max_pagination_limit);
-    
-    // Simulation pour compilation
-    let total_items = 10;
-    let total_pages = (total_items + limit - 1) / limit;
-    
-    let response = PaginatedResponse {
-        data: vec![],
-        pagination: PaginationInfo {
-            current_page: page,
-            total_pages,
-            total_items,
-            items_per_page: limit,
-            has_next: page < total_pages,
-            has_previous: page > 1,
-        },
+) -> Result<Json<PaginatedResponse<ChannelResponse>>, (StatusCode, Json<DetailedErrorResponse>)> {
+    let channels = if filter.include_private.unwrap_or(false) {
+        state.channel_service.list_all_channels()
+    } else {
+        state.channel_service.list_channels()
+    };
+
+    let name = filter.name.map(|name| name.to_lowercase());
+    let channel_type = filter.channel_type.as_deref().map(str::to_lowercase);
+    if let Some(ref channel_type) = channel_type {
+        if !matches!(channel_type.as_str(), "public" | "private" | "protected") {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_CHANNEL_TYPE",
+                format!("Unknown channel type '{}', expected public, private or protected", channel_type),
+            ));
+        }
+    }
+
+    let channels: Vec<ChannelResponse> = channels.into_iter()
+        .filter(|c| name.as_ref().is_none_or(|name| c.name.to_lowercase().contains(name)))
+        .filter(|c| match channel_type.as_deref() {
+            Some("public") => !c.is_private,
+            Some("private") => c.is_private,
+            Some("protected") => c.has_password,
+            _ => true,
+        })
+        .filter(|c| filter.min_users.is_none_or(|min| c.current_user_count >= min))
+        .filter(|c| filter.max_users.is_none_or(|max| c.current_user_count <= max))
+        .collect();
+
+    let max_limit = state.config.max_pagination_limit;
+    let response = match pagination.sort_by.as_deref().unwrap_or("name") {
+        "name" => paginate(channels, &pagination, max_limit, |a, b| {
+            a.name.to_lowercase().cmp(&b.name.to_lowercase())
+        })?,
+        "users" => paginate(channels, &pagination, max_limit, |a, b| {
+            a.current_user_count.cmp(&b.current_user_count)
+        })?,
+        "created_at" => paginate(channels, &pagination, max_limit, |a, b| {
+            a.created_at.cmp(&b.created_at)
+        })?,
+        other => return Err(api_error(
+            StatusCode::BAD_REQUEST,
+            "INVALID_SORT_FIELD",
+            format!("Cannot sort channels by '{}'", other),
+        )),
     };
-    
+
     Ok(Json(response))
 }
 
 /// PUT /advanced/audio/config - Met à jour la configuration audio
 pub async fn update_audio_config(
-    State(_state): State<AdvancedApiState>,
-    Json(_request): Json<AudioConfigRequest>,
+    State(state): State<AdvancedApiState>,
+    Json(request): Json<AudioConfigRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    // Simulation pour compilation
-    Ok(Json(serde_json::json!({"success": true, "message": "Audio config updated"})))
+    ensure_enabled(state.config.enable_audio_control, "Audio control")?;
+
+    if let Some(bit_depth) = request.bit_depth {
+        if bit_depth != 16 {
+            return Err(api_error(StatusCode::BAD_REQUEST, "INVALID_AUDIO_CONFIG", "Only 16-bit PCM is supported"));
+        }
+    }
+
+    let mut config = state.audio_service.config();
+    // Valeurs de routage des channels sans configuration propre
+    let mut defaults = state.audio_service.channel_routing_defaults();
+
+    if let Some(ref quality_mode) = request.quality_mode {
+        defaults.quality_mode = quality_mode.parse::<QualityMode>()
+            .map_err(|e| api_error(StatusCode::BAD_REQUEST, "INVALID_QUALITY_MODE", e))?;
+    }
+
+    if let Some(latency_target_ms) = request.latency_target_ms {
+        if !(MIN_LATENCY_TARGET_MS..=MAX_LATENCY_TARGET_MS).contains(&latency_target_ms) {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_AUDIO_CONFIG",
+                format!("latency_target_ms must be between {} and {}", MIN_LATENCY_TARGET_MS, MAX_LATENCY_TARGET_MS),
+            ));
+        }
+        defaults.latency_target_ms = latency_target_ms;
+    }
+
+    if let Some(sample_rate) = request.sample_rate {
+        if ![8000, 16000, 24000, 32000, 44100, 48000].contains(&sample_rate) {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_AUDIO_CONFIG",
+                format!("Unsupported sample rate {}", sample_rate),
+            ));
+        }
+        config.sample_rate = sample_rate;
+    }
+
+    if let Some(channels) = request.channels {
+        if !(1..=2).contains(&channels) {
+            return Err(api_error(StatusCode::BAD_REQUEST, "INVALID_AUDIO_CONFIG", "channels must be 1 or 2"));
+        }
+        config.channels = channels as u16;
+    }
+
+    if let Some(buffer_size) = request.buffer_size {
+        if !(64..=8192).contains(&buffer_size) {
+            return Err(api_error(StatusCode::BAD_REQUEST, "INVALID_AUDIO_CONFIG", "buffer_size must be between 64 and 8192"));
+        }
+        config.buffer_size = buffer_size;
+    }
+
+    if let Some(max_packet_size) = request.max_packet_size {
+        if !(256..=65507).contains(&max_packet_size) {
+            return Err(api_error(StatusCode::BAD_REQUEST, "INVALID_AUDIO_CONFIG", "max_packet_size must be between 256 and 65507"));
+        }
+        config.max_packet_size = max_packet_size;
+    }
+
+    if let Some(loopback_mode) = request.loopback_mode {
+        config.loopback_mode = loopback_mode;
+    }
+
+    state.audio_service.update_config(config.clone());
+    tracing::info!("Audio config updated: {:?}", config);
+    notify_audio_config(&state).await;
+
+    if request.quality_mode.is_some() || request.latency_target_ms.is_some() {
+        state.audio_service.set_channel_routing_defaults(defaults.clone());
+        notify_default_routing(&state).await;
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Audio config updated",
+        "config": config,
+        "routing_defaults": defaults,
+    })))
 }
 
 /// GET /advanced/statistics/server - Statistiques globales du serveur
 pub async fn get_server_statistics(
-    State(_state): State<AdvancedApiState>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<ServerStatistics>, (StatusCode, Json<DetailedErrorResponse>)> {
-    // Simulation pour compilation
+    ensure_enabled(state.config.enable_statistics, "Statistics")?;
+
+    let users = state.user_service.list_users();
+    let channels = state.channel_service.list_all_channels();
+    let uptime_seconds = state.started_at.elapsed().as_secs();
+    let (total_audio_packets, total_bytes) = state.audio_service.total_traffic();
+    let total_data_transferred_mb = total_bytes as f64 / (1024.0 * 1024.0);
+    let process = state.system_monitor.snapshot();
+
     let stats = ServerStatistics {
-        total_users: 25,
-        active_users: 15,
-        total_channels: 5,
-        active_channels: 3,
-        uptime_seconds: 3600,
-        total_audio_packets: 1000000,
-        total_data_transferred_mb: 500.0,
+        total_users: users.len(),
+        active_users: users.iter()
+            .filter(|u| matches!(u.status, UserStatus::Online | UserStatus::InChannel))
+            .count(),
+        total_channels: channels.len(),
+        active_channels: channels.iter().filter(|c| c.current_user_count > 0).count(),
+        uptime_seconds,
+        total_audio_packets,
+        total_data_transferred_mb,
         system_stats: SystemStats {
-            cpu_usage_percent: 25.0,
-            memory_usage_mb: 512,
-            network_throughput_mbps: 10.0,
-            active_connections: 15,
+            cpu_usage_percent: process.cpu_usage_percent,
+            memory_usage_mb: process.memory_usage_mb,
+            network_throughput_mbps: if uptime_seconds > 0 {
+                (total_bytes as f64 * 8.0 / 1_000_000.0 / uptime_seconds as f64) as f32
+            } else {
+                0.0
+            },
+            active_connections: state.websocket_handler.connection_count(),
         },
     };
-    
+
     Ok(Json(stats))
 }
 
-/// GET /advanced/channels/:id/statistics - Statistiques détaillées d'un channel
-pub async fn get_channel_statistics(
-    Path(_channel_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
-) -> Result<Json<ChannelStatistics>, (StatusCode, Json<DetailedErrorResponse>)> {
-    // Simulation pour compilation
-    let stats = ChannelStatistics {
-        channel_id: Uuid::new_v4(),
-        current_users: 5,
-        total_connections: 100,
-        uptime_seconds: 1800,
-        audio_stats: ChannelAudioStats {
-            packets_sent: 10000,
-            packets_received: 9950,
-            bytes_transferred: 5000000,
-            average_latency_ms: 45.0,
-            packet_loss_rate: 0.5,
-            audio_quality_score: 0.95,
-            jitter_ms: 2.0,
+/// Diffuse les paramètres audio négociés à tous les clients
+async fn notify_audio_config(state: &AdvancedApiState) {
+    let _ = state.websocket_handler.broadcast_to_all(
+        ServerMessage::AudioConfigChanged { config: state.audio_service.negotiated_config() },
+        None,
+    ).await;
+}
+
+/// Annonce leur nouvelle configuration aux channels qui suivent les valeurs par défaut
+async fn notify_default_routing(state: &AdvancedApiState) {
+    for channel in state.channel_service.list_all_channels() {
+        if state.audio_service.has_channel_routing_config(&channel.id) {
+            continue;
+        }
+        if let Ok(config) = channel_routing_config(state, &channel.id) {
+            let _ = state.websocket_handler.broadcast_to_channel(
+                channel.id,
+                ServerMessage::ChannelRoutingChanged { channel_id: channel.id, config },
+                None,
+            ).await;
+        }
+    }
+}
+
+/// Construit les statistiques d'un channel à partir du routeur audio
+fn build_channel_statistics(state: &AdvancedApiState, channel: &DetailedChannelResponse) -> ChannelStatistics {
+    let routing = state.audio_service.get_channel_stats(&channel.id);
+    let report = state.audio_service.analyze_channel_performance(&channel.id);
+
+    let audio_stats = match routing {
+        Some(ref stats) => ChannelAudioStats {
+            packets_sent: stats.packets_sent,
+            packets_received: stats.packets_received,
+            bytes_transferred: stats.bytes_received + stats.bytes_sent,
+            average_latency_ms: stats.average_latency_ms,
+            packet_loss_rate: stats.packet_loss_rate,
+            audio_quality_score: report.as_ref().map(|r| r.quality_score).unwrap_or(0.0),
+            jitter_ms: stats.jitter_ms,
         },
-        performance_stats: ChannelPerformanceStats {
-            cpu_usage_percent: 5.0,
-            memory_usage_mb: 50,
-            active_streams: 5,
-            processing_latency_us: 500,
+        None => ChannelAudioStats {
+            packets_sent: 0,
+            packets_received: 0,
+            bytes_transferred: 0,
+            average_latency_ms: 0.0,
+            packet_loss_rate: 0.0,
+            audio_quality_score: 0.0,
+            jitter_ms: 0.0,
         },
     };
-    
-    Ok(Json(stats))
+
+    ChannelStatistics {
+        channel_id: channel.id,
+        current_users: channel.current_users.len(),
+        total_connections: routing.as_ref().map(|s| s.connected_users as u64).unwrap_or(0),
+        uptime_seconds: channel.created_at.elapsed().unwrap_or_default().as_secs(),
+        audio_stats,
+        performance_stats: ChannelPerformanceStats {
+            cpu_usage_percent: 0.0,
+            memory_usage_mb: 0,
+            active_streams: channel.current_users.iter()
+                .filter(|user_id| state.audio_service.is_user_speaking(user_id))
+                .count(),
+            processing_latency_us: 0,
+        },
+    }
+}
+
+/// GET /advanced/channels/:id/statistics - Statistiques détaillées d'un channel
+pub async fn get_channel_statistics(
+    Path(channel_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
+) -> Result<Json<ChannelStatistics>, (StatusCode, Json<DetailedErrorResponse>)> {
+    ensure_enabled(state.config.enable_statistics, "Statistics")?;
+    let channel = find_channel(&state, &channel_id)?;
+
+    Ok(Json(build_channel_statistics(&state, &channel)))
 }
 
 /// GET /advanced/admin/health-check - Vérification de santé complète
 pub async fn comprehensive_health_check(
-    State(_state): State<AdvancedApiState>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
+    let default_channel_ok = state.channel_service
+        .get_channel(&ChannelService::get_default_channel_id())
+        .is_ok();
+    let (total_audio_packets, _) = state.audio_service.total_traffic();
+
     let health_status = serde_json::json!({
-        "status": "healthy",
-        "timestamp": std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs(),
+        "status": if default_channel_ok { "healthy" } else { "degraded" },
+        "timestamp": unix_timestamp(),
         "services": {
             "user_service": "healthy",
-            "channel_service": "healthy",
+            "channel_service": if default_channel_ok { "healthy" } else { "missing default channel" },
             "audio_service": "healthy"
         },
         "metrics": {
-            "users_count": 25,
-            "channels_count": 5,
-            "uptime_seconds": 3600
+            "users_count": state.user_service.list_users().len(),
+            "channels_count": state.channel_service.list_all_channels().len(),
+            "websocket_connections": state.websocket_handler.connection_count(),
+            "total_audio_packets": total_audio_packets,
+            "uptime_seconds": state.started_at.elapsed().as_secs()
         }
     });
-    
+
     Ok(Json(health_status))
 }
 
 /// POST /advanced/admin/cleanup - Nettoie les ressources
 pub async fn cleanup_resources(
-    State(_state): State<AdvancedApiState>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    // Simuler le nettoyage des ressources
-    let result = serde_json::json!({
+    ensure_enabled(state.config.enable_admin_endpoints, "Admin")?;
+
+    let user_exists = |user_id: &Uuid| state.user_service.get_user(user_id).is_ok();
+    let channel_exists = |channel_id: &Uuid| state.channel_service.get_channel(channel_id).is_ok();
+
+    let stale_members = state.channel_service.remove_unknown_members(user_exists);
+    let (stale_clients, stale_buffers, stale_channel_stats) =
+        state.audio_service.cleanup_stale(user_exists, channel_exists);
+
+    tracing::info!(
+        "Cleanup: {} members, {} clients, {} buffers, {} channel stats removed",
+        stale_members, stale_clients, stale_buffers, stale_channel_stats
+    );
+
+    Ok(Json(serde_json::json!({
         "success": true,
         "message": "Resources cleaned successfully",
         "cleaned": {
-            "expired_sessions": 5,
-            "unused_buffers": 10,
-            "old_statistics": 20
+            "stale_channel_members": stale_members,
+            "stale_udp_clients": stale_clients,
+            "unused_buffers": stale_buffers,
+            "old_statistics": stale_channel_stats
         }
-    });
-    
-    Ok(Json(result))
+    })))
 }
 
-// Fonctions de support manquantes (stubs pour compilation)
+/// GET /advanced/users/:id/statistics - Statistiques d'un utilisateur
 pub async fn get_user_statistics(
-    Path(_user_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(serde_json::json!({"stub": true})))
+    ensure_enabled(state.config.enable_statistics, "Statistics")?;
+    let user = find_user(&state, &user_id)?;
+
+    Ok(Json(serde_json::json!({
+        "user_id": user.id,
+        "username": user.username,
+        "status": user.status,
+        "current_channel": user.current_channel,
+        "is_speaking": state.audio_service.is_user_speaking(&user_id),
+        "idle_seconds": state.audio_service.idle_duration(&user_id).map(|d| d.as_secs()),
+    })))
 }
 
+/// GET /advanced/users/search?q= - Recherche d'utilisateurs par nom
 pub async fn search_users(
-    State(_state): State<AdvancedApiState>,
-) -> Result<Json<Vec<User>>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(vec![]))
+    Query(query): Query<UserSearchQuery>,
+    State(state): State<AdvancedApiState>,
+) -> Result<Json<Vec<UserResponse>>, (StatusCode, Json<DetailedErrorResponse>)> {
+    let text = query.q.as_deref().map(str::trim).unwrap_or_default();
+    if text.is_empty() {
+        return Err(api_error(StatusCode::BAD_REQUEST, "MISSING_QUERY", "Query parameter 'q' is required"));
+    }
+
+    let limit = query.limit.unwrap_or(20).clamp(1, state.config.max_pagination_limit.max(1));
+    let mut users = state.user_service.search_users(text);
+    users.sort_by_key(|u| u.username.to_lowercase());
+    users.truncate(limit);
+
+    Ok(Json(users))
 }
 
+/// PUT /advanced/channels/:id - Met à jour un channel
 pub async fn update_channel(
-    Path(_channel_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
-    Json(_request): Json<ChannelUpdateRequest>,
-) -> Result<Json<Channel>, (StatusCode, Json<DetailedErrorResponse>)> {
-    let error = DetailedErrorResponse {
-        success: false,
-        error: "Not implemented".to_string(),
-        error_code: "NOT_IMPLEMENTED".to_string(),
-        details: None,
-        timestamp: std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs(),
-    };
-    Err((StatusCode::NOT_IMPLEMENTED, Json(error)))
+    Path(channel_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
+    Json(request): Json<ChannelUpdateRequest>,
+) -> Result<Json<DetailedChannelResponse>, (StatusCode, Json<DetailedErrorResponse>)> {
+    let current = find_channel(&state, &channel_id)?;
+
+    let is_system = ChannelService::is_default_channel(&channel_id) || ChannelService::is_afk_channel(&channel_id);
+    if is_system && request.is_private == Some(true) {
+        return Err(api_error(StatusCode::FORBIDDEN, "CHANNEL_PROTECTED", "System channels cannot be made private"));
+    }
+
+    let updated = state.channel_service
+        .update_channel(&channel_id, request.name, request.description, request.max_users, request.is_private)
+        .map_err(|e| match e {
+            Error::Conflict(_) => api_error(StatusCode::CONFLICT, "CHANNEL_NAME_TAKEN", e),
+            _ => api_error(StatusCode::BAD_REQUEST, "INVALID_CHANNEL_UPDATE", e),
+        })?;
+
+    // Un channel devenu privé disparaît des listes des clients
+    if updated.is_private && !current.is_private {
+        state.websocket_handler.notify_channel_deleted(channel_id).await;
+    } else if !updated.is_private && current.is_private {
+        state.websocket_handler.notify_channel_created(channel_id).await;
+    } else {
+        state.websocket_handler.notify_channel_updated(channel_id).await;
+    }
+
+    Ok(Json(updated))
 }
 
+/// DELETE /advanced/channels/:id - Supprime un channel
 pub async fn delete_channel(
-    Path(_channel_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
+    Path(channel_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<StatusCode, (StatusCode, Json<DetailedErrorResponse>)> {
+    ensure_enabled(state.config.enable_admin_endpoints, "Admin")?;
+    find_channel(&state, &channel_id)?;
+
+    if ChannelService::is_default_channel(&channel_id) || ChannelService::is_afk_channel(&channel_id) {
+        return Err(api_error(StatusCode::FORBIDDEN, "CHANNEL_PROTECTED", "System channels cannot be deleted"));
+    }
+
+    let members = state.channel_service.force_delete_channel(&channel_id)
+        .map_err(|e| api_error(StatusCode::NOT_FOUND, "CHANNEL_NOT_FOUND", e))?;
+
+    for user_id in members {
+        let _ = state.user_service.user_leave_channel(&user_id);
+        state.audio_service.remove_user_from_channel(&user_id, &channel_id);
+        let _ = state.websocket_handler.broadcast_to_all(
+            ServerMessage::UserLeft { channel_id, user_id },
+            None,
+        ).await;
+    }
+    state.websocket_handler.notify_channel_deleted(channel_id).await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// GET /advanced/channels/:id/users - Utilisateurs présents dans un channel
 pub async fn get_channel_users(
-    Path(_channel_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
-) -> Result<Json<Vec<User>>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(vec![]))
+    Path(channel_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
+) -> Result<Json<Vec<UserResponse>>, (StatusCode, Json<DetailedErrorResponse>)> {
+    let channel = find_channel(&state, &channel_id)?;
+
+    let users = channel.current_users.iter()
+        .filter_map(|user_id| state.user_service.get_user(user_id).ok())
+        .collect();
+
+    Ok(Json(users))
 }
 
+/// POST /advanced/channels/:id/users/:user_id - Place un utilisateur dans un channel
 pub async fn add_user_to_channel(
-    Path((_channel_id, _user_id)): Path<(Uuid, Uuid)>,
-    State(_state): State<AdvancedApiState>,
+    Path((channel_id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<StatusCode, (StatusCode, Json<DetailedErrorResponse>)> {
+    let channel = find_channel(&state, &channel_id)?;
+    let user = find_user(&state, &user_id)?;
+
+    if channel.current_users.contains(&user_id) {
+        return Err(api_error(StatusCode::CONFLICT, "ALREADY_IN_CHANNEL", "User is already in this channel"));
+    }
+
+    // Un utilisateur ne peut être que dans un channel à la fois
+    if let Some(previous_channel) = user.current_channel {
+        let _ = state.websocket_handler.remove_user_from_channel(user_id, previous_channel).await;
+    }
+
+    state.websocket_handler.add_user_to_channel(user_id, channel_id).await
+        .map_err(|e| api_error(StatusCode::CONFLICT, "CHANNEL_JOIN_FAILED", e))?;
+
     Ok(StatusCode::OK)
 }
 
+/// DELETE /advanced/channels/:id/users/:user_id - Retire un utilisateur d'un channel
 pub async fn remove_user_from_channel(
-    Path((_channel_id, _user_id)): Path<(Uuid, Uuid)>,
-    State(_state): State<AdvancedApiState>,
+    Path((channel_id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<StatusCode, (StatusCode, Json<DetailedErrorResponse>)> {
+    let channel = find_channel(&state, &channel_id)?;
+
+    if !channel.current_users.contains(&user_id) {
+        return Err(api_error(StatusCode::NOT_FOUND, "USER_NOT_IN_CHANNEL", "User is not in this channel"));
+    }
+
+    state.websocket_handler.remove_user_from_channel(user_id, channel_id).await
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, "CHANNEL_LEAVE_FAILED", e))?;
+
     Ok(StatusCode::OK)
 }
 
+/// GET /advanced/audio/channels/:id/routing - Configuration de routage d'un channel
 pub async fn get_channel_routing_config(
-    Path(_channel_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(serde_json::json!({"stub": true})))
+    Path(channel_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
+) -> Result<Json<ChannelRoutingConfig>, (StatusCode, Json<DetailedErrorResponse>)> {
+    Ok(Json(channel_routing_config(&state, &channel_id)?))
 }
 
+/// PUT /advanced/audio/channels/:id/routing - Met à jour le routage d'un channel
 pub async fn update_channel_routing_config(
-    Path(_channel_id): Path<Uuid>,
-    State(_state): State<AdvancedApiState>,
-    Json(_request): Json<ChannelRoutingRequest>,
-) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(serde_json::json!({"stub": true})))
+    Path(channel_id): Path<Uuid>,
+    State(state): State<AdvancedApiState>,
+    Json(request): Json<ChannelRoutingRequest>,
+) -> Result<Json<ChannelRoutingConfig>, (StatusCode, Json<DetailedErrorResponse>)> {
+    ensure_enabled(state.config.enable_audio_control, "Audio control")?;
+    let mut config = channel_routing_config(&state, &channel_id)?;
+
+    if let Some(ref quality_mode) = request.quality_mode {
+        config.quality_mode = quality_mode.parse::<QualityMode>()
+            .map_err(|e| api_error(StatusCode::BAD_REQUEST, "INVALID_QUALITY_MODE", e))?;
+    }
+    if let Some(latency_target_ms) = request.latency_target_ms {
+        if !(MIN_LATENCY_TARGET_MS..=MAX_LATENCY_TARGET_MS).contains(&latency_target_ms) {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_ROUTING_CONFIG",
+                format!("latency_target_ms must be between {} and {}", MIN_LATENCY_TARGET_MS, MAX_LATENCY_TARGET_MS),
+            ));
+        }
+        config.latency_target_ms = latency_target_ms;
+    }
+    if let Some(bitrate_kbps) = request.bitrate_kbps {
+        if !(MIN_BITRATE_KBPS..=MAX_BITRATE_KBPS).contains(&bitrate_kbps) {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "INVALID_ROUTING_CONFIG",
+                format!("bitrate_kbps must be between {} and {}", MIN_BITRATE_KBPS, MAX_BITRATE_KBPS),
+            ));
+        }
+        config.bitrate_kbps = bitrate_kbps;
+    }
+    // Conservés pour compatibilité : chaque client garde ses propres réglages
+    if let Some(enable_echo_cancellation) = request.enable_echo_cancellation {
+        config.enable_echo_cancellation = enable_echo_cancellation;
+    }
+    if let Some(enable_noise_suppression) = request.enable_noise_suppression {
+        config.enable_noise_suppression = enable_noise_suppression;
+    }
+
+    // La capacité appartient au channel : `ChannelService` la valide et l'applique
+    if let Some(max_users) = request.max_users {
+        let updated = state.channel_service
+            .update_channel(&channel_id, None, None, Some(max_users), None)
+            .map_err(|e| api_error(StatusCode::BAD_REQUEST, "INVALID_ROUTING_CONFIG", e))?;
+        config.max_users = updated.max_users;
+        state.websocket_handler.notify_channel_updated(channel_id).await;
+    }
+
+    state.audio_service.set_channel_routing_config(channel_id, config.clone());
+    let _ = state.websocket_handler.broadcast_to_channel(
+        channel_id,
+        ServerMessage::ChannelRoutingChanged { channel_id, config: config.clone() },
+        None,
+    ).await;
+
+    Ok(Json(config))
 }
 
+/// POST /advanced/audio/config/reset - Restaure la configuration audio de démarrage
 pub async fn reset_audio_config(
-    State(_state): State<AdvancedApiState>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    // Simulation pour compilation
-    Ok(Json(serde_json::json!({"success": true, "message": "Audio config reset to default"})))
+    ensure_enabled(state.config.enable_audio_control, "Audio control")?;
+
+    let config = state.audio_service.reset_config();
+    state.audio_service.set_channel_routing_defaults(ChannelRoutingConfig::default());
+    tracing::info!("Audio config reset to startup values");
+    notify_audio_config(&state).await;
+    notify_default_routing(&state).await;
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Audio config reset to default",
+        "config": config,
+        "routing_defaults": ChannelRoutingConfig::default(),
+    })))
 }
 
+/// GET /advanced/statistics/channels - Statistiques de tous les channels
 pub async fn get_all_channels_statistics(
-    State(_state): State<AdvancedApiState>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<Vec<ChannelStatistics>>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(vec![]))
+    ensure_enabled(state.config.enable_statistics, "Statistics")?;
+
+    let stats = state.channel_service.list_all_channels()
+        .iter()
+        .filter_map(|c| state.channel_service.get_channel(&c.id).ok())
+        .map(|channel| build_channel_statistics(&state, &channel))
+        .collect();
+
+    Ok(Json(stats))
 }
 
+/// GET /advanced/statistics/users - Répartition des utilisateurs
 pub async fn get_all_users_statistics(
-    State(_state): State<AdvancedApiState>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(serde_json::json!({"stub": true})))
+    ensure_enabled(state.config.enable_statistics, "Statistics")?;
+
+    let users = state.user_service.list_users();
+    let count_status = |status: UserStatus| users.iter().filter(|u| u.status == status).count();
+
+    Ok(Json(serde_json::json!({
+        "total_users": users.len(),
+        "by_status": {
+            "online": count_status(UserStatus::Online),
+            "in_channel": count_status(UserStatus::InChannel),
+            "away": count_status(UserStatus::Away),
+            "reconnecting": count_status(UserStatus::Reconnecting),
+            "offline": count_status(UserStatus::Offline),
+        },
+        "in_channel": users.iter().filter(|u| u.current_channel.is_some()).count(),
+        "speaking": users.iter().filter(|u| state.audio_service.is_user_speaking(&u.id)).count(),
+        "websocket_connections": state.websocket_handler.connection_count(),
+    })))
 }
 
+/// POST /advanced/admin/reset - Déconnecte tous les utilisateurs et réinitialise l'état
 pub async fn reset_server_state(
-    State(_state): State<AdvancedApiState>,
+    State(state): State<AdvancedApiState>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<DetailedErrorResponse>)> {
-    Ok(Json(serde_json::json!({"success": true, "message": "Server state reset"})))
+    ensure_enabled(state.config.enable_admin_endpoints, "Admin")?;
+
+    let users = state.user_service.list_users();
+    for user in &users {
+        state.websocket_handler.force_disconnect(user.id).await;
+    }
+
+    let removed_channels = state.channel_service.reset_channels();
+    for channel_id in &removed_channels {
+        state.websocket_handler.notify_channel_deleted(*channel_id).await;
+    }
+
+    state.audio_service.reset_routing();
+    state.audio_service.reset_config();
+
+    tracing::warn!(
+        "Server state reset: {} users disconnected, {} channels removed",
+        users.len(), removed_channels.len()
+    );
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "message": "Server state reset",
+        "disconnected_users": users.len(),
+        "removed_channels": removed_channels.len(),
+    })))
 }
 
 #[cfg(test)]
@@ -531,6 +1067,27 @@ mod tests {
         assert_eq!(info.current_page, 2);
     }
 
+    #[test]
+    fn test_paginate() {
+        let pagination = PaginationQuery {
+            page: Some(2),
+            limit: Some(2),
+            sort_by: None,
+            order: Some("desc".to_string()),
+        };
+
+        let response = paginate(vec![1, 2, 3, 4, 5], &pagination, 100, |a, b| a.cmp(b)).unwrap();
+        assert_eq!(response.data, vec![3, 2]);
+        assert_eq!(response.pagination.total_pages, 3);
+        assert!(response.pagination.has_next);
+        assert!(response.pagination.has_previous);
+
+        let invalid = PaginationQuery { page: None, limit: None, sort_by: None, order: Some("up".to_string()) };
+        let err = paginate(vec![1], &invalid, 100, |a: &i32, b| a.cmp(b)).unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        assert_eq!(err.1.error_code, "INVALID_SORT_ORDER");
+    }
+
     #[test]
     fn test_advanced_api_config() {
         let config = AdvancedApiConfig::default();
@@ -100,6 +100,7 @@ pub fn create_metrics_router(state: MetricsApiState) -> Router {
         .route("/alerts", get(get_alerts))
         .route("/summary", get(get_metrics_summary))
         .route("/component/:component", get(get_component_metrics))
+        .route("/metrics", get(super::prometheus::get_prometheus_metrics))
         .with_state(state)
 }
 
@@ -313,6 +314,12 @@ pub async fn get_component_metrics(
                     processing_queue_size: metrics.performance_metrics.processing_queue_size,
                     average_processing_time_us: metrics.performance_metrics.average_processing_time_us,
                     concurrent_operations: metrics.performance_metrics.concurrent_operations,
+                    thread_count: metrics.performance_metrics.thread_count,
+                    open_file_descriptors: metrics.performance_metrics.open_file_descriptors,
+                    open_sockets: metrics.performance_metrics.open_sockets,
+                    runtime_workers: metrics.performance_metrics.runtime_workers,
+                    runtime_alive_tasks: metrics.performance_metrics.runtime_alive_tasks,
+                    runtime_global_queue_depth: metrics.performance_metrics.runtime_global_queue_depth,
                 },
                 "network" => ComponentMetrics::Network {
                     packets_received_per_second: metrics.network_metrics.packets_received_per_second,
@@ -384,6 +391,12 @@ pub enum ComponentMetrics {
         processing_queue_size: usize,
         average_processing_time_us: u64,
         concurrent_operations: usize,
+        thread_count: usize,
+        open_file_descriptors: usize,
+        open_sockets: usize,
+        runtime_workers: usize,
+        runtime_alive_tasks: usize,
+        runtime_global_queue_depth: usize,
     },
     Network {
         packets_received_per_second: u64,
@@ -1,5 +1,6 @@
 pub mod metrics;
 pub mod advanced;
+pub mod prometheus;
 
 pub use metrics::{create_metrics_router, MetricsApiState, MetricsApiConfig};
 pub use advanced::{create_advanced_router, AdvancedApiState, AdvancedApiConfig};
\ No newline at end of file
@@ -0,0 +1,249 @@
+use axum::{
+    extract::State,
+    http::header,
+    response::IntoResponse,
+};
+use std::fmt::Write;
+
+use super::metrics::MetricsApiState;
+use crate::audio::metrics::MetricsSnapshot;
+
+/// Type de contenu du format texte Prometheus
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// GET /metrics - Export des métriques au format texte Prometheus
+pub async fn get_prometheus_metrics(
+    State(state): State<MetricsApiState>,
+) -> impl IntoResponse {
+    let snapshot = state.metrics_collector.read().await.export_snapshot().await;
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], render(&snapshot))
+}
+
+/// Type de métrique Prometheus
+#[derive(Debug, Clone, Copy)]
+enum MetricKind {
+    Gauge,
+    Counter,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricKind::Gauge => "gauge",
+            MetricKind::Counter => "counter",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+/// Écrivain du format d'exposition texte
+#[derive(Debug, Default)]
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    /// Déclare une famille de métriques (HELP + TYPE)
+    fn family(&mut self, name: &str, help: &str, kind: MetricKind) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind.as_str());
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, String)], value: f64) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels.iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.out, " {}", format_value(value));
+    }
+
+    /// Famille avec un seul échantillon sans label
+    fn single(&mut self, name: &str, help: &str, kind: MetricKind, value: f64) {
+        self.family(name, help, kind);
+        self.sample(name, &[], value);
+    }
+
+    /// Famille avec un échantillon par élément
+    fn per_item<T>(
+        &mut self,
+        name: &str,
+        help: &str,
+        kind: MetricKind,
+        items: &[T],
+        labels: impl Fn(&T) -> Vec<(&'static str, String)>,
+        value: impl Fn(&T) -> f64,
+    ) {
+        if items.is_empty() {
+            return;
+        }
+        self.family(name, help, kind);
+        for item in items {
+            self.sample(name, &labels(item), value(item));
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Génère l'exposition Prometheus complète à partir d'un instantané du collecteur
+pub fn render(snapshot: &MetricsSnapshot) -> String {
+    let mut exp = Exposition::default();
+    let metrics = &snapshot.metrics;
+    let performance = &metrics.performance_metrics;
+    let network = &metrics.network_metrics;
+
+    // Serveur
+    exp.single("voicechat_connected_users", "Users with a registered UDP audio address", MetricKind::Gauge, snapshot.connected_users as f64);
+    exp.single("voicechat_active_channels", "Channels with connected users", MetricKind::Gauge, metrics.audio_metrics.active_channels as f64);
+    exp.single("voicechat_uptime_seconds", "Server uptime", MetricKind::Gauge, metrics.system_health.uptime_seconds as f64);
+    exp.single("voicechat_health_score", "Overall health score (0-1)", MetricKind::Gauge, metrics.system_health.overall_health_score as f64);
+
+    // Réseau
+    exp.single("voicechat_packets_received_per_second", "UDP packets received per second", MetricKind::Gauge, network.packets_received_per_second as f64);
+    exp.single("voicechat_packets_sent_per_second", "UDP packets sent per second", MetricKind::Gauge, network.packets_sent_per_second as f64);
+    exp.single("voicechat_bytes_received_per_second", "UDP bytes received per second", MetricKind::Gauge, network.bytes_received_per_second as f64);
+    exp.single("voicechat_bytes_sent_per_second", "UDP bytes sent per second", MetricKind::Gauge, network.bytes_sent_per_second as f64);
+
+    // Processus
+    exp.single("voicechat_process_cpu_usage_percent", "Process CPU usage across all cores", MetricKind::Gauge, performance.cpu_usage_percent as f64);
+    exp.single("voicechat_process_resident_memory_bytes", "Process resident memory", MetricKind::Gauge, (performance.memory_usage_mb * 1024 * 1024) as f64);
+    exp.single("voicechat_process_threads", "Process thread count", MetricKind::Gauge, performance.thread_count as f64);
+    exp.single("voicechat_process_open_fds", "Open file descriptors", MetricKind::Gauge, performance.open_file_descriptors as f64);
+    exp.single("voicechat_process_open_sockets", "Open sockets", MetricKind::Gauge, performance.open_sockets as f64);
+    exp.single("voicechat_runtime_workers", "Tokio runtime worker threads", MetricKind::Gauge, performance.runtime_workers as f64);
+    exp.single("voicechat_runtime_alive_tasks", "Tokio tasks currently alive", MetricKind::Gauge, performance.runtime_alive_tasks as f64);
+    exp.single("voicechat_runtime_global_queue_depth", "Tasks waiting in the tokio global queue", MetricKind::Gauge, performance.runtime_global_queue_depth as f64);
+
+    // Routage par channel
+    let routing = &snapshot.channel_routing;
+    let channel = |(id, _): &(uuid::Uuid, _)| vec![("channel_id", id.to_string())];
+    exp.per_item("voicechat_channel_packets_received_total", "Audio packets received per channel", MetricKind::Counter, routing, channel, |(_, s)| s.packets_received as f64);
+    exp.per_item("voicechat_channel_packets_routed_total", "Audio packets routed per channel", MetricKind::Counter, routing, channel, |(_, s)| s.packets_routed as f64);
+    exp.per_item("voicechat_channel_packets_sent_total", "Audio packets sent per channel", MetricKind::Counter, routing, channel, |(_, s)| s.packets_sent as f64);
+    exp.per_item("voicechat_channel_bytes_received_total", "Audio bytes received per channel", MetricKind::Counter, routing, channel, |(_, s)| s.bytes_received as f64);
+    exp.per_item("voicechat_channel_bytes_sent_total", "Audio bytes sent per channel", MetricKind::Counter, routing, channel, |(_, s)| s.bytes_sent as f64);
+    exp.per_item("voicechat_channel_active_users", "Users currently speaking per channel", MetricKind::Gauge, routing, channel, |(_, s)| s.active_users as f64);
+    exp.per_item("voicechat_channel_connected_users", "Users routed in each channel", MetricKind::Gauge, routing, channel, |(_, s)| s.connected_users as f64);
+    exp.per_item("voicechat_channel_latency_ms", "Average audio latency per channel", MetricKind::Gauge, routing, channel, |(_, s)| s.average_latency_ms as f64);
+    exp.per_item("voicechat_channel_jitter_ms", "Audio jitter per channel", MetricKind::Gauge, routing, channel, |(_, s)| s.jitter_ms as f64);
+    exp.per_item("voicechat_channel_packet_loss_ratio", "Packet loss ratio per channel", MetricKind::Gauge, routing, channel, |(_, s)| s.packet_loss_rate as f64);
+
+    // Mixage de supervision par channel
+    let mixing = &snapshot.channel_mixing;
+    let channel = |(id, _): &(uuid::Uuid, _)| vec![("channel_id", id.to_string())];
+    exp.per_item("voicechat_mixer_active_voices", "Voices mixed in the last frame", MetricKind::Gauge, mixing, channel, |(_, s)| s.active_voices as f64);
+    exp.per_item("voicechat_mixer_samples_processed_total", "Samples processed by the mixer", MetricKind::Counter, mixing, channel, |(_, s)| s.total_samples_processed as f64);
+    exp.per_item("voicechat_mixer_peak_level", "Peak level of the last mixed frame", MetricKind::Gauge, mixing, channel, |(_, s)| s.peak_level as f64);
+    exp.per_item("voicechat_mixer_rms_level", "RMS level of the last mixed frame", MetricKind::Gauge, mixing, channel, |(_, s)| s.rms_level as f64);
+    exp.per_item("voicechat_mixer_clipping", "Whether clipping was detected in the last frame", MetricKind::Gauge, mixing, channel, |(_, s)| if s.clipping_detected { 1.0 } else { 0.0 });
+    exp.per_item("voicechat_mixer_processing_time_us", "Processing time of the last mixed frame", MetricKind::Gauge, mixing, channel, |(_, s)| s.processing_time_us as f64);
+
+    // Pool de threads
+    if let Some(pool) = &snapshot.thread_pool {
+        exp.single("voicechat_thread_pool_active_workers", "Audio thread pool workers", MetricKind::Gauge, pool.active_workers as f64);
+        exp.single("voicechat_thread_pool_busy_workers", "Audio thread pool workers processing a task", MetricKind::Gauge, pool.busy_workers as f64);
+        exp.single("voicechat_thread_pool_queued_tasks", "Tasks waiting in the audio thread pool", MetricKind::Gauge, pool.queued_tasks as f64);
+        exp.single("voicechat_thread_pool_peak_queue_size", "Peak audio thread pool queue size", MetricKind::Gauge, pool.peak_queue_size as f64);
+        exp.single("voicechat_thread_pool_completed_tasks_total", "Tasks completed by the audio thread pool", MetricKind::Counter, pool.completed_tasks as f64);
+        exp.single("voicechat_thread_pool_failed_tasks_total", "Tasks failed in the audio thread pool", MetricKind::Counter, pool.failed_tasks as f64);
+        exp.single("voicechat_thread_pool_average_processing_time_us", "Average task processing time", MetricKind::Gauge, pool.average_processing_time_us as f64);
+    }
+
+    // Histogramme de latence, en secondes (unité de base Prometheus)
+    let latency = &snapshot.latency;
+    let name = "voicechat_audio_latency_seconds";
+    exp.family(name, "Client to server transit of each audio packet", MetricKind::Histogram);
+    for (bound, count) in latency.bounds_ms.iter().zip(latency.cumulative_counts()) {
+        exp.sample(&format!("{}_bucket", name), &[("le", format_value(*bound / 1000.0))], count as f64);
+    }
+    exp.sample(&format!("{}_bucket", name), &[("le", "+Inf".to_string())], latency.count as f64);
+    exp.sample(&format!("{}_sum", name), &[], latency.sum_ms / 1000.0);
+    exp.sample(&format!("{}_count", name), &[], latency.count as f64);
+
+    exp.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::metrics::LatencyHistogram;
+    use crate::audio::mixer::MixingStats;
+    use crate::audio::performance::ThreadPoolStats;
+    use crate::audio::{MetricsCollector, MetricsConfig, RoutingStats};
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_render_exposition() {
+        let collector = MetricsCollector::new(MetricsConfig::default());
+        let mut snapshot = collector.export_snapshot().await;
+
+        let channel_id = Uuid::new_v4();
+        snapshot.channel_routing.push((channel_id, RoutingStats {
+            packets_received: 42,
+            packets_routed: 40,
+            packets_sent: 80,
+            bytes_received: 4200,
+            bytes_sent: 8000,
+            active_users: 1,
+            connected_users: 2,
+            average_latency_ms: 35.0,
+            packet_loss_rate: 0.01,
+            jitter_ms: 2.5,
+            user_latency: Default::default(),
+            created_at: std::time::Instant::now(),
+        }));
+        snapshot.channel_mixing.push((channel_id, MixingStats {
+            active_voices: 2,
+            peak_level: 12000.0,
+            rms_level: 3000.0,
+            clipping_detected: true,
+            ..Default::default()
+        }));
+        snapshot.thread_pool = Some(ThreadPoolStats {
+            active_workers: 4,
+            busy_workers: 1,
+            queued_tasks: 3,
+            completed_tasks: 120,
+            failed_tasks: 0,
+            average_processing_time_us: 85,
+            peak_queue_size: 5,
+        });
+        snapshot.latency = LatencyHistogram::new(&[10.0, 50.0]);
+        snapshot.latency.observe(35.0);
+
+        let output = render(&snapshot);
+        assert!(output.contains("# TYPE voicechat_channel_packets_received_total counter"));
+        assert!(output.contains(&format!("voicechat_channel_packets_received_total{{channel_id=\"{}\"}} 42", channel_id)));
+        let channel = format!("{{channel_id=\"{}\"}}", channel_id);
+        assert!(output.contains(&format!("voicechat_mixer_active_voices{} 2", channel)));
+        assert!(output.contains(&format!("voicechat_mixer_peak_level{} 12000", channel)));
+        assert!(output.contains(&format!("voicechat_mixer_rms_level{} 3000", channel)));
+        assert!(output.contains(&format!("voicechat_mixer_clipping{} 1", channel)));
+        assert!(output.contains("voicechat_thread_pool_active_workers 4"));
+        assert!(output.contains("voicechat_thread_pool_busy_workers 1"));
+        assert!(output.contains("voicechat_thread_pool_queued_tasks 3"));
+        assert!(output.contains("# TYPE voicechat_thread_pool_completed_tasks_total counter"));
+        assert!(output.contains("voicechat_audio_latency_seconds_bucket{le=\"0.01\"} 0"));
+        assert!(output.contains("voicechat_audio_latency_seconds_bucket{le=\"0.05\"} 1"));
+        assert!(output.contains("voicechat_audio_latency_seconds_bucket{le=\"+Inf\"} 1"));
+        assert!(output.contains("voicechat_audio_latency_seconds_sum 0.035"));
+        assert!(output.contains("voicechat_audio_latency_seconds_count 1"));
+        assert!(!output.contains("voicechat_audio_latency_ms"));
+    }
+}
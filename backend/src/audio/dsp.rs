@@ -0,0 +1,439 @@
+use std::collections::VecDeque;
+
+/// Niveau minimal considéré, pour éviter log10(0)
+const MIN_LEVEL: f32 = 1e-9;
+
+/// Conversion décibels (dBFS) vers amplitude linéaire
+pub fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Conversion amplitude linéaire vers décibels (dBFS)
+pub fn linear_to_db(level: f32) -> f32 {
+    20.0 * level.max(MIN_LEVEL).log10()
+}
+
+/// Coefficient de lissage exponentiel pour une constante de temps donnée
+pub fn time_coefficient(time_ms: f32, sample_rate: u32) -> f32 {
+    let samples = time_ms * 0.001 * sample_rate as f32;
+    if samples <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / samples).exp()
+    }
+}
+
+/// Filtre biquad (forme directe transposée II)
+#[derive(Debug, Clone)]
+pub struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    /// Passe-haut du 2e ordre (RBJ Audio EQ Cookbook)
+    pub fn high_pass(sample_rate: u32, cutoff_hz: f32, q: f32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate as f32;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / (2.0 * q);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos_w0) / 2.0 / a0,
+            b1: -(1.0 + cos_w0) / a0,
+            b2: (1.0 + cos_w0) / 2.0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let output = self.b0 * input + self.z1;
+        self.z1 = self.b1 * input - self.a1 * output + self.z2;
+        self.z2 = self.b2 * input - self.a2 * output;
+        output
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+}
+
+/// Suiveur d'enveloppe crête avec attaque et relâchement
+#[derive(Debug, Clone)]
+pub struct EnvelopeFollower {
+    attack: f32,
+    release: f32,
+    envelope: f32,
+}
+
+impl EnvelopeFollower {
+    pub fn new(sample_rate: u32, attack_ms: f32, release_ms: f32) -> Self {
+        Self {
+            attack: time_coefficient(attack_ms, sample_rate),
+            release: time_coefficient(release_ms, sample_rate),
+            envelope: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let level = input.abs();
+        let coeff = if level > self.envelope { self.attack } else { self.release };
+        self.envelope = level + (self.envelope - level) * coeff;
+        self.envelope
+    }
+
+    pub fn value(&self) -> f32 {
+        self.envelope
+    }
+}
+
+/// Noise gate suivant l'enveloppe, avec hystérésis et temps de maintien
+#[derive(Debug, Clone)]
+pub struct NoiseGate {
+    open_threshold: f32,
+    close_threshold: f32,
+    floor_gain: f32,
+    envelope: EnvelopeFollower,
+    attack: f32,
+    release: f32,
+    hold_samples: u32,
+    hold_counter: u32,
+    open: bool,
+    gain: f32,
+}
+
+impl NoiseGate {
+    /// `threshold_db` ouvre le gate, qui se referme `hysteresis_db` plus bas.
+    /// `floor_gain` est le gain appliqué gate fermé (0.0 = silence complet).
+    pub fn new(sample_rate: u32, threshold_db: f32, hysteresis_db: f32, floor_gain: f32) -> Self {
+        Self {
+            open_threshold: db_to_linear(threshold_db),
+            close_threshold: db_to_linear(threshold_db - hysteresis_db.max(0.0)),
+            floor_gain: floor_gain.clamp(0.0, 1.0),
+            envelope: EnvelopeFollower::new(sample_rate, 1.0, 50.0),
+            attack: time_coefficient(2.0, sample_rate),
+            release: time_coefficient(80.0, sample_rate),
+            hold_samples: (sample_rate as f32 * 0.05) as u32,
+            hold_counter: 0,
+            open: false,
+            gain: floor_gain.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Met à jour les seuils sans perdre l'état du gate
+    pub fn set_thresholds(&mut self, threshold_db: f32, hysteresis_db: f32, floor_gain: f32) {
+        self.open_threshold = db_to_linear(threshold_db);
+        self.close_threshold = db_to_linear(threshold_db - hysteresis_db.max(0.0));
+        self.floor_gain = floor_gain.clamp(0.0, 1.0);
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let envelope = self.envelope.process(input);
+
+        if self.open {
+            if envelope >= self.close_threshold {
+                self.hold_counter = self.hold_samples;
+            } else if self.hold_counter > 0 {
+                self.hold_counter -= 1;
+            } else {
+                self.open = false;
+            }
+        } else if envelope >= self.open_threshold {
+            self.open = true;
+            self.hold_counter = self.hold_samples;
+        }
+
+        let target = if self.open { 1.0 } else { self.floor_gain };
+        let coeff = if target > self.gain { self.attack } else { self.release };
+        self.gain = target + (self.gain - target) * coeff;
+        input * self.gain
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+/// Compresseur à anticipation (look-ahead) ; avec un ratio infini il sert de limiteur
+#[derive(Debug, Clone)]
+pub struct Compressor {
+    threshold_db: f32,
+    ratio: f32,
+    attack: f32,
+    release: f32,
+    /// Détecteur crête (attaque instantanée) sur le signal non retardé
+    detector: EnvelopeFollower,
+    /// Réduction de gain courante en dB (toujours <= 0)
+    gain_reduction_db: f32,
+    delay_line: VecDeque<f32>,
+    lookahead_samples: usize,
+}
+
+impl Compressor {
+    pub fn new(
+        sample_rate: u32,
+        threshold_db: f32,
+        ratio: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        lookahead_ms: f32,
+    ) -> Self {
+        let lookahead_samples = (lookahead_ms * 0.001 * sample_rate as f32).round() as usize;
+        Self {
+            threshold_db,
+            ratio: ratio.max(1.0),
+            attack: time_coefficient(attack_ms, sample_rate),
+            release: time_coefficient(release_ms, sample_rate),
+            detector: EnvelopeFollower::new(sample_rate, 0.0, release_ms),
+            gain_reduction_db: 0.0,
+            delay_line: VecDeque::from(vec![0.0; lookahead_samples]),
+            lookahead_samples,
+        }
+    }
+
+    /// Limiteur crête : ratio infini et attaque calée sur l'anticipation
+    pub fn limiter(sample_rate: u32, ceiling_db: f32) -> Self {
+        let lookahead_ms = 1.5;
+        Self::new(sample_rate, ceiling_db, f32::INFINITY, lookahead_ms / 5.0, 60.0, lookahead_ms)
+    }
+
+    pub fn set_parameters(&mut self, threshold_db: f32, ratio: f32) {
+        self.threshold_db = threshold_db;
+        self.ratio = ratio.max(1.0);
+    }
+
+    /// Latence introduite par l'anticipation, en samples
+    pub fn latency(&self) -> usize {
+        self.lookahead_samples
+    }
+
+    pub fn gain_reduction_db(&self) -> f32 {
+        self.gain_reduction_db
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        // Le détecteur voit le signal avant qu'il ne sorte de la ligne de retard
+        let level = self.detector.process(input);
+        let overshoot = linear_to_db(level) - self.threshold_db;
+        let target = if overshoot > 0.0 {
+            overshoot * (1.0 / self.ratio - 1.0)
+        } else {
+            0.0
+        };
+
+        let coeff = if target < self.gain_reduction_db { self.attack } else { self.release };
+        self.gain_reduction_db = target + (self.gain_reduction_db - target) * coeff;
+
+        self.delay_line.push_back(input);
+        let delayed = self.delay_line.pop_front().unwrap_or(input);
+        delayed * db_to_linear(self.gain_reduction_db)
+    }
+}
+
+/// Contrôle automatique de gain avec lissage du gain
+#[derive(Debug, Clone)]
+pub struct AutomaticGainControl {
+    target_rms: f32,
+    silence_threshold: f32,
+    min_gain: f32,
+    max_gain: f32,
+    /// Moyenne glissante du carré du signal
+    mean_square: f32,
+    /// Enveloppe rapide servant à détecter la présence de signal
+    activity: EnvelopeFollower,
+    rms_coeff: f32,
+    attack: f32,
+    release: f32,
+    gain: f32,
+}
+
+impl AutomaticGainControl {
+    pub fn new(sample_rate: u32, target_db: f32, min_gain: f32, max_gain: f32) -> Self {
+        Self {
+            target_rms: db_to_linear(target_db),
+            silence_threshold: db_to_linear(-50.0),
+            min_gain,
+            max_gain,
+            mean_square: 0.0,
+            activity: EnvelopeFollower::new(sample_rate, 1.0, 20.0),
+            rms_coeff: time_coefficient(100.0, sample_rate),
+            // Baisse du gain rapide, remontée lente pour éviter le pompage
+            attack: time_coefficient(50.0, sample_rate),
+            release: time_coefficient(800.0, sample_rate),
+            gain: 1.0,
+        }
+    }
+
+    pub fn set_target(&mut self, target_db: f32) {
+        self.target_rms = db_to_linear(target_db);
+    }
+
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        self.mean_square = input * input + (self.mean_square - input * input) * self.rms_coeff;
+        let rms = self.mean_square.sqrt();
+        let envelope = self.activity.process(input);
+
+        // Pendant les silences (ou un signal trop faible pour atteindre la cible),
+        // le gain est figé pour ne pas amplifier le bruit de fond
+        let activity_threshold = self.silence_threshold.max(self.target_rms / self.max_gain);
+        if envelope > activity_threshold {
+            let desired = (self.target_rms / rms).clamp(self.min_gain, self.max_gain);
+            let coeff = if desired < self.gain { self.attack } else { self.release };
+            self.gain = desired + (self.gain - desired) * coeff;
+        }
+
+        input * self.gain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    fn sine(frequency: f32, amplitude: f32, seconds: f32) -> Vec<f32> {
+        let count = (SAMPLE_RATE as f32 * seconds) as usize;
+        (0..count)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0, |max, s| max.max(s.abs()))
+    }
+
+    /// Traite un signal et ne garde que la seconde moitié (régime établi)
+    fn steady_state(input: &[f32], mut process: impl FnMut(f32) -> f32) -> Vec<f32> {
+        let output: Vec<f32> = input.iter().map(|&s| process(s)).collect();
+        output[output.len() / 2..].to_vec()
+    }
+
+    #[test]
+    fn test_high_pass_removes_rumble() {
+        let mut filter = Biquad::high_pass(SAMPLE_RATE, 100.0, std::f32::consts::FRAC_1_SQRT_2);
+        let rumble = sine(50.0, 0.5, 1.0);
+        let filtered = steady_state(&rumble, |s| filter.process(s));
+        assert!(rms(&filtered) < rms(&rumble) * 0.3);
+
+        filter.reset();
+        let voice = sine(1000.0, 0.5, 1.0);
+        let filtered = steady_state(&voice, |s| filter.process(s));
+        assert!((rms(&filtered) / rms(&voice) - 1.0).abs() < 0.02);
+
+        // Une composante continue disparaît
+        filter.reset();
+        let dc = vec![0.3; SAMPLE_RATE as usize];
+        let filtered = steady_state(&dc, |s| filter.process(s));
+        assert!(peak(&filtered) < 0.001);
+    }
+
+    #[test]
+    fn test_noise_gate_hysteresis() {
+        // Ouverture à -30 dBFS, fermeture à -40 dBFS
+        let mut gate = NoiseGate::new(SAMPLE_RATE, -30.0, 10.0, 0.0);
+
+        // Bruit à -35 dBFS : entre les deux seuils, le gate reste fermé
+        let noise = sine(300.0, db_to_linear(-35.0), 0.5);
+        let output = steady_state(&noise, |s| gate.process(s));
+        assert!(!gate.is_open());
+        assert!(peak(&output) < 1e-3);
+
+        // La voix ouvre le gate et passe sans altération
+        let voice = sine(300.0, db_to_linear(-10.0), 0.5);
+        let output = steady_state(&voice, |s| gate.process(s));
+        assert!(gate.is_open());
+        assert!((rms(&output) / rms(&voice) - 1.0).abs() < 0.01);
+
+        // Le même niveau de -35 dBFS ne referme pas le gate une fois ouvert
+        let output = steady_state(&noise, |s| gate.process(s));
+        assert!(gate.is_open());
+        assert!((rms(&output) / rms(&noise[noise.len() / 2..]) - 1.0).abs() < 0.01);
+
+        // Sous le seuil de fermeture, le gate se referme après le maintien
+        let quiet = sine(300.0, db_to_linear(-50.0), 0.5);
+        steady_state(&quiet, |s| gate.process(s));
+        assert!(!gate.is_open());
+    }
+
+    #[test]
+    fn test_compressor_ratio() {
+        let mut compressor = Compressor::new(SAMPLE_RATE, -12.0, 4.0, 5.0, 200.0, 2.0);
+        let loud = sine(440.0, 1.0, 1.0);
+        let output = steady_state(&loud, |s| compressor.process(s));
+
+        // 12 dB au-dessus du seuil avec un ratio 4:1 -> 3 dB au-dessus, soit -9 dBFS
+        let output_db = linear_to_db(peak(&output));
+        assert!((output_db + 9.0).abs() < 0.5, "output peak {} dBFS", output_db);
+
+        // Sous le seuil, le signal n'est pas modifié (au retard près)
+        let mut compressor = Compressor::new(SAMPLE_RATE, -12.0, 4.0, 5.0, 200.0, 2.0);
+        let quiet = sine(440.0, db_to_linear(-20.0), 0.5);
+        let output: Vec<f32> = quiet.iter().map(|&s| compressor.process(s)).collect();
+        let latency = compressor.latency();
+        for (out, input) in output[latency..].iter().zip(&quiet) {
+            assert!((out - input).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_limiter_catches_transients() {
+        let ceiling = db_to_linear(-1.0);
+        let mut limiter = Compressor::limiter(SAMPLE_RATE, -1.0);
+
+        // Silence puis attaque brutale à pleine échelle
+        let mut input = vec![0.0; 4800];
+        input.extend(sine(1000.0, 1.0, 0.2));
+        let output: Vec<f32> = input.iter().map(|&s| limiter.process(s)).collect();
+
+        assert!(peak(&output) <= ceiling * 1.01, "peak {} above ceiling {}", peak(&output), ceiling);
+        assert!(peak(&output) > ceiling * 0.9);
+    }
+
+    #[test]
+    fn test_agc_converges_smoothly() {
+        let mut agc = AutomaticGainControl::new(SAMPLE_RATE, -18.0, 0.1, 4.0);
+        let quiet = sine(300.0, 0.1, 4.0);
+        let mut previous_gain = agc.gain();
+        let mut max_step: f32 = 0.0;
+
+        let output: Vec<f32> = quiet.iter()
+            .map(|&s| {
+                let out = agc.process(s);
+                max_step = max_step.max((agc.gain() - previous_gain).abs());
+                previous_gain = agc.gain();
+                out
+            })
+            .collect();
+
+        let target = db_to_linear(-18.0);
+        let settled = &output[output.len() * 3 / 4..];
+        assert!((rms(settled) / target - 1.0).abs() < 0.1, "rms {} target {}", rms(settled), target);
+        // Pas de saut de gain audible d'un sample à l'autre
+        assert!(max_step < 0.001);
+
+        // Le silence ne fait pas monter le gain (hors courte traîne de l'enveloppe)
+        let gain_before = agc.gain();
+        for _ in 0..SAMPLE_RATE {
+            agc.process(0.0);
+        }
+        assert!(agc.gain() < gain_before * 1.05);
+    }
+}
@@ -1,15 +1,18 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use serde::{Serialize, Deserialize};
 
+use uuid::Uuid;
+
 use crate::audio::{
     AudioThreadPool, AudioMixer,
-    AudioUdpServer, AudioRouter,
+    AudioRouter, RoutingStats,
+    mixer::MixingStats,
     performance::ThreadPoolStats,
-    mixer::MixerGlobalStats,
-    server::ServerStats,
+    ProcessSnapshot, SystemMonitor,
 };
+use crate::networking::{TransportStats, TransportSnapshot};
 
 /// Configuration du système de métriques
 #[derive(Debug, Clone)]
@@ -86,6 +89,12 @@ pub struct PerformanceMetrics {
     pub processing_queue_size: usize,
     pub average_processing_time_us: u64,
     pub concurrent_operations: usize,
+    pub thread_count: usize,
+    pub open_file_descriptors: usize,
+    pub open_sockets: usize,
+    pub runtime_workers: usize,
+    pub runtime_alive_tasks: usize,
+    pub runtime_global_queue_depth: usize,
 }
 
 /// Métriques réseau
@@ -108,6 +117,8 @@ pub struct SystemHealthMetrics {
     pub uptime_seconds: u64,
     pub error_rate_percent: f32,
     pub service_availability_percent: f32,
+    /// Part de la mémoire de la machine occupée par le processus
+    pub memory_usage_percent: f32,
 }
 
 /// Information d'alerte
@@ -129,9 +140,10 @@ pub enum AlertSeverity {
     Critical,
 }
 
-/// Statistiques simplifiées du mixer
+/// Statistiques simplifiées du mixage de supervision
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MixerStats {
+    /// Channels mixés récemment
     pub active_mixers: usize,
     pub total_samples_processed: u64,
     pub compression_ratio: f32,
@@ -156,6 +168,84 @@ pub struct RoutingMetrics {
     pub route_quality_average: f32,
 }
 
+/// Bornes par défaut de l'histogramme de latence (ms)
+const DEFAULT_LATENCY_BUCKETS_MS: [f64; 9] = [5.0, 10.0, 20.0, 50.0, 100.0, 150.0, 200.0, 500.0, 1000.0];
+
+/// Histogramme de latence audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// Bornes supérieures des buckets en millisecondes
+    pub bounds_ms: Vec<f64>,
+    /// Observations par bucket (non cumulées, la dernière case compte les valeurs hors bornes)
+    pub counts: Vec<u64>,
+    pub sum_ms: f64,
+    pub count: u64,
+}
+
+impl LatencyHistogram {
+    pub fn new(bounds_ms: &[f64]) -> Self {
+        Self {
+            bounds_ms: bounds_ms.to_vec(),
+            counts: vec![0; bounds_ms.len() + 1],
+            sum_ms: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Enregistre une observation
+    pub fn observe(&mut self, latency_ms: f64) {
+        let bucket = self.bounds_ms.iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(self.bounds_ms.len());
+        self.counts[bucket] += 1;
+        self.sum_ms += latency_ms;
+        self.count += 1;
+    }
+
+    /// Compteurs cumulés par borne, au format attendu par Prometheus (hors +Inf)
+    pub fn cumulative_counts(&self) -> Vec<u64> {
+        self.counts[..self.bounds_ms.len()].iter()
+            .scan(0, |total, count| {
+                *total += count;
+                Some(*total)
+            })
+            .collect()
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(&DEFAULT_LATENCY_BUCKETS_MS)
+    }
+}
+
+/// Vue complète des métriques pour l'export (Prometheus)
+#[derive(Debug, Clone)]
+pub struct MetricsSnapshot {
+    pub metrics: RealTimeMetrics,
+    pub connected_users: usize,
+    pub channel_routing: Vec<(Uuid, RoutingStats)>,
+    pub channel_mixing: Vec<(Uuid, MixingStats)>,
+    pub thread_pool: Option<ThreadPoolStats>,
+    pub latency: LatencyHistogram,
+}
+
+/// Composants surveillés par le collecteur
+#[derive(Debug, Clone, Default)]
+struct MonitoredComponents {
+    thread_pool: Option<Arc<AudioThreadPool>>,
+    transport: Option<Arc<TransportStats>>,
+    mixer: Option<Arc<RwLock<AudioMixer>>>,
+    router: Option<Arc<AudioRouter>>,
+}
+
+/// Derniers compteurs relevés, pour le calcul des débits
+#[derive(Debug, Clone, Copy, Default)]
+struct CounterState {
+    transport: TransportSnapshot,
+    packets_routed: u64,
+}
+
 /// Collecteur de métriques principal
 #[derive(Debug)]
 pub struct MetricsCollector {
@@ -164,19 +254,15 @@ pub struct MetricsCollector {
     metrics_history: Arc<RwLock<Vec<RealTimeMetrics>>>,
     alerts: Arc<RwLock<Vec<AlertInfo>>>,
     start_time: Instant,
+    system_monitor: Arc<SystemMonitor>,
+    latency_histogram: Arc<Mutex<LatencyHistogram>>,
     
     // Références aux composants surveillés
-    thread_pool: Option<Arc<AudioThreadPool>>,
-    udp_server: Option<Arc<RwLock<AudioUdpServer>>>,
-    mixer: Option<Arc<RwLock<AudioMixer>>>,
-    router: Option<Arc<RwLock<AudioRouter>>>,
+    components: MonitoredComponents,
     
     // Compteurs pour les dérivées
     last_collection: Instant,
-    last_packets_received: u64,
-    last_packets_sent: u64,
-    last_bytes_received: u64,
-    last_bytes_sent: u64,
+    last_counters: CounterState,
 }
 
 impl MetricsCollector {
@@ -191,15 +277,11 @@ impl MetricsCollector {
             metrics_history: Arc::new(RwLock::new(Vec::new())),
             alerts: Arc::new(RwLock::new(Vec::new())),
             start_time: now,
-            thread_pool: None,
-            udp_server: None,
-            mixer: None,
-            router: None,
+            system_monitor: Arc::new(SystemMonitor::new()),
+            latency_histogram: Arc::new(Mutex::new(LatencyHistogram::default())),
+            components: MonitoredComponents::default(),
             last_collection: now,
-            last_packets_received: 0,
-            last_packets_sent: 0,
-            last_bytes_received: 0,
-            last_bytes_sent: 0,
+            last_counters: CounterState::default(),
         }
     }
 
@@ -207,14 +289,19 @@ impl MetricsCollector {
     pub fn register_components(
         &mut self,
         thread_pool: Option<Arc<AudioThreadPool>>,
-        udp_server: Option<Arc<RwLock<AudioUdpServer>>>,
+        transport: Option<Arc<TransportStats>>,
         mixer: Option<Arc<RwLock<AudioMixer>>>,
-        router: Option<Arc<RwLock<AudioRouter>>>,
+        router: Option<Arc<AudioRouter>>,
     ) {
-        self.thread_pool = thread_pool;
-        self.udp_server = udp_server;
-        self.mixer = mixer;
-        self.router = router;
+        // Partir des compteurs actuels pour que le premier débit calculé soit juste
+        self.last_counters = CounterState {
+            transport: transport.as_ref().map(|t| t.snapshot()).unwrap_or_default(),
+            packets_routed: router.as_ref().map(|r| Self::total_packets_routed(r)).unwrap_or(0),
+        };
+        if let Some(router) = &router {
+            router.set_latency_histogram(self.latency_histogram.clone());
+        }
+        self.components = MonitoredComponents { thread_pool, transport, mixer, router };
     }
 
     /// Lance la collection automatique de métriques
@@ -224,17 +311,12 @@ impl MetricsCollector {
         let alerts = self.alerts.clone();
         let config = self.config.clone();
         
-        let thread_pool = self.thread_pool.clone();
-        let udp_server = self.udp_server.clone();
-        let mixer = self.mixer.clone();
-        let router = self.router.clone();
+        let components = self.components.clone();
+        let system_monitor = self.system_monitor.clone();
         
         let start_time = self.start_time;
         let mut last_collection = self.last_collection;
-        let mut last_packets_received = self.last_packets_received;
-        let mut last_packets_sent = self.last_packets_sent;
-        let mut last_bytes_received = self.last_bytes_received;
-        let mut last_bytes_sent = self.last_bytes_sent;
+        let mut last_counters = self.last_counters;
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(config.collection_interval);
@@ -247,16 +329,11 @@ impl MetricsCollector {
                 
                 // Collecter les métriques
                 let metrics = Self::collect_all_metrics(
-                    &thread_pool,
-                    &udp_server,
-                    &mixer,
-                    &router,
+                    &components,
+                    &system_monitor,
                     start_time,
                     time_delta,
-                    &mut last_packets_received,
-                    &mut last_packets_sent,
-                    &mut last_bytes_received,
-                    &mut last_bytes_sent,
+                    &mut last_counters,
                 ).await;
 
                 // Détecter les alertes
@@ -277,7 +354,8 @@ impl MetricsCollector {
                     let cutoff_time = SystemTime::now()
                         .duration_since(UNIX_EPOCH)
                         .unwrap_or_default()
-                        .as_secs() - config.history_retention.as_secs();
+                        .as_secs()
+                        .saturating_sub(config.history_retention.as_secs());
                     
                     history.retain(|m| m.timestamp >= cutoff_time);
                 }
@@ -291,7 +369,8 @@ impl MetricsCollector {
                     let cutoff_time = SystemTime::now()
                         .duration_since(UNIX_EPOCH)
                         .unwrap_or_default()
-                        .as_secs() - 3600; // 1 heure
+                        .as_secs()
+                        .saturating_sub(3600); // 1 heure
                     
                     alerts_guard.retain(|a| a.timestamp >= cutoff_time);
                 }
@@ -303,16 +382,11 @@ impl MetricsCollector {
 
     /// Collecte toutes les métriques des composants
     async fn collect_all_metrics(
-        thread_pool: &Option<Arc<AudioThreadPool>>,
-        udp_server: &Option<Arc<RwLock<AudioUdpServer>>>,
-        mixer: &Option<Arc<RwLock<AudioMixer>>>,
-        router: &Option<Arc<RwLock<AudioRouter>>>,
+        components: &MonitoredComponents,
+        system_monitor: &SystemMonitor,
         start_time: Instant,
         time_delta: f32,
-        last_packets_received: &mut u64,
-        last_packets_sent: &mut u64,
-        last_bytes_received: &mut u64,
-        last_bytes_sent: &mut u64,
+        last_counters: &mut CounterState,
     ) -> RealTimeMetrics {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -320,26 +394,31 @@ impl MetricsCollector {
             .as_secs();
 
         // Métriques audio
-        let audio_metrics = Self::collect_audio_metrics(mixer, router).await;
+        let audio_metrics = Self::collect_audio_metrics(
+            &components.mixer,
+            &components.router,
+            time_delta,
+            &mut last_counters.packets_routed,
+        ).await;
         
-        // Métriques performance
-        let performance_metrics = Self::collect_performance_metrics(thread_pool).await;
+        // Métriques performance (un seul relevé par collecte : il avance la référence CPU)
+        let process = system_monitor.snapshot();
+        let performance_metrics = Self::collect_performance_metrics(&process, &components.thread_pool, system_monitor).await;
         
         // Métriques réseau
         let network_metrics = Self::collect_network_metrics(
-            udp_server,
+            &components.transport,
+            &components.router,
             time_delta,
-            last_packets_received,
-            last_packets_sent,
-            last_bytes_received,
-            last_bytes_sent,
-        ).await;
+            &mut last_counters.transport,
+        );
         
         // Santé système
         let system_health = Self::calculate_system_health(
             &audio_metrics,
             &performance_metrics,
             &network_metrics,
+            process.system_memory_total_mb,
             start_time,
         );
 
@@ -352,19 +431,30 @@ impl MetricsCollector {
         }
     }
 
+    /// Cumul des packets routés sur tous les channels
+    fn total_packets_routed(router: &AudioRouter) -> u64 {
+        router.all_channel_stats().iter().map(|(_, stats)| stats.packets_routed).sum()
+    }
+
     /// Collecte les métriques audio
     async fn collect_audio_metrics(
         mixer: &Option<Arc<RwLock<AudioMixer>>>,
-        router: &Option<Arc<RwLock<AudioRouter>>>,
+        router: &Option<Arc<AudioRouter>>,
+        time_delta: f32,
+        last_packets_routed: &mut u64,
     ) -> AudioSystemMetrics {
         let mixer_stats = if let Some(mixer) = mixer {
             let mixer_guard = mixer.read().await;
-            let global_stats = mixer_guard.global_stats();
+            let (active_mixers, total_samples_processed, peak_level, ratio_sum) = mixer_guard.all_stats()
+                .fold((0, 0, 0.0_f32, 0.0_f32), |(mixers, samples, peak, ratios), (channel_id, stats)| {
+                    let ratio = mixer_guard.get_channel_config(channel_id).compression_ratio;
+                    (mixers + 1, samples + stats.total_samples_processed, peak.max(stats.peak_level), ratios + ratio)
+                });
             MixerStats {
-                active_mixers: global_stats.active_channels,
-                total_samples_processed: 0, // Approximation
-                compression_ratio: 4.0, // Valeur par défaut
-                peak_level: 0.0,
+                active_mixers,
+                total_samples_processed,
+                compression_ratio: if active_mixers > 0 { ratio_sum / active_mixers as f32 } else { 1.0 },
+                peak_level,
             }
         } else {
             MixerStats {
@@ -375,103 +465,134 @@ impl MetricsCollector {
             }
         };
 
-        let routing_stats = if let Some(router) = router {
-            let router_guard = router.read().await;
-            let (_total_clients, total_channels, _total_buffers) = router_guard.get_global_stats();
-            RoutingMetrics {
-                active_routes: total_channels,
-                routing_decisions_per_second: 0, // Calculé ailleurs
-                adaptive_adjustments: 0, // Pas encore disponible
-                route_quality_average: 1.0, // Valeur par défaut
+        let Some(router) = router else {
+            return AudioSystemMetrics {
+                active_channels: mixer_stats.active_mixers,
+                active_users: 0,
+                total_audio_streams: 0,
+                average_latency_ms: 0.0,
+                packet_loss_percentage: 0.0,
+                audio_quality_score: 1.0,
+                mixer_stats,
+                buffer_stats: BufferStats {
+                    total_buffers: 0,
+                    average_fill_level: 0.0,
+                    underruns: 0,
+                    overruns: 0,
+                },
+                routing_stats: RoutingMetrics {
+                    active_routes: 0,
+                    routing_decisions_per_second: 0,
+                    adaptive_adjustments: 0,
+                    route_quality_average: 1.0,
+                },
+            };
+        };
+
+        let channel_stats = router.all_channel_stats();
+        let active: Vec<&RoutingStats> = channel_stats.iter()
+            .map(|(_, stats)| stats)
+            .filter(|stats| stats.connected_users > 0)
+            .collect();
+
+        // Moyennes pondérées par le trafic reçu
+        let total_received: u64 = active.iter().map(|stats| stats.packets_received).sum();
+        let weighted = |value: fn(&RoutingStats) -> f32| -> f32 {
+            if total_received == 0 {
+                return 0.0;
             }
+            active.iter()
+                .map(|stats| value(stats) * stats.packets_received as f32)
+                .sum::<f32>() / total_received as f32
+        };
+        let average_latency_ms = weighted(|stats| stats.average_latency_ms);
+        let packet_loss_percentage = weighted(|stats| stats.packet_loss_rate) * 100.0;
+
+        let quality_scores: Vec<f32> = channel_stats.iter()
+            .filter_map(|(channel_id, _)| router.analyze_channel_performance(channel_id))
+            .map(|report| report.quality_score)
+            .collect();
+        let route_quality_average = if quality_scores.is_empty() {
+            1.0
         } else {
-            RoutingMetrics {
-                active_routes: 0,
-                routing_decisions_per_second: 0,
-                adaptive_adjustments: 0,
-                route_quality_average: 1.0,
-            }
+            quality_scores.iter().sum::<f32>() / quality_scores.len() as f32
         };
 
+        let packets_routed = channel_stats.iter().map(|(_, stats)| stats.packets_routed).sum::<u64>();
+        let routed_delta = packets_routed.saturating_sub(*last_packets_routed);
+        *last_packets_routed = packets_routed;
+
+        let (total_clients, _total_channels, _total_buffers) = router.get_global_stats();
+        let (total_buffers, average_fill_level, dropped_packets) = router.buffer_stats();
+
         AudioSystemMetrics {
-            active_channels: mixer_stats.active_mixers,
-            active_users: 0, // À calculer depuis les connexions
-            total_audio_streams: mixer_stats.active_mixers,
-            average_latency_ms: 50.0, // Approximation
-            packet_loss_percentage: 0.1,
-            audio_quality_score: 0.95,
+            active_channels: active.len(),
+            active_users: total_clients,
+            total_audio_streams: router.speaking_count(),
+            average_latency_ms,
+            packet_loss_percentage,
+            audio_quality_score: route_quality_average,
             mixer_stats,
             buffer_stats: BufferStats {
-                total_buffers: 10,
-                average_fill_level: 0.5,
+                total_buffers,
+                average_fill_level,
                 underruns: 0,
-                overruns: 0,
+                overruns: dropped_packets,
+            },
+            routing_stats: RoutingMetrics {
+                active_routes: active.len(),
+                routing_decisions_per_second: if time_delta > 0.0 { (routed_delta as f32 / time_delta) as u64 } else { 0 },
+                adaptive_adjustments: 0, // Pas encore disponible
+                route_quality_average,
             },
-            routing_stats,
         }
     }
 
     /// Collecte les métriques de performance
     async fn collect_performance_metrics(
-        thread_pool: &Option<Arc<AudioThreadPool>>
+        process: &ProcessSnapshot,
+        thread_pool: &Option<Arc<AudioThreadPool>>,
+        system_monitor: &SystemMonitor,
     ) -> PerformanceMetrics {
+        let runtime = system_monitor.runtime_snapshot();
+
+        let mut metrics = PerformanceMetrics {
+            cpu_usage_percent: process.cpu_usage_percent,
+            memory_usage_mb: process.memory_usage_mb,
+            thread_pool_utilization: 0.0,
+            processing_queue_size: 0,
+            average_processing_time_us: 0,
+            concurrent_operations: 0,
+            thread_count: process.thread_count,
+            open_file_descriptors: process.open_file_descriptors,
+            open_sockets: process.open_sockets,
+            runtime_workers: runtime.workers,
+            runtime_alive_tasks: runtime.alive_tasks,
+            runtime_global_queue_depth: runtime.global_queue_depth,
+        };
+
         if let Some(pool) = thread_pool {
             let stats = pool.get_stats().await;
-            PerformanceMetrics {
-                cpu_usage_percent: Self::get_cpu_usage(),
-                memory_usage_mb: Self::get_memory_usage(),
-                thread_pool_utilization: (stats.active_workers as f32 / (stats.active_workers + 1) as f32) * 100.0,
-                processing_queue_size: stats.queued_tasks,
-                average_processing_time_us: stats.average_processing_time_us,
-                concurrent_operations: stats.active_workers,
-            }
-        } else {
-            PerformanceMetrics {
-                cpu_usage_percent: 0.0,
-                memory_usage_mb: 0,
-                thread_pool_utilization: 0.0,
-                processing_queue_size: 0,
-                average_processing_time_us: 0,
-                concurrent_operations: 0,
+            if stats.active_workers > 0 {
+                metrics.thread_pool_utilization = stats.busy_workers as f32 / stats.active_workers as f32 * 100.0;
             }
+            metrics.processing_queue_size = stats.queued_tasks;
+            metrics.average_processing_time_us = stats.average_processing_time_us;
+            metrics.concurrent_operations = stats.busy_workers;
         }
+
+        metrics
     }
 
     /// Collecte les métriques réseau
-    async fn collect_network_metrics(
-        udp_server: &Option<Arc<RwLock<AudioUdpServer>>>,
+    fn collect_network_metrics(
+        transport: &Option<Arc<TransportStats>>,
+        router: &Option<Arc<AudioRouter>>,
         time_delta: f32,
-        last_packets_received: &mut u64,
-        last_packets_sent: &mut u64,
-        last_bytes_received: &mut u64,
-        last_bytes_sent: &mut u64,
+        last_transport: &mut TransportSnapshot,
     ) -> NetworkMetrics {
-        if let Some(server) = udp_server {
-            let server_guard = server.read().await;
-            let stats = server_guard.get_stats().await;
-            
-            // Calculer les taux par seconde
-            let packets_received_delta = stats.total_packets_received - *last_packets_received;
-            let packets_sent_delta = stats.total_packets_sent - *last_packets_sent;
-            let bytes_received_delta = stats.bytes_received - *last_bytes_received;
-            let bytes_sent_delta = stats.bytes_sent - *last_bytes_sent;
-            
-            *last_packets_received = stats.total_packets_received;
-            *last_packets_sent = stats.total_packets_sent;
-            *last_bytes_received = stats.bytes_received;
-            *last_bytes_sent = stats.bytes_sent;
-            
-            NetworkMetrics {
-                packets_received_per_second: (packets_received_delta as f32 / time_delta) as u64,
-                packets_sent_per_second: (packets_sent_delta as f32 / time_delta) as u64,
-                bytes_received_per_second: (bytes_received_delta as f32 / time_delta) as u64,
-                bytes_sent_per_second: (bytes_sent_delta as f32 / time_delta) as u64,
-                connection_count: stats.active_connections,
-                failed_connections: stats.packet_errors,
-                network_jitter_ms: 5.0, // Approximation
-            }
-        } else {
-            NetworkMetrics {
+        let Some(transport) = transport else {
+            return NetworkMetrics {
                 packets_received_per_second: 0,
                 packets_sent_per_second: 0,
                 bytes_received_per_second: 0,
@@ -479,8 +600,35 @@ impl MetricsCollector {
                 connection_count: 0,
                 failed_connections: 0,
                 network_jitter_ms: 0.0,
+            };
+        };
+
+        let current = transport.snapshot();
+        let rate = |now: u64, before: u64| -> u64 {
+            if time_delta > 0.0 {
+                (now.saturating_sub(before) as f32 / time_delta) as u64
+            } else {
+                0
             }
-        }
+        };
+
+        let metrics = NetworkMetrics {
+            packets_received_per_second: rate(current.packets_received, last_transport.packets_received),
+            packets_sent_per_second: rate(current.packets_sent, last_transport.packets_sent),
+            bytes_received_per_second: rate(current.bytes_received, last_transport.bytes_received),
+            bytes_sent_per_second: rate(current.bytes_sent, last_transport.bytes_sent),
+            connection_count: router.as_ref().map(|r| r.get_global_stats().0).unwrap_or(0),
+            failed_connections: current.errors,
+            network_jitter_ms: router.as_ref()
+                .map(|r| {
+                    let stats = r.all_channel_stats();
+                    stats.iter().map(|(_, s)| s.jitter_ms).fold(0.0, f32::max)
+                })
+                .unwrap_or(0.0),
+        };
+
+        *last_transport = current;
+        metrics
     }
 
     /// Calcule la santé globale du système
@@ -488,6 +636,7 @@ impl MetricsCollector {
         audio: &AudioSystemMetrics,
         performance: &PerformanceMetrics,
         network: &NetworkMetrics,
+        system_memory_total_mb: u64,
         start_time: Instant,
     ) -> SystemHealthMetrics {
         // Score de santé basé sur plusieurs facteurs
@@ -495,8 +644,14 @@ impl MetricsCollector {
         let cpu_score = (100.0 - performance.cpu_usage_percent.min(100.0)) / 100.0;
         let packet_loss_score = (10.0 - audio.packet_loss_percentage.min(10.0)) / 10.0;
         let quality_score = audio.audio_quality_score;
+        let memory_usage_percent = if system_memory_total_mb > 0 {
+            performance.memory_usage_mb as f32 / system_memory_total_mb as f32 * 100.0
+        } else {
+            0.0
+        };
+        let memory_score = (100.0 - memory_usage_percent.min(100.0)) / 100.0;
         
-        let overall_health_score = (latency_score + cpu_score + packet_loss_score + quality_score) / 4.0;
+        let overall_health_score = (latency_score + cpu_score + memory_score + packet_loss_score + quality_score) / 5.0;
         
         SystemHealthMetrics {
             overall_health_score,
@@ -504,6 +659,7 @@ impl MetricsCollector {
             uptime_seconds: start_time.elapsed().as_secs(),
             error_rate_percent: (network.failed_connections as f32 / (network.connection_count.max(1) as f32)) * 100.0,
             service_availability_percent: if overall_health_score > 0.7 { 100.0 } else { overall_health_score * 100.0 },
+            memory_usage_percent,
         }
     }
 
@@ -548,19 +704,18 @@ impl MetricsCollector {
             });
         }
 
-        alerts
-    }
-
-    /// Obtient l'utilisation CPU (simulation)
-    fn get_cpu_usage() -> f32 {
-        // En production, utiliser une vraie bibliothèque de monitoring système
-        25.0
-    }
+        // Alerte utilisation mémoire
+        if metrics.performance_metrics.memory_usage_mb > thresholds.high_memory_usage_mb {
+            alerts.push(AlertInfo {
+                id: format!("memory-{}", timestamp),
+                severity: AlertSeverity::Warning,
+                message: format!("High memory usage: {}MB", metrics.performance_metrics.memory_usage_mb),
+                timestamp,
+                component: "performance".to_string(),
+            });
+        }
 
-    /// Obtient l'utilisation mémoire (simulation)
-    fn get_memory_usage() -> u64 {
-        // En production, utiliser une vraie bibliothèque de monitoring système
-        512
+        alerts
     }
 
     /// Crée des métriques vides
@@ -600,6 +755,12 @@ impl MetricsCollector {
                 processing_queue_size: 0,
                 average_processing_time_us: 0,
                 concurrent_operations: 0,
+                thread_count: 0,
+                open_file_descriptors: 0,
+                open_sockets: 0,
+                runtime_workers: 0,
+                runtime_alive_tasks: 0,
+                runtime_global_queue_depth: 0,
             },
             network_metrics: NetworkMetrics {
                 packets_received_per_second: 0,
@@ -616,10 +777,42 @@ impl MetricsCollector {
                 uptime_seconds: 0,
                 error_rate_percent: 0.0,
                 service_availability_percent: 100.0,
+                memory_usage_percent: 0.0,
             },
         }
     }
 
+    /// Rassemble les métriques courantes et les statistiques détaillées des composants
+    pub async fn export_snapshot(&self) -> MetricsSnapshot {
+        let metrics = self.get_current_metrics().await;
+
+        let (connected_users, channel_routing) = match &self.components.router {
+            Some(router) => (router.get_global_stats().0, router.all_channel_stats()),
+            None => (metrics.network_metrics.connection_count, Vec::new()),
+        };
+
+        let channel_mixing = match &self.components.mixer {
+            Some(mixer) => mixer.read().await.all_stats()
+                .map(|(channel_id, stats)| (*channel_id, stats.clone()))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let thread_pool = match &self.components.thread_pool {
+            Some(pool) => Some(pool.get_stats().await),
+            None => None,
+        };
+
+        MetricsSnapshot {
+            metrics,
+            connected_users,
+            channel_routing,
+            channel_mixing,
+            thread_pool,
+            latency: self.latency_histogram.lock().unwrap().clone(),
+        }
+    }
+
     /// Récupère les métriques actuelles
     pub async fn get_current_metrics(&self) -> RealTimeMetrics {
         self.current_metrics.read().await.clone()
@@ -708,6 +901,72 @@ mod tests {
         assert!(alerts.is_empty()); // Pas d'alertes avec des métriques vides
     }
 
+    #[test]
+    fn test_memory_alert() {
+        let mut metrics = MetricsCollector::create_empty_metrics();
+        let thresholds = AlertThresholds::default();
+        metrics.performance_metrics.memory_usage_mb = thresholds.high_memory_usage_mb + 1;
+
+        let alerts = MetricsCollector::detect_alerts(&metrics, &thresholds);
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].id.starts_with("memory-"));
+    }
+
+    #[test]
+    fn test_latency_histogram() {
+        let mut histogram = LatencyHistogram::new(&[10.0, 50.0]);
+        histogram.observe(5.0);
+        histogram.observe(10.0);
+        histogram.observe(30.0);
+        histogram.observe(80.0);
+
+        assert_eq!(histogram.counts, vec![2, 1, 1]);
+        assert_eq!(histogram.cumulative_counts(), vec![2, 3]);
+        assert_eq!(histogram.count, 4);
+        assert_eq!(histogram.sum_ms, 125.0);
+    }
+
+    #[tokio::test]
+    async fn test_collect_from_registered_components() {
+        let router = Arc::new(AudioRouter::new());
+        let transport = Arc::new(TransportStats::default());
+        let channel_id = Uuid::new_v4();
+        let speaker = Uuid::new_v4();
+        let listener = Uuid::new_v4();
+
+        router.add_user_to_channel(speaker, channel_id);
+        router.add_user_to_channel(listener, channel_id);
+        router.register_client(speaker, "127.0.0.1:9000".parse().unwrap());
+        router.register_client(listener, "127.0.0.1:9001".parse().unwrap());
+        transport.record_received(100);
+
+        let mut collector = MetricsCollector::new(MetricsConfig::default());
+        collector.register_components(None, Some(transport.clone()), None, Some(router.clone()));
+
+        // Trafic postérieur à l'enregistrement
+        let packet = crate::audio::AudioPacket::audio(speaker, channel_id, 1, bytes::Bytes::from(vec![0u8; 16]), 48000, 1);
+        router.route_to_channel(packet);
+        transport.record_received(100);
+        transport.record_sent(100);
+
+        let mut counters = collector.last_counters;
+        let metrics = MetricsCollector::collect_all_metrics(
+            &collector.components,
+            &collector.system_monitor,
+            collector.start_time,
+            1.0,
+            &mut counters,
+        ).await;
+
+        assert_eq!(metrics.network_metrics.packets_received_per_second, 1);
+        assert_eq!(metrics.network_metrics.packets_sent_per_second, 1);
+        assert_eq!(metrics.network_metrics.connection_count, 2);
+        assert_eq!(metrics.audio_metrics.active_channels, 1);
+        assert_eq!(metrics.audio_metrics.active_users, 2);
+        assert_eq!(metrics.audio_metrics.routing_stats.routing_decisions_per_second, 1);
+        assert_eq!(counters.transport.packets_received, 2);
+    }
+
     #[tokio::test]
     async fn test_metrics_collector() {
         let config = MetricsConfig::default();
@@ -3,6 +3,12 @@ use std::collections::HashMap;
 use std::time::Instant;
 use uuid::Uuid;
 use crate::audio::AudioPacket;
+use crate::audio::dsp::{AutomaticGainControl, Biquad, Compressor, NoiseGate};
+
+/// Fréquence de coupure du filtre passe-haut vocal
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+/// Pleine échelle des samples 16-bit
+const FULL_SCALE: f32 = i16::MAX as f32;
 
 /// Contrôles de volume et effets par utilisateur
 #[derive(Debug, Clone)]
@@ -41,6 +47,14 @@ pub struct ChannelMixConfig {
     pub compression_ratio: f32,
     pub noise_gate_enabled: bool,
     pub noise_gate_threshold: f32,
+    /// Écart entre seuil d'ouverture et de fermeture du gate (dB)
+    pub noise_gate_hysteresis_db: f32,
+    /// Seuil du compresseur (dBFS)
+    pub compression_threshold_db: f32,
+    /// Plafond du limiteur de sortie (dBFS)
+    pub limiter_ceiling_db: f32,
+    /// Niveau RMS visé par l'AGC (dBFS)
+    pub agc_target_db: f32,
     pub max_concurrent_voices: usize,
 }
 
@@ -53,6 +67,10 @@ impl Default for ChannelMixConfig {
             compression_ratio: 4.0,
             noise_gate_enabled: true,
             noise_gate_threshold: -40.0, // dB
+            noise_gate_hysteresis_db: 6.0,
+            compression_threshold_db: -12.0,
+            limiter_ceiling_db: -1.0,
+            agc_target_db: -18.0,
             max_concurrent_voices: 8,
         }
     }
@@ -82,6 +100,40 @@ impl Default for MixingStats {
     }
 }
 
+/// État DSP d'un utilisateur dans un channel, conservé d'un packet à l'autre
+#[derive(Debug, Clone)]
+struct VoiceProcessor {
+    high_pass: Biquad,
+    gate: NoiseGate,
+}
+
+impl VoiceProcessor {
+    fn new(sample_rate: u32, config: &ChannelMixConfig) -> Self {
+        Self {
+            high_pass: Biquad::high_pass(sample_rate, HIGH_PASS_CUTOFF_HZ, std::f32::consts::FRAC_1_SQRT_2),
+            gate: NoiseGate::new(sample_rate, config.noise_gate_threshold, config.noise_gate_hysteresis_db, 0.0),
+        }
+    }
+}
+
+/// État DSP du mix d'un channel
+#[derive(Debug, Clone)]
+struct MixProcessor {
+    agc: AutomaticGainControl,
+    compressor: Compressor,
+    limiter: Compressor,
+}
+
+impl MixProcessor {
+    fn new(sample_rate: u32, config: &ChannelMixConfig) -> Self {
+        Self {
+            agc: AutomaticGainControl::new(sample_rate, config.agc_target_db, 0.1, 4.0),
+            compressor: Compressor::new(sample_rate, config.compression_threshold_db, config.compression_ratio, 5.0, 150.0, 2.0),
+            limiter: Compressor::limiter(sample_rate, config.limiter_ceiling_db),
+        }
+    }
+}
+
 /// Mixeur audio avancé
 #[derive(Debug)]
 pub struct AudioMixer {
@@ -93,8 +145,10 @@ pub struct AudioMixer {
     mix_buffer: Vec<f32>,
     /// Statistiques de mixage
     stats: HashMap<Uuid, MixingStats>,
-    /// Historique de niveaux pour AGC
-    level_history: HashMap<Uuid, Vec<f32>>,
+    /// Filtres par utilisateur et par channel
+    voice_processors: HashMap<(Uuid, Uuid), VoiceProcessor>,
+    /// Dynamique (AGC, compresseur, limiteur) par channel
+    mix_processors: HashMap<Uuid, MixProcessor>,
     /// Sample rate de sortie
     sample_rate: u32,
     /// Nombre de channels de sortie
@@ -108,7 +162,8 @@ impl AudioMixer {
             channel_configs: HashMap::new(),
             mix_buffer: Vec::new(),
             stats: HashMap::new(),
-            level_history: HashMap::new(),
+            voice_processors: HashMap::new(),
+            mix_processors: HashMap::new(),
             sample_rate,
             channels,
         }
@@ -202,13 +257,9 @@ impl AudioMixer {
             return None;
         }
 
-        // Redimensionner le buffer de mixage si nécessaire
-        if self.mix_buffer.len() < sample_count * self.channels as usize {
-            self.mix_buffer.resize(sample_count * self.channels as usize, 0.0);
-        } else {
-            // Réinitialiser le buffer
-            self.mix_buffer.fill(0.0);
-        }
+        // Réinitialiser le buffer de mixage à la taille de la trame
+        self.mix_buffer.clear();
+        self.mix_buffer.resize(sample_count * self.channels as usize, 0.0);
 
         let mut stats = MixingStats::default();
         stats.active_voices = active_packets.len();
@@ -216,12 +267,13 @@ impl AudioMixer {
         // Mixer chaque packet avec ses contrôles
         for (packet, controls) in &active_packets {
             if let Ok(samples) = self.bytes_to_samples(&packet.payload) {
-                self.apply_audio_processing(&samples, controls, &config, &mut stats);
+                let key = (packet.header.user_id, channel_id);
+                self.apply_audio_processing(key, &samples, controls, &config, &mut stats);
             }
         }
 
         // Post-traitement global
-        self.apply_global_processing(&config, &mut stats);
+        self.apply_global_processing(channel_id, sample_count, &config, &mut stats);
 
         // Convertir en sortie
         let output = self.mix_buffer_to_bytes(sample_count);
@@ -233,56 +285,47 @@ impl AudioMixer {
         output
     }
 
-    /// Applique le traitement audio pour un utilisateur
+    /// Applique le traitement audio pour un utilisateur (passe-haut puis noise gate)
     fn apply_audio_processing(
         &mut self,
+        key: (Uuid, Uuid),
         samples: &[i16],
         controls: &UserAudioControls,
         config: &ChannelMixConfig,
         stats: &mut MixingStats,
     ) {
+        let sample_rate = self.sample_rate;
+        let processor = self.voice_processors
+            .entry(key)
+            .or_insert_with(|| VoiceProcessor::new(sample_rate, config));
+
+        // Gate complet si activé sur le channel, sinon simple atténuation du bruit de fond
+        let gate_enabled = config.noise_gate_enabled || controls.noise_suppression > 0.0;
+        let floor_gain = if config.noise_gate_enabled { 0.0 } else { 1.0 - controls.noise_suppression };
+        processor.gate.set_thresholds(config.noise_gate_threshold, config.noise_gate_hysteresis_db, floor_gain);
+
+        let gain = controls.volume * config.master_volume;
+
         for (i, &sample) in samples.iter().enumerate() {
             if i >= self.mix_buffer.len() {
                 break;
             }
 
-            let mut processed_sample = sample as f32;
-
-            // Appliquer le volume utilisateur
-            processed_sample *= controls.volume;
-
-            // Appliquer le volume maître du channel
-            processed_sample *= config.master_volume;
-
-            // Gate de bruit
-            if config.noise_gate_enabled {
-                let sample_db = 20.0 * (processed_sample.abs() / i16::MAX as f32).log10();
-                if sample_db < config.noise_gate_threshold {
-                    processed_sample = 0.0;
-                }
-            }
+            let mut processed_sample = sample as f32 / FULL_SCALE * gain;
 
-            // Suppression de bruit basique
-            if controls.noise_suppression > 0.0 {
-                let noise_factor = 1.0 - controls.noise_suppression;
-                if processed_sample.abs() < (i16::MAX as f32 * 0.1) {
-                    processed_sample *= noise_factor;
-                }
+            if controls.high_pass_enabled {
+                processed_sample = processor.high_pass.process(processed_sample);
             }
 
-            // Filtre passe-haut basique
-            if controls.high_pass_enabled {
-                // Implémentation simplifiée - en production, utiliser un vrai filtre
-                if processed_sample.abs() < (i16::MAX as f32 * 0.05) {
-                    processed_sample *= 0.5;
-                }
+            if gate_enabled {
+                processed_sample = processor.gate.process(processed_sample);
             }
 
             // Accumuler dans le buffer de mixage
             self.mix_buffer[i] += processed_sample;
 
             // Mettre à jour les statistiques
-            let abs_sample = processed_sample.abs();
+            let abs_sample = processed_sample.abs() * FULL_SCALE;
             if abs_sample > stats.peak_level {
                 stats.peak_level = abs_sample;
             }
@@ -291,43 +334,47 @@ impl AudioMixer {
         }
     }
 
-    /// Applique le traitement global au mix
-    fn apply_global_processing(&mut self, config: &ChannelMixConfig, stats: &mut MixingStats) {
+    /// Applique le traitement global au mix : AGC lissé, compresseur puis limiteur à anticipation
+    fn apply_global_processing(
+        &mut self,
+        channel_id: Uuid,
+        sample_count: usize,
+        config: &ChannelMixConfig,
+        stats: &mut MixingStats,
+    ) {
+        let sample_rate = self.sample_rate;
+        let processor = self.mix_processors
+            .entry(channel_id)
+            .or_insert_with(|| MixProcessor::new(sample_rate, config));
+        processor.agc.set_target(config.agc_target_db);
+        processor.compressor.set_parameters(config.compression_threshold_db, config.compression_ratio);
+        processor.limiter.set_parameters(config.limiter_ceiling_db, f32::INFINITY);
+
+        let frame_len = sample_count.min(self.mix_buffer.len());
         let mut rms_sum = 0.0;
 
-        for sample in self.mix_buffer.iter_mut() {
-            // Compression dynamique basique
-            if config.compression_enabled && sample.abs() > (i16::MAX as f32 * 0.7) {
-                let threshold = i16::MAX as f32 * 0.7;
-                let excess = sample.abs() - threshold;
-                let compressed_excess = excess / config.compression_ratio;
-                let sign = if *sample >= 0.0 { 1.0 } else { -1.0 };
-                *sample = sign * (threshold + compressed_excess);
+        for sample in self.mix_buffer[..frame_len].iter_mut() {
+            if config.auto_gain_control {
+                *sample = processor.agc.process(*sample);
             }
 
-            // Détection de saturation
-            if sample.abs() >= i16::MAX as f32 {
+            if config.compression_enabled {
+                *sample = processor.compressor.process(*sample);
+            }
+
+            *sample = processor.limiter.process(*sample);
+
+            // Détection de saturation résiduelle
+            if sample.abs() >= 1.0 {
                 stats.clipping_detected = true;
-                *sample = sample.signum() * (i16::MAX as f32 - 1.0);
+                *sample = sample.signum() * (FULL_SCALE - 1.0) / FULL_SCALE;
             }
 
             rms_sum += *sample * *sample;
         }
 
-        // Calculer le RMS
-        stats.rms_level = (rms_sum / self.mix_buffer.len() as f32).sqrt();
-
-        // Contrôle automatique de gain (AGC)
-        if config.auto_gain_control {
-            let target_rms = i16::MAX as f32 * 0.3; // 30% du maximum
-            if stats.rms_level > 0.0 {
-                let gain_adjustment = target_rms / stats.rms_level;
-                let clamped_gain = gain_adjustment.clamp(0.1, 2.0);
-                
-                for sample in self.mix_buffer.iter_mut() {
-                    *sample *= clamped_gain;
-                }
-            }
+        if frame_len > 0 {
+            stats.rms_level = (rms_sum / frame_len as f32).sqrt() * FULL_SCALE;
         }
     }
 
@@ -337,7 +384,7 @@ impl AudioMixer {
         
         for i in 0..sample_count {
             if i < self.mix_buffer.len() {
-                let sample = self.mix_buffer[i].clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+                let sample = (self.mix_buffer[i] * FULL_SCALE).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
                 output_bytes.extend_from_slice(&sample.to_le_bytes());
             } else {
                 output_bytes.extend_from_slice(&0i16.to_le_bytes());
@@ -401,11 +448,20 @@ impl AudioMixer {
     /// Supprime les contrôles d'un utilisateur
     pub fn remove_user(&mut self, user_id: &Uuid, channel_id: &Uuid) {
         self.user_controls.remove(&(*user_id, *channel_id));
+        self.voice_processors.remove(&(*user_id, *channel_id));
     }
 
     /// Réinitialise tous les contrôles utilisateur pour un channel
     pub fn reset_channel_users(&mut self, channel_id: &Uuid) {
         self.user_controls.retain(|(_, ch_id), _| ch_id != channel_id);
+        self.voice_processors.retain(|(_, ch_id), _| ch_id != channel_id);
+        self.mix_processors.remove(channel_id);
+    }
+
+    /// Oublie l'état et les statistiques d'un channel qui ne produit plus d'audio
+    pub fn remove_channel(&mut self, channel_id: &Uuid) {
+        self.reset_channel_users(channel_id);
+        self.stats.remove(channel_id);
     }
 
     /// Récupère les statistiques de mixage pour un channel
@@ -413,6 +469,11 @@ impl AudioMixer {
         self.stats.get(channel_id)
     }
 
+    /// Récupère les statistiques de mixage de tous les channels
+    pub fn all_stats(&self) -> impl Iterator<Item = (&Uuid, &MixingStats)> {
+        self.stats.iter()
+    }
+
     /// Récupère les statistiques globales du mixeur
     pub fn global_stats(&self) -> MixerGlobalStats {
         MixerGlobalStats {
@@ -561,6 +622,69 @@ mod tests {
         assert!(mixer.mix_packets_advanced(vec![], channel_id).is_none());
     }
 
+    /// Génère une trame PCM 16-bit d'une sinusoïde, en continuité de phase
+    fn sine_packet(user_id: Uuid, channel_id: Uuid, frame: usize, frequency: f32, amplitude: f32) -> AudioPacket {
+        const FRAME: usize = 960;
+        let bytes: Vec<u8> = (0..FRAME)
+            .flat_map(|i| {
+                let t = (frame * FRAME + i) as f32 / 48000.0;
+                let sample = amplitude * (2.0 * std::f32::consts::PI * frequency * t).sin();
+                ((sample * FULL_SCALE) as i16).to_le_bytes()
+            })
+            .collect();
+        AudioPacket::audio(user_id, channel_id, frame as u32, Bytes::from(bytes), 48000, 1)
+    }
+
+    fn output_samples(bytes: &Bytes) -> Vec<i16> {
+        bytes.chunks_exact(2).map(|c| i16::from_le_bytes([c[0], c[1]])).collect()
+    }
+
+    #[test]
+    fn test_mix_is_limited() {
+        let mut mixer = AudioMixer::default();
+        let channel_id = Uuid::new_v4();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let ceiling = crate::audio::dsp::db_to_linear(-1.0) * FULL_SCALE;
+
+        // Deux voix fortes en phase : la somme dépasse largement la pleine échelle
+        for frame in 0..50 {
+            let packets = vec![
+                sine_packet(alice, channel_id, frame, 440.0, 0.9),
+                sine_packet(bob, channel_id, frame, 440.0, 0.9),
+            ];
+            let output = mixer.mix_packets_advanced(packets, channel_id).unwrap();
+            let peak = output_samples(&output).iter().map(|s| (*s as f32).abs()).fold(0.0, f32::max);
+            assert!(peak <= ceiling * 1.01, "frame {} peak {} above ceiling {}", frame, peak, ceiling);
+        }
+
+        let stats = mixer.get_stats(&channel_id).unwrap();
+        assert!(!stats.clipping_detected);
+        assert_eq!(stats.active_voices, 2);
+    }
+
+    #[test]
+    fn test_mix_gates_background_noise() {
+        let mut mixer = AudioMixer::default();
+        let channel_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+
+        // Bruit de fond à -60 dBFS, sous le seuil du gate (-40 dBFS)
+        let mut last = Vec::new();
+        for frame in 0..20 {
+            let packet = sine_packet(user_id, channel_id, frame, 1000.0, 0.001);
+            last = output_samples(&mixer.mix_packets_advanced(vec![packet], channel_id).unwrap());
+        }
+        assert!(last.iter().all(|s| s.abs() <= 1));
+
+        // La voix ouvre le gate et ressort à un niveau audible
+        for frame in 20..40 {
+            let packet = sine_packet(user_id, channel_id, frame, 300.0, 0.3);
+            last = output_samples(&mixer.mix_packets_advanced(vec![packet], channel_id).unwrap());
+        }
+        let rms = (last.iter().map(|s| (*s as f32).powi(2)).sum::<f32>() / last.len() as f32).sqrt();
+        assert!(rms > FULL_SCALE * 0.05);
+    }
+
     #[test]
     fn test_bytes_conversion() {
         let mixer = AudioMixer::default();
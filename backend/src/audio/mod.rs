@@ -5,11 +5,14 @@ pub mod mixer;
 pub mod server;
 pub mod performance;
 pub mod metrics;
+pub mod system;
+pub mod dsp;
 
-pub use packet::{AudioPacket, AudioHeader, PacketType};
+pub use packet::{AudioPacket, AudioHeader, ClockEstimate, PacketType, ReceiverReport, SyncEcho};
 pub use buffer::{AudioBuffer, CircularBuffer};
-pub use router::{AudioRouter, RoutingStats};
+pub use router::{AddressObservation, AudioRouter, RoutingStats};
 pub use mixer::AudioMixer;
 pub use server::AudioUdpServer;
 pub use performance::{AudioThreadPool, AudioThreadPoolConfig, AudioProcessingPriority};
-pub use metrics::{MetricsCollector, MetricsConfig, RealTimeMetrics, HealthReport};
\ No newline at end of file
+pub use metrics::{MetricsCollector, MetricsConfig, RealTimeMetrics, HealthReport};
+pub use system::{SystemMonitor, ProcessSnapshot, RuntimeSnapshot};
\ No newline at end of file
@@ -1,5 +1,6 @@
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
@@ -16,6 +17,66 @@ pub enum PacketType {
     AudioStop = 3,
     /// Packet de synchronisation/heartbeat
     Sync = 4,
+    /// Ancien défi UDP, plus émis : le nonce passe par le WebSocket authentifié
+    Probe = 5,
+    /// Réponse du client à un défi, envoyée depuis sa nouvelle adresse (payload : nonce reçu)
+    ProbeReply = 6,
+    /// Réponse du serveur à un heartbeat (payload : `SyncEcho`)
+    SyncReply = 7,
+    /// Rapport de réception d'un client (payload : `ReceiverReport`)
+    ReceiverReport = 8,
+}
+
+/// Qualité de l'audio reçu par un client depuis son rapport précédent (façon RTCP)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReceiverReport {
+    /// Packets attendus d'après les numéros de séquence des émetteurs
+    pub packets_expected: u32,
+    pub packets_lost: u32,
+    /// Gigue d'arrivée (RFC 3550) en millisecondes
+    pub jitter_ms: f32,
+    /// Aller-retour mesuré sur les heartbeats (0 si inconnu)
+    pub rtt_ms: f32,
+}
+
+impl ReceiverReport {
+    /// Taux de perte entre 0 et 1
+    pub fn loss_rate(&self) -> f32 {
+        if self.packets_expected == 0 {
+            0.0
+        } else {
+            (self.packets_lost as f32 / self.packets_expected as f32).min(1.0)
+        }
+    }
+}
+
+/// Estimation d'horloge qu'un client joint à ses heartbeats (payload de `Sync`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClockEstimate {
+    /// Aller-retour client <-> serveur, hors temps de traitement du serveur
+    pub rtt_us: u64,
+    /// Horloge du serveur moins horloge du client
+    pub offset_us: i64,
+}
+
+/// Réponse à un heartbeat (payload de `SyncReply`, horodatée à l'envoi par le header) :
+/// de quoi calculer l'aller-retour et le décalage d'horloge façon NTP
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SyncEcho {
+    /// Adresse publique du client vue par le serveur
+    pub observed: SocketAddr,
+    /// Horodatage du heartbeat par le client
+    pub client_sent_us: u64,
+    /// Réception du heartbeat par le serveur
+    pub server_received_us: u64,
+}
+
+/// Instant présent en microsecondes depuis UNIX_EPOCH (horloge des headers)
+pub fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
 }
 
 /// Header du packet audio - 32 bytes
@@ -51,10 +112,7 @@ impl AudioHeader {
         sample_rate: u32,
         channels: u8,
     ) -> Self {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_micros() as u64;
+        let timestamp = now_micros();
 
         Self {
             packet_type,
@@ -80,13 +138,15 @@ impl AudioHeader {
             .map_err(|e| format!("Failed to deserialize header: {}", e))
     }
 
-    /// Calcule l'âge du packet en microsecondes
+    /// Calcule l'âge du packet en microsecondes (horodaté par cette même machine)
     pub fn age_micros(&self) -> u64 {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_micros() as u64;
-        now.saturating_sub(self.timestamp)
+        now_micros().saturating_sub(self.timestamp)
+    }
+
+    /// Délai de transit depuis l'émetteur, son horloge étant décalée de `clock_offset_us`
+    /// par rapport à la nôtre
+    pub fn transit_micros(&self, clock_offset_us: i64) -> i64 {
+        now_micros() as i64 - (self.timestamp as i64 + clock_offset_us)
     }
 
     /// Vérifie si le packet est trop ancien (> 100ms)
@@ -192,6 +252,62 @@ impl AudioPacket {
         )
     }
 
+    /// Crée la réponse à un heartbeat reçu à `received_us` : adresse publique du client
+    /// et horodatages pour sa mesure d'aller-retour
+    pub fn sync_reply(user_id: Uuid, channel_id: Uuid, observed: SocketAddr, request: &AudioHeader, received_us: u64) -> Self {
+        let echo = SyncEcho {
+            observed,
+            client_sent_us: request.timestamp,
+            server_received_us: received_us,
+        };
+        Self::new(
+            PacketType::SyncReply,
+            user_id,
+            channel_id,
+            0,
+            Bytes::from(bincode::serialize(&echo).unwrap_or_default()),
+            48000,
+            1,
+        )
+    }
+
+    /// Estimation d'horloge jointe à un heartbeat (absente au premier)
+    pub fn clock_estimate(&self) -> Option<ClockEstimate> {
+        match self.header.packet_type {
+            PacketType::Sync => bincode::deserialize(&self.payload).ok(),
+            _ => None,
+        }
+    }
+
+    /// Crée un rapport de réception
+    pub fn receiver_report(user_id: Uuid, channel_id: Uuid, report: &ReceiverReport) -> Self {
+        Self::new(
+            PacketType::ReceiverReport,
+            user_id,
+            channel_id,
+            0,
+            Bytes::from(bincode::serialize(report).unwrap_or_default()),
+            48000,
+            1,
+        )
+    }
+
+    /// Contenu d'un rapport de réception
+    pub fn report(&self) -> Option<ReceiverReport> {
+        match self.header.packet_type {
+            PacketType::ReceiverReport => bincode::deserialize(&self.payload).ok(),
+            _ => None,
+        }
+    }
+
+    /// Nonce porté par un défi ou sa réponse
+    pub fn probe_nonce(&self) -> Option<Uuid> {
+        match self.header.packet_type {
+            PacketType::Probe | PacketType::ProbeReply => Uuid::from_slice(&self.payload).ok(),
+            _ => None,
+        }
+    }
+
     /// Sérialise le packet complet en bytes
     pub fn to_bytes(&self) -> Vec<u8> {
         let header_bytes = self.header.to_bytes();
@@ -244,7 +360,13 @@ impl AudioPacket {
     pub fn is_control(&self) -> bool {
         matches!(
             self.header.packet_type,
-            PacketType::AudioStart | PacketType::AudioStop | PacketType::Sync
+            PacketType::AudioStart
+                | PacketType::AudioStop
+                | PacketType::Sync
+                | PacketType::Probe
+                | PacketType::ProbeReply
+                | PacketType::SyncReply
+                | PacketType::ReceiverReport
         )
     }
 }
@@ -53,6 +53,9 @@ pub struct AudioProcessingTask {
 #[derive(Debug, Clone)]
 pub struct ThreadPoolStats {
     pub active_workers: usize,
+    /// Workers en train de traiter une tâche
+    pub busy_workers: usize,
+    /// Tâches soumises qu'aucun worker n'a encore prises
     pub queued_tasks: usize,
     pub completed_tasks: u64,
     pub failed_tasks: u64,
@@ -81,6 +84,7 @@ impl AudioThreadPool {
         let semaphore = Arc::new(Semaphore::new(config.max_concurrent_mixes));
         let stats = Arc::new(RwLock::new(ThreadPoolStats {
             active_workers: 0,
+            busy_workers: 0,
             queued_tasks: 0,
             completed_tasks: 0,
             failed_tasks: 0,
@@ -162,8 +166,18 @@ impl AudioThreadPool {
                     }
                 };
 
+                {
+                    let mut stats_guard = stats.write().await;
+                    stats_guard.queued_tasks = stats_guard.queued_tasks.saturating_sub(1);
+                }
+
                 let _permit = semaphore.acquire().await.unwrap();
+                stats.write().await.busy_workers += 1;
                 Self::process_task(task, &mixer, &stats).await;
+                {
+                    let mut stats_guard = stats.write().await;
+                    stats_guard.busy_workers = stats_guard.busy_workers.saturating_sub(1);
+                }
             }
 
             {
@@ -284,12 +298,7 @@ impl AudioThreadPool {
         let receiver = self.submit_mix_task(channel_id, packets, priority).await?;
         
         match tokio::time::timeout(timeout, receiver).await {
-            Ok(Ok(result)) => {
-                // Décrémenter le compteur de file d'attente
-                let mut stats_guard = self.stats.write().await;
-                stats_guard.queued_tasks = stats_guard.queued_tasks.saturating_sub(1);
-                Ok(result)
-            }
+            Ok(Ok(result)) => Ok(result),
             Ok(Err(_)) => Err("Task processing was cancelled"),
             Err(_) => Err("Task processing timed out"),
         }
@@ -454,4 +463,31 @@ mod tests {
         
         let _ = pool.shutdown().await;
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_queue_drains_without_waiting_for_results() {
+        let config = AudioThreadPoolConfig {
+            max_workers: 2,
+            queue_size: 10,
+            ..Default::default()
+        };
+        let mixer = Arc::new(RwLock::new(AudioMixer::default()));
+        let pool = AudioThreadPool::new(config, mixer.clone()).await;
+
+        // Réponse ignorée (mixage de supervision) : la file doit tout de même se vider
+        let channel_id = Uuid::new_v4();
+        let packet = AudioPacket::audio(Uuid::new_v4(), channel_id, 0, Bytes::from(vec![0x00, 0x40].repeat(480)), 48000, 1);
+        drop(pool.submit_mix_task(channel_id, vec![packet], AudioProcessingPriority::Low).await.unwrap());
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while pool.get_stats().await.completed_tasks == 0 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("task not processed");
+        let stats = pool.get_stats().await;
+        assert_eq!((stats.queued_tasks, stats.busy_workers), (0, 0));
+        assert_eq!(mixer.read().await.get_stats(&channel_id).unwrap().active_voices, 1);
+
+        let _ = pool.shutdown().await;
+    }
+}
@@ -1,22 +1,41 @@
-use dashmap::DashMap;
+use dashmap::{mapref::entry::Entry, DashMap, DashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use uuid::Uuid;
-use crate::audio::{AudioPacket, AudioBuffer};
+use crate::audio::{AudioPacket, AudioBuffer, AudioHeader, ClockEstimate, ReceiverReport};
+use crate::audio::metrics::LatencyHistogram;
 
 /// Configuration de routage pour un channel
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChannelRoutingConfig {
+    /// Capacité du channel, tenue par `ChannelService`
     pub max_users: usize,
     pub quality_mode: QualityMode,
+    /// Cible du tampon de lecture des clients
     pub latency_target_ms: u64,
+    /// Obsolète : chaque client règle son annulation d'écho, la valeur est seulement conservée
     pub enable_echo_cancellation: bool,
+    /// Obsolète : chaque client règle sa suppression de bruit, la valeur est seulement conservée
     pub enable_noise_suppression: bool,
+    /// Débit maximal de l'audio émis (PCM 16 bits)
     pub bitrate_kbps: u32,
 }
 
+/// Débit PCM 16 bits du meilleur profil (48 kHz stéréo)
+pub const MAX_BITRATE_KBPS: u32 = 1536;
+/// Débit PCM 16 bits du plus petit format accepté (8 kHz mono)
+pub const MIN_BITRATE_KBPS: u32 = 128;
+/// Bornes de la cible du tampon de lecture des clients
+pub const MIN_LATENCY_TARGET_MS: u64 = 20;
+pub const MAX_LATENCY_TARGET_MS: u64 = 500;
+
 /// Mode de qualité audio
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum QualityMode {
     Low,      // Optimisé pour bande passante
     Medium,   // Équilibré
@@ -24,6 +43,216 @@ pub enum QualityMode {
     Adaptive, // S'adapte automatiquement
 }
 
+impl Default for ChannelRoutingConfig {
+    fn default() -> Self {
+        Self {
+            max_users: 10,
+            // Un channel non configuré suit la qualité de réception de ses membres
+            quality_mode: QualityMode::Adaptive,
+            latency_target_ms: 50,
+            enable_echo_cancellation: false,
+            enable_noise_suppression: false,
+            bitrate_kbps: MAX_BITRATE_KBPS,
+        }
+    }
+}
+
+impl std::str::FromStr for QualityMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(QualityMode::Low),
+            "medium" => Ok(QualityMode::Medium),
+            "high" => Ok(QualityMode::High),
+            "adaptive" => Ok(QualityMode::Adaptive),
+            other => Err(format!("Unknown quality mode '{}'", other)),
+        }
+    }
+}
+
+/// Paramètres d'encodage demandés aux émetteurs d'un channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodingProfile {
+    /// Niveau appliqué (jamais `Adaptive`)
+    pub quality: QualityMode,
+    pub max_sample_rate: u32,
+    pub max_channels: u8,
+    /// Durée minimale d'audio par packet (moins d'en-têtes quand elle augmente)
+    pub frame_ms: u32,
+}
+
+impl EncodingProfile {
+    pub fn for_mode(mode: QualityMode) -> Self {
+        match mode {
+            QualityMode::Low => Self { quality: QualityMode::Low, max_sample_rate: 16000, max_channels: 1, frame_ms: 40 },
+            QualityMode::Medium => Self { quality: QualityMode::Medium, max_sample_rate: 24000, max_channels: 1, frame_ms: 20 },
+            QualityMode::High | QualityMode::Adaptive => Self { quality: QualityMode::High, max_sample_rate: 48000, max_channels: 2, frame_ms: 20 },
+        }
+    }
+
+    /// Débit de l'audio émis avec ce profil (PCM 16 bits)
+    pub fn bitrate_kbps(&self) -> u32 {
+        self.max_sample_rate * self.max_channels as u32 * 16 / 1000
+    }
+
+    /// Ramène le profil sous `bitrate_kbps` : passage en mono, puis baisse de la fréquence
+    pub fn capped(mut self, bitrate_kbps: u32) -> Self {
+        if self.bitrate_kbps() > bitrate_kbps {
+            self.max_channels = 1;
+            self.max_sample_rate = self.max_sample_rate.min(bitrate_kbps * 1000 / 16);
+        }
+        self
+    }
+
+    fn rank(&self) -> u8 {
+        match self.quality {
+            QualityMode::Low => 0,
+            QualityMode::Medium => 1,
+            QualityMode::High | QualityMode::Adaptive => 2,
+        }
+    }
+
+    /// Niveau immédiatement supérieur (la remontée se fait par paliers)
+    fn step_up(&self) -> Self {
+        match self.quality {
+            QualityMode::Low => Self::for_mode(QualityMode::Medium),
+            _ => Self::for_mode(QualityMode::High),
+        }
+    }
+}
+
+/// Qualité du lien serveur -> client, lissée sur ses rapports de réception
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LinkQuality {
+    /// Taux de perte entre 0 et 1
+    pub loss_rate: f32,
+    pub jitter_ms: f32,
+    pub rtt_ms: f32,
+}
+
+impl LinkQuality {
+    /// Niveau d'encodage que ce lien supporte
+    pub fn sustainable_mode(&self) -> QualityMode {
+        if self.loss_rate > 0.05 || self.jitter_ms > 60.0 || self.rtt_ms > 300.0 {
+            QualityMode::Low
+        } else if self.loss_rate > 0.01 || self.jitter_ms > 30.0 || self.rtt_ms > 150.0 {
+            QualityMode::Medium
+        } else {
+            QualityMode::High
+        }
+    }
+
+    fn worst(self, other: Self) -> Self {
+        Self {
+            loss_rate: self.loss_rate.max(other.loss_rate),
+            jitter_ms: self.jitter_ms.max(other.jitter_ms),
+            rtt_ms: self.rtt_ms.max(other.rtt_ms),
+        }
+    }
+}
+
+/// Latence d'un client, mesurée par ses heartbeats et l'horodatage de son audio
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserLatency {
+    /// Aller-retour client <-> serveur
+    pub rtt_ms: f32,
+    /// Horloge du serveur moins horloge du client
+    pub clock_offset_ms: f32,
+    /// Transit client -> serveur de son audio, corrigé du décalage d'horloge
+    pub one_way_ms: f32,
+}
+
+/// Qualité de connexion d'un utilisateur, affichée à côté de son nom dans le channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionQuality {
+    pub user_id: Uuid,
+    /// 0 quand le RTT est inconnu (client tunnelé)
+    pub rtt_ms: f32,
+    /// Pire taux de perte (en %) entre l'audio émis et l'audio reçu
+    pub loss_percent: f32,
+    pub jitter_ms: f32,
+    /// Format de l'audio émis
+    pub codec: String,
+    pub sample_rate: u32,
+    pub channels: u8,
+    /// Débit de l'audio émis (0 quand l'utilisateur ne parle pas)
+    pub bitrate_kbps: f32,
+    /// Barres de signal, de 1 (mauvais) à 4 (excellent)
+    pub signal: u8,
+}
+
+impl ConnectionQuality {
+    /// Un RTT inconnu (client tunnelé, sans heartbeat) n'entre pas dans la note
+    fn signal_bars(rtt_ms: Option<f32>, loss_percent: f32, jitter_ms: f32) -> u8 {
+        let rtt_ms = rtt_ms.unwrap_or_default();
+        if loss_percent > 10.0 || rtt_ms > 400.0 || jitter_ms > 80.0 {
+            1
+        } else if loss_percent > 5.0 || rtt_ms > 250.0 || jitter_ms > 50.0 {
+            2
+        } else if loss_percent > 1.0 || rtt_ms > 150.0 || jitter_ms > 30.0 {
+            3
+        } else {
+            4
+        }
+    }
+}
+
+/// Audio d'un client tel que reçu par le serveur
+#[derive(Debug, Clone)]
+struct UplinkStats {
+    highest_sequence: u32,
+    /// `highest_sequence` au début de la fenêtre de mesure
+    window_base: u32,
+    window_start: Instant,
+    window_received: u32,
+    window_bytes: u64,
+    last_transit_us: i64,
+    jitter_us: f32,
+    last_packet: Instant,
+    sample_rate: u32,
+    channels: u8,
+    /// Mesures de la dernière fenêtre complète
+    loss_rate: f32,
+    bitrate_kbps: f32,
+}
+
+impl UplinkStats {
+    fn new(packet: &AudioPacket, transit_us: i64) -> Self {
+        let now = Instant::now();
+        Self {
+            highest_sequence: packet.header.sequence,
+            window_base: packet.header.sequence.wrapping_sub(1),
+            window_start: now,
+            window_received: 1,
+            window_bytes: packet.payload.len() as u64,
+            last_transit_us: transit_us,
+            jitter_us: 0.0,
+            last_packet: now,
+            sample_rate: packet.header.sample_rate,
+            channels: packet.header.channels,
+            loss_rate: 0.0,
+            bitrate_kbps: 0.0,
+        }
+    }
+}
+
+/// Codec de l'audio transporté (PCM brut, sans compression)
+const AUDIO_CODEC: &str = "pcm_s16le";
+/// Durée des fenêtres de mesure du débit et des pertes de l'audio reçu
+const UPLINK_WINDOW: Duration = Duration::from_secs(1);
+/// Sans audio depuis ce délai, un client ne parle plus (débit nul)
+const UPLINK_IDLE: Duration = Duration::from_secs(2);
+/// Recul de séquence au-delà duquel un client est considéré comme ayant redémarré
+const SEQUENCE_RESTART_GAP: u32 = 1000;
+
+/// Un rapport plus ancien ne reflète plus le lien
+const LINK_REPORT_TTL: Duration = Duration::from_secs(15);
+/// Poids d'un nouveau rapport dans la qualité lissée
+const LINK_SMOOTHING: f32 = 0.5;
+/// Poids d'un nouveau packet dans le transit lissé
+const LATENCY_SMOOTHING: f32 = 1.0 / 16.0;
+
 /// Statistiques de routage pour un channel
 #[derive(Debug, Clone)]
 pub struct RoutingStats {
@@ -37,9 +266,22 @@ pub struct RoutingStats {
     pub average_latency_ms: f32,
     pub packet_loss_rate: f32,
     pub jitter_ms: f32,
+    /// Latence de chaque membre ayant mesuré son horloge
+    pub user_latency: HashMap<Uuid, UserLatency>,
     pub created_at: std::time::Instant,
 }
 
+impl RoutingStats {
+    /// Bouche-à-oreille moyen : transit émetteur -> serveur puis serveur -> auditeur
+    /// (moitié de l'aller-retour)
+    fn end_to_end_latency_ms(&self) -> f32 {
+        let mean = |values: Vec<f32>| if values.is_empty() { 0.0 } else { values.iter().sum::<f32>() / values.len() as f32 };
+        let uplink = mean(self.user_latency.values().map(|l| l.one_way_ms).filter(|ms| *ms > 0.0).collect());
+        let downlink = mean(self.user_latency.values().map(|l| l.rtt_ms / 2.0).filter(|ms| *ms > 0.0).collect());
+        uplink + downlink
+    }
+}
+
 /// Rapport de performance d'un channel
 #[derive(Debug, Clone)]
 pub struct ChannelPerformanceReport {
@@ -61,12 +303,87 @@ pub struct AudioRouter {
     user_buffers: DashMap<(Uuid, Uuid), AudioBuffer>, // (user_id, channel_id) -> buffer
     /// Adresses UDP des clients connectés
     client_addresses: DashMap<Uuid, SocketAddr>,
+    /// Dernier packet reçu de chaque client (audio ou heartbeat)
+    client_last_seen: DashMap<Uuid, Instant>,
+    /// Nouvelle adresse en cours de vérification par client (changement de mapping NAT)
+    address_challenges: DashMap<Uuid, AddressChallenge>,
+    /// Clients dont l'audio passe par le WebSocket (UDP bloqué), prioritaires sur l'UDP
+    tunnels: DashMap<Uuid, TunnelSender>,
+    /// Qualité de réception rapportée par chaque client
+    link_quality: DashMap<Uuid, (LinkQuality, Instant)>,
+    /// Aller-retour, décalage d'horloge et transit de chaque client
+    latency: DashMap<Uuid, UserLatency>,
+    /// Pertes, gigue et débit de l'audio reçu de chaque client
+    uplink: DashMap<Uuid, UplinkStats>,
+    /// Dernier profil d'encodage annoncé par channel
+    channel_profiles: DashMap<Uuid, EncodingProfile>,
     /// Statistiques de routage
     stats: Arc<DashMap<Uuid, RoutingStats>>, // channel_id -> stats
     /// Configuration de routage par channel
     channel_configs: DashMap<Uuid, ChannelRoutingConfig>,
+    /// Configuration des channels qui n'en ont pas de propre
+    channel_defaults: RwLock<ChannelRoutingConfig>,
     /// Synchronisation inter-canaux
     channel_sync: DashMap<Uuid, u64>, // channel_id -> dernier timestamp traité
+    /// Dernière activité vocale par utilisateur (ou date d'entrée dans le channel)
+    voice_activity: DashMap<Uuid, Instant>,
+    /// Utilisateurs dont l'audio n'est plus routé (ex: déplacés en AFK)
+    muted_users: DashSet<Uuid>,
+    /// Utilisateurs sourdine (casque coupé) : rien ne leur est envoyé
+    deafened_users: DashSet<Uuid>,
+    /// État de parole par utilisateur, dérivé des packets audio reçus
+    speaking: DashMap<Uuid, SpeakingState>,
+    /// Histogramme du collecteur de métriques, alimenté à chaque packet audio
+    latency_histogram: OnceLock<Arc<Mutex<LatencyHistogram>>>,
+    /// Copie des packets routés, mixée hors du chemin critique pour mesurer chaque channel
+    mix_tap: OnceLock<mpsc::Sender<AudioPacket>>,
+}
+
+/// Suivi de l'état de parole d'un utilisateur
+#[derive(Debug, Clone)]
+struct SpeakingState {
+    channel_id: Uuid,
+    last_audio: Instant,
+    announced: bool,
+}
+
+/// Envoi des packets sérialisés vers un client passant par le tunnel WebSocket
+pub type TunnelSender = mpsc::Sender<Vec<u8>>;
+
+/// Délai avant de renvoyer un défi resté sans réponse
+const ADDRESS_CHALLENGE_RETRY: Duration = Duration::from_secs(1);
+/// Délai sans packet de l'adresse candidate avant d'abandonner son défi
+const ADDRESS_CHALLENGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Défi d'une adresse candidate. Le nonce part par le WebSocket authentifié du client, qui
+/// doit le renvoyer en UDP depuis cette adresse.
+#[derive(Debug, Clone)]
+struct AddressChallenge {
+    address: SocketAddr,
+    nonce: Uuid,
+    /// Dernier envoi du nonce (`None` tant qu'il n'est pas parti)
+    sent_at: Option<Instant>,
+    /// Dernier packet reçu de l'adresse candidate
+    last_seen: Instant,
+}
+
+/// Résultat de l'observation de l'adresse source d'un packet client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressObservation {
+    /// Adresse enregistrée (ou premier enregistrement du client)
+    Known,
+    /// Nouvelle adresse mise au défi, l'ancienne reste utilisée jusqu'à la réponse
+    Challenge { address: SocketAddr },
+    /// Nouvelle adresse dont le défi est en cours
+    Pending,
+}
+
+/// Changement d'état de parole à diffuser aux clients
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakingChange {
+    pub user_id: Uuid,
+    pub channel_id: Uuid,
+    pub is_speaking: bool,
 }
 
 impl AudioRouter {
@@ -74,25 +391,171 @@ impl AudioRouter {
         Self {
             user_buffers: DashMap::new(),
             client_addresses: DashMap::new(),
+            client_last_seen: DashMap::new(),
+            address_challenges: DashMap::new(),
+            tunnels: DashMap::new(),
+            link_quality: DashMap::new(),
+            latency: DashMap::new(),
+            uplink: DashMap::new(),
+            channel_profiles: DashMap::new(),
             stats: Arc::new(DashMap::new()),
             channel_configs: DashMap::new(),
+            channel_defaults: RwLock::new(ChannelRoutingConfig::default()),
             channel_sync: DashMap::new(),
+            voice_activity: DashMap::new(),
+            muted_users: DashSet::new(),
+            deafened_users: DashSet::new(),
+            speaking: DashMap::new(),
+            latency_histogram: OnceLock::new(),
+            mix_tap: OnceLock::new(),
         }
     }
 
+    /// Branche l'histogramme de latence qui reçoit le transit de chaque packet audio
+    pub fn set_latency_histogram(&self, histogram: Arc<Mutex<LatencyHistogram>>) {
+        let _ = self.latency_histogram.set(histogram);
+    }
+
+    /// Branche la file du mixage de supervision ; une file pleine perd la copie, jamais le packet routé
+    pub fn set_mix_tap(&self, tap: mpsc::Sender<AudioPacket>) {
+        let _ = self.mix_tap.set(tap);
+    }
+
     /// Enregistre l'adresse d'un client
     pub fn register_client(&self, user_id: Uuid, address: SocketAddr) {
         // println!("📍 AudioRouter: Registering client {} at address {}", user_id, address);
         self.client_addresses.insert(user_id, address);
+        self.client_last_seen.insert(user_id, Instant::now());
         // println!("📍 AudioRouter: Client {} registered successfully. Total clients: {}", 
         //     user_id, self.client_addresses.len());
     }
 
+    /// Adresse UDP enregistrée d'un client
+    pub fn client_address(&self, user_id: &Uuid) -> Option<SocketAddr> {
+        self.client_addresses.get(user_id).map(|address| *address)
+    }
+
+    /// Prend en compte l'adresse source d'un packet. Une adresse différente de celle
+    /// enregistrée (rebinding NAT, changement de réseau) n'est adoptée qu'une fois que le
+    /// client en a renvoyé le nonce de son défi, transmis sur son WebSocket authentifié :
+    /// un tiers qui forge l'identifiant d'un utilisateur ne voit jamais ce nonce.
+    /// D'ici là l'audio part vers l'ancienne adresse.
+    pub fn observe_client(&self, user_id: Uuid, address: SocketAddr) -> AddressObservation {
+        match self.client_address(&user_id) {
+            None => {
+                self.register_client(user_id, address);
+                AddressObservation::Known
+            }
+            Some(current) if current == address => {
+                self.client_last_seen.insert(user_id, Instant::now());
+                AddressObservation::Known
+            }
+            Some(_) => match self.address_challenges.entry(user_id) {
+                Entry::Occupied(mut pending) if pending.get().address == address => {
+                    pending.get_mut().last_seen = Instant::now();
+                    AddressObservation::Pending
+                }
+                entry => {
+                    // Nouveau candidat (remplace un éventuel défi vers une autre adresse)
+                    entry.insert(AddressChallenge {
+                        address,
+                        nonce: Uuid::new_v4(),
+                        sent_at: None,
+                        last_seen: Instant::now(),
+                    });
+                    AddressObservation::Challenge { address }
+                }
+            },
+        }
+    }
+
+    /// Nonces à envoyer par le WebSocket de leur client : nouveaux défis, et défis restés sans
+    /// réponse depuis `ADDRESS_CHALLENGE_RETRY`. Les candidats muets sont abandonnés.
+    pub fn collect_address_challenges(&self) -> Vec<(Uuid, Uuid)> {
+        self.address_challenges.retain(|_, challenge| challenge.last_seen.elapsed() < ADDRESS_CHALLENGE_TIMEOUT);
+
+        let mut due = Vec::new();
+        for mut challenge in self.address_challenges.iter_mut() {
+            if challenge.sent_at.is_none_or(|sent_at| sent_at.elapsed() >= ADDRESS_CHALLENGE_RETRY) {
+                challenge.sent_at = Some(Instant::now());
+                due.push((*challenge.key(), challenge.nonce));
+            }
+        }
+        due
+    }
+
+    /// Adopte la nouvelle adresse si elle a renvoyé le nonce de son défi
+    pub fn confirm_client_address(&self, user_id: Uuid, address: SocketAddr, nonce: Uuid) -> bool {
+        let confirmed = self.address_challenges
+            .remove_if(&user_id, |_, challenge| challenge.address == address && challenge.nonce == nonce)
+            .is_some();
+        if confirmed {
+            self.register_client(user_id, address);
+        }
+        confirmed
+    }
+
+    /// Fait passer l'audio destiné à ce client par son WebSocket
+    pub fn register_tunnel(&self, user_id: Uuid, sender: TunnelSender) {
+        self.tunnels.insert(user_id, sender);
+    }
+
+    /// Revient à l'UDP pour ce client, sauf si une autre connexion a enregistré son propre tunnel
+    pub fn unregister_tunnel(&self, user_id: &Uuid, sender: &TunnelSender) {
+        self.tunnels.remove_if(user_id, |_, current| current.same_channel(sender));
+    }
+
+    /// Le client émet de nouveau en UDP depuis son adresse vérifiée : l'audio qui lui est
+    /// destiné quitte le tunnel. Retourne `true` si un tunnel était actif.
+    pub fn release_tunnel(&self, user_id: &Uuid, from: SocketAddr) -> bool {
+        self.client_address(user_id) == Some(from) && self.tunnels.remove(user_id).is_some()
+    }
+
+    /// Indique si le client reçoit son audio par le tunnel WebSocket
+    pub fn has_tunnel(&self, user_id: &Uuid) -> bool {
+        self.tunnels.contains_key(user_id)
+    }
+
+    /// Livre un packet par le tunnel du client ; retourne `false` s'il n'en a pas
+    /// (l'audio part alors en UDP). Un tunnel saturé perd le packet, comme l'UDP.
+    pub fn send_via_tunnel(&self, user_id: &Uuid, packet: &AudioPacket) -> bool {
+        let Some(sender) = self.tunnels.get(user_id).map(|sender| sender.clone()) else {
+            return false;
+        };
+
+        let bytes = packet.to_bytes();
+        let len = bytes.len();
+        match sender.try_send(bytes) {
+            Ok(()) => self.record_packet_sent(&packet.header.channel_id, len),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                tracing::trace!("Tunnel of user {} is full, dropping packet", user_id);
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.tunnels.remove_if(user_id, |_, current| current.same_channel(&sender));
+                return false;
+            }
+        }
+        true
+    }
+
     /// Supprime un client
     pub fn unregister_client(&self, user_id: &Uuid) {
         self.client_addresses.remove(user_id);
+        self.client_last_seen.remove(user_id);
+        self.address_challenges.remove(user_id);
+        self.tunnels.remove(user_id);
+        self.link_quality.remove(user_id);
+        self.latency.remove(user_id);
+        self.uplink.remove(user_id);
+        for mut stats in self.stats.iter_mut() {
+            stats.user_latency.remove(user_id);
+        }
         // Supprimer les buffers de cet utilisateur
         self.user_buffers.retain(|(uid, _), _| uid != user_id);
+        self.voice_activity.remove(user_id);
+        self.muted_users.remove(user_id);
+        self.deafened_users.remove(user_id);
+        self.speaking.remove(user_id);
     }
 
     /// Ajoute un utilisateur à un channel
@@ -102,6 +565,10 @@ impl AudioRouter {
             self.user_buffers.insert(key, AudioBuffer::new(1024, 48000)); // 1KB buffer à 48kHz
         }
 
+        // Rejoindre un channel remet à zéro le délai d'inactivité et lève le mute serveur
+        self.voice_activity.insert(user_id, Instant::now());
+        self.muted_users.remove(&user_id);
+
         // Mettre à jour les statistiques
         self.stats.entry(channel_id).or_insert_with(|| RoutingStats {
             packets_received: 0,
@@ -114,6 +581,7 @@ impl AudioRouter {
             average_latency_ms: 0.0,
             packet_loss_rate: 0.0,
             jitter_ms: 0.0,
+            user_latency: HashMap::new(),
             created_at: std::time::Instant::now(),
         }).connected_users += 1;
     }
@@ -123,11 +591,18 @@ impl AudioRouter {
         let key = (*user_id, *channel_id);
         self.user_buffers.remove(&key);
 
+        if !self.user_buffers.iter().any(|entry| entry.key().0 == *user_id) {
+            self.voice_activity.remove(user_id);
+        }
+        self.speaking.remove_if(user_id, |_, state| state.channel_id == *channel_id);
+
         // Mettre à jour les statistiques
         if let Some(mut stats) = self.stats.get_mut(channel_id) {
             if stats.connected_users > 0 {
                 stats.connected_users -= 1;
             }
+            stats.user_latency.remove(user_id);
+            stats.average_latency_ms = stats.end_to_end_latency_ms();
         }
     }
 
@@ -136,6 +611,15 @@ impl AudioRouter {
         // println!("🔀 AudioRouter: Routing packet from user {} in channel {}", from_user, channel_id);
         
         let mut destinations = Vec::new();
+
+        // L'audio d'un utilisateur muté par le serveur n'est pas redistribué
+        if self.muted_users.contains(&from_user) {
+            return destinations;
+        }
+        if let Some(tap) = self.mix_tap.get() {
+            let _ = tap.try_send(packet.clone());
+        }
+
         let packets_received = 1_u64;
         let mut packets_routed = 0_u64;
 
@@ -143,12 +627,14 @@ impl AudioRouter {
         // println!("🔀 AudioRouter: Searching for users in channel {}", channel_id);
         for entry in self.user_buffers.iter() {
             let (user_id, ch_id) = entry.key();
-            if *ch_id == channel_id && *user_id != from_user {
+            if *ch_id == channel_id && *user_id != from_user && !self.deafened_users.contains(user_id) {
                 // println!("🎯 AudioRouter: Found target user {} in channel {}", user_id, ch_id);
                 packets_routed += 1;
                 
                 // Récupérer l'adresse de destination
-                if let Some(addr) = self.client_addresses.get(user_id) {
+                if self.send_via_tunnel(user_id, packet) {
+                    // Livré directement par le tunnel WebSocket
+                } else if let Some(addr) = self.client_addresses.get(user_id) {
                     // println!("📤 AudioRouter: Routing to user {} at {}", user_id, addr.value());
                     destinations.push(*addr.value());
                 } else {
@@ -167,11 +653,6 @@ impl AudioRouter {
             stats.packets_received += packets_received;
             stats.packets_routed += packets_routed;
             stats.bytes_received += packet.payload.len() as u64;
-            
-            // Calculer quelques métriques basiques
-            if stats.packets_received > 0 {
-                stats.packet_loss_rate = 1.0 - (stats.packets_routed as f32 / stats.packets_received as f32);
-            }
         }
 
         destinations
@@ -205,6 +686,45 @@ impl AudioRouter {
         self.stats.get(channel_id).map(|stats| stats.clone())
     }
 
+    /// Comptabilise un packet effectivement envoyé pour un channel
+    pub fn record_packet_sent(&self, channel_id: &Uuid, bytes: usize) {
+        if let Some(mut stats) = self.stats.get_mut(channel_id) {
+            stats.packets_sent += 1;
+            stats.bytes_sent += bytes as u64;
+        }
+    }
+
+    /// Occupation des buffers : (nombre de buffers, remplissage moyen 0-1, packets perdus)
+    pub fn buffer_stats(&self) -> (usize, f32, u64) {
+        let mut total_buffers = 0;
+        let mut fill_sum = 0.0;
+        let mut dropped = 0;
+
+        for entry in self.user_buffers.iter() {
+            let stats = entry.value().stats().audio_buffer;
+            total_buffers += 1;
+            if stats.capacity > 0 {
+                fill_sum += stats.current_size as f32 / stats.capacity as f32;
+            }
+            dropped += stats.dropped_packets as u64;
+        }
+
+        let average_fill = if total_buffers > 0 { fill_sum / total_buffers as f32 } else { 0.0 };
+        (total_buffers, average_fill, dropped)
+    }
+
+    /// Nombre d'utilisateurs en train de parler
+    pub fn speaking_count(&self) -> usize {
+        self.speaking.iter().filter(|entry| entry.value().announced).count()
+    }
+
+    /// Récupère les statistiques de tous les channels
+    pub fn all_channel_stats(&self) -> Vec<(Uuid, RoutingStats)> {
+        self.stats.iter()
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
     /// Récupère la liste des utilisateurs connectés à un channel
     pub fn get_channel_users(&self, channel_id: &Uuid) -> Vec<Uuid> {
         self.user_buffers.iter()
@@ -230,12 +750,101 @@ impl AudioRouter {
         }
     }
 
+    /// Enregistre une activité vocale pour un utilisateur
+    pub fn record_voice_activity(&self, user_id: Uuid, channel_id: Uuid) {
+        let now = Instant::now();
+        self.voice_activity.insert(user_id, now);
+
+        let mut state = self.speaking.entry(user_id).or_insert(SpeakingState {
+            channel_id,
+            last_audio: now,
+            announced: false,
+        });
+        if state.channel_id != channel_id {
+            *state = SpeakingState { channel_id, last_audio: now, announced: false };
+        }
+        state.last_audio = now;
+    }
+
+    /// Indique si un utilisateur est en train de parler
+    pub fn is_user_speaking(&self, user_id: &Uuid) -> bool {
+        self.speaking.get(user_id).is_some_and(|state| state.announced)
+    }
+
+    /// Calcule les changements d'état de parole depuis le dernier appel.
+    /// Un utilisateur arrête de parler après `hangover` sans packet audio.
+    pub fn collect_speaking_changes(&self, hangover: Duration) -> Vec<SpeakingChange> {
+        let mut changes = Vec::new();
+
+        for mut entry in self.speaking.iter_mut() {
+            let user_id = *entry.key();
+            let state = entry.value_mut();
+            if !state.announced && state.last_audio.elapsed() < hangover {
+                state.announced = true;
+                changes.push(SpeakingChange { user_id, channel_id: state.channel_id, is_speaking: true });
+            }
+        }
+
+        self.speaking.retain(|user_id, state| {
+            if state.last_audio.elapsed() < hangover {
+                return true;
+            }
+            if state.announced {
+                changes.push(SpeakingChange { user_id: *user_id, channel_id: state.channel_id, is_speaking: false });
+            }
+            false
+        });
+
+        changes
+    }
+
+    /// Durée écoulée depuis la dernière activité vocale d'un utilisateur
+    pub fn idle_duration(&self, user_id: &Uuid) -> Option<Duration> {
+        self.voice_activity.get(user_id).map(|last| last.elapsed())
+    }
+
+    /// Liste les utilisateurs sans activité vocale depuis au moins `timeout`
+    pub fn idle_users(&self, timeout: Duration) -> Vec<Uuid> {
+        self.voice_activity.iter()
+            .filter(|entry| entry.value().elapsed() >= timeout)
+            .map(|entry| *entry.key())
+            .collect()
+    }
+
+    /// Coupe ou rétablit le routage de l'audio d'un utilisateur
+    pub fn set_user_muted(&self, user_id: Uuid, muted: bool) {
+        if muted {
+            self.muted_users.insert(user_id);
+        } else {
+            self.muted_users.remove(&user_id);
+        }
+    }
+
+    /// Indique si l'audio d'un utilisateur est coupé par le serveur
+    pub fn is_user_muted(&self, user_id: &Uuid) -> bool {
+        self.muted_users.contains(user_id)
+    }
+
+    /// Coupe ou rétablit l'envoi de l'audio du channel à un utilisateur
+    pub fn set_user_deafened(&self, user_id: Uuid, deafened: bool) {
+        if deafened {
+            self.deafened_users.insert(user_id);
+        } else {
+            self.deafened_users.remove(&user_id);
+        }
+    }
+
+    pub fn is_user_deafened(&self, user_id: &Uuid) -> bool {
+        self.deafened_users.contains(user_id)
+    }
+
     /// Nettoie les ressources pour un channel
     pub fn cleanup_channel(&self, channel_id: &Uuid) {
         // Supprimer tous les buffers du channel
         self.user_buffers.retain(|(_, ch_id), _| ch_id != channel_id);
         // Supprimer les statistiques
         self.stats.remove(channel_id);
+        self.channel_profiles.remove(channel_id);
     }
 
     /// Récupère les métriques globales
@@ -247,6 +856,320 @@ impl AudioRouter {
         (total_clients, total_channels, total_buffers)
     }
 
+    /// Supprime les clients, buffers et statistiques qui référencent des utilisateurs
+    /// ou des channels disparus. Retourne (clients, buffers, channels) supprimés.
+    pub fn cleanup_stale<U, C>(&self, user_exists: U, channel_exists: C) -> (usize, usize, usize)
+    where
+        U: Fn(&Uuid) -> bool,
+        C: Fn(&Uuid) -> bool,
+    {
+        let clients_before = self.client_addresses.len();
+        self.client_addresses.retain(|user_id, _| user_exists(user_id));
+        self.client_last_seen.retain(|user_id, _| user_exists(user_id));
+        self.address_challenges.retain(|user_id, _| user_exists(user_id));
+        self.tunnels.retain(|user_id, _| user_exists(user_id));
+        self.link_quality.retain(|user_id, _| user_exists(user_id));
+        self.latency.retain(|user_id, _| user_exists(user_id));
+        self.uplink.retain(|user_id, _| user_exists(user_id));
+        for mut stats in self.stats.iter_mut() {
+            stats.user_latency.retain(|user_id, _| user_exists(user_id));
+        }
+        self.voice_activity.retain(|user_id, _| user_exists(user_id));
+        self.muted_users.retain(|user_id| user_exists(user_id));
+        self.deafened_users.retain(|user_id| user_exists(user_id));
+        self.speaking.retain(|user_id, _| user_exists(user_id));
+
+        let buffers_before = self.user_buffers.len();
+        self.user_buffers.retain(|(user_id, channel_id), _| user_exists(user_id) && channel_exists(channel_id));
+
+        let channels_before = self.stats.len();
+        self.stats.retain(|channel_id, _| channel_exists(channel_id));
+        self.channel_configs.retain(|channel_id, _| channel_exists(channel_id));
+        self.channel_sync.retain(|channel_id, _| channel_exists(channel_id));
+        self.channel_profiles.retain(|channel_id, _| channel_exists(channel_id));
+
+        (
+            clients_before - self.client_addresses.len(),
+            buffers_before - self.user_buffers.len(),
+            channels_before - self.stats.len(),
+        )
+    }
+
+    /// Oublie les adresses des clients muets depuis plus de `timeout` (client arrêté,
+    /// réseau coupé) ; le prochain heartbeat les réenregistre. Retourne les clients expirés.
+    pub fn expire_clients(&self, timeout: Duration) -> Vec<Uuid> {
+        let expired: Vec<Uuid> = self.client_last_seen.iter()
+            .filter(|entry| entry.value().elapsed() > timeout)
+            .map(|entry| *entry.key())
+            .collect();
+
+        for user_id in &expired {
+            self.client_addresses.remove(user_id);
+            self.client_last_seen.remove(user_id);
+            self.address_challenges.remove(user_id);
+        }
+        expired
+    }
+
+    /// Intègre le rapport de réception d'un client ; les statistiques du channel reflètent
+    /// ensuite son lien le plus faible
+    pub fn record_receiver_report(&self, user_id: Uuid, channel_id: Uuid, report: &ReceiverReport) -> LinkQuality {
+        let sample = LinkQuality {
+            loss_rate: report.loss_rate(),
+            jitter_ms: report.jitter_ms,
+            rtt_ms: report.rtt_ms,
+        };
+        let quality = match self.link_quality(&user_id) {
+            Some(previous) => LinkQuality {
+                loss_rate: previous.loss_rate + (sample.loss_rate - previous.loss_rate) * LINK_SMOOTHING,
+                jitter_ms: previous.jitter_ms + (sample.jitter_ms - previous.jitter_ms) * LINK_SMOOTHING,
+                // RTT inconnu dans ce rapport : garder le précédent
+                rtt_ms: if sample.rtt_ms > 0.0 {
+                    previous.rtt_ms + (sample.rtt_ms - previous.rtt_ms) * LINK_SMOOTHING
+                } else {
+                    previous.rtt_ms
+                },
+            },
+            None => sample,
+        };
+        self.link_quality.insert(user_id, (quality, Instant::now()));
+
+        if let Some(weakest) = self.weakest_link(&channel_id) {
+            if let Some(mut stats) = self.stats.get_mut(&channel_id) {
+                stats.packet_loss_rate = weakest.loss_rate;
+                stats.jitter_ms = weakest.jitter_ms;
+            }
+        }
+        quality
+    }
+
+    /// Intègre l'aller-retour et le décalage d'horloge mesurés par un client
+    pub fn record_clock_estimate(&self, user_id: Uuid, channel_id: Uuid, estimate: &ClockEstimate) {
+        self.update_latency(user_id, channel_id, |latency| {
+            latency.rtt_ms = estimate.rtt_us as f32 / 1000.0;
+            latency.clock_offset_ms = estimate.offset_us as f32 / 1000.0;
+        });
+    }
+
+    /// Mesure le transit d'un packet audio une fois l'horloge de l'émetteur connue
+    pub fn record_uplink_latency(&self, user_id: Uuid, channel_id: Uuid, header: &AudioHeader) {
+        let Some(offset_ms) = self.latency.get(&user_id).map(|latency| latency.clock_offset_ms) else {
+            return;
+        };
+        let transit_ms = (header.transit_micros((offset_ms * 1000.0) as i64).max(0) as f32) / 1000.0;
+        if let Some(histogram) = self.latency_histogram.get() {
+            histogram.lock().unwrap().observe(transit_ms as f64);
+        }
+        self.update_latency(user_id, channel_id, |latency| {
+            latency.one_way_ms = if latency.one_way_ms > 0.0 {
+                latency.one_way_ms + (transit_ms - latency.one_way_ms) * LATENCY_SMOOTHING
+            } else {
+                transit_ms
+            };
+        });
+    }
+
+    fn update_latency(&self, user_id: Uuid, channel_id: Uuid, update: impl FnOnce(&mut UserLatency)) {
+        let latency = {
+            let mut latency = self.latency.entry(user_id).or_default();
+            update(&mut latency);
+            *latency
+        };
+        if let Some(mut stats) = self.stats.get_mut(&channel_id) {
+            stats.user_latency.insert(user_id, latency);
+            stats.average_latency_ms = stats.end_to_end_latency_ms();
+        }
+    }
+
+    /// Comptabilise un packet audio reçu d'un client : pertes, gigue et débit par fenêtre
+    pub fn record_uplink_audio(&self, user_id: Uuid, packet: &AudioPacket) {
+        let header = &packet.header;
+        let transit_us = header.transit_micros(0);
+        let mut stats = match self.uplink.entry(user_id) {
+            Entry::Occupied(entry) => entry.into_ref(),
+            Entry::Vacant(entry) => {
+                entry.insert(UplinkStats::new(packet, transit_us));
+                return;
+            }
+        };
+
+        let behind = stats.highest_sequence.wrapping_sub(header.sequence);
+        if behind > SEQUENCE_RESTART_GAP && behind < u32::MAX / 2 {
+            // Le client a recommencé sa numérotation (nouveau socket)
+            *stats = UplinkStats::new(packet, transit_us);
+            return;
+        }
+        if header.sequence.wrapping_sub(stats.highest_sequence) < u32::MAX / 2 {
+            stats.highest_sequence = header.sequence;
+        }
+        stats.window_received += 1;
+        stats.window_bytes += packet.payload.len() as u64;
+        let delta = (transit_us - stats.last_transit_us).unsigned_abs() as f32;
+        stats.jitter_us += (delta - stats.jitter_us) / 16.0;
+        stats.last_transit_us = transit_us;
+        stats.last_packet = Instant::now();
+        stats.sample_rate = header.sample_rate;
+        stats.channels = header.channels;
+
+        let elapsed = stats.window_start.elapsed();
+        if elapsed >= UPLINK_WINDOW {
+            let expected = stats.highest_sequence.wrapping_sub(stats.window_base);
+            stats.loss_rate = if expected == 0 {
+                0.0
+            } else {
+                expected.saturating_sub(stats.window_received) as f32 / expected as f32
+            };
+            stats.bitrate_kbps = stats.window_bytes as f32 * 8.0 / elapsed.as_secs_f32() / 1000.0;
+            stats.window_base = stats.highest_sequence;
+            stats.window_start = Instant::now();
+            stats.window_received = 0;
+            stats.window_bytes = 0;
+        }
+    }
+
+    /// Qualité de connexion d'un client, des deux sens de son lien UDP
+    pub fn connection_quality(&self, user_id: &Uuid) -> Option<ConnectionQuality> {
+        let uplink = self.uplink.get(user_id).map(|stats| stats.clone());
+        let latency = self.user_latency(user_id);
+        let downlink = self.link_quality(user_id);
+        if uplink.is_none() && latency.is_none() && downlink.is_none() {
+            return None;
+        }
+
+        let downlink = downlink.unwrap_or_default();
+        let rtt_ms = latency.map(|latency| latency.rtt_ms)
+            .filter(|rtt_ms| *rtt_ms > 0.0)
+            .or(Some(downlink.rtt_ms).filter(|rtt_ms| *rtt_ms > 0.0));
+        // Les mesures de l'audio émis ne valent que tant que l'utilisateur parle
+        let speaking = uplink.as_ref().filter(|stats| stats.last_packet.elapsed() < UPLINK_IDLE);
+        let uplink_loss = speaking.map(|stats| stats.loss_rate).unwrap_or_default();
+        let loss_percent = uplink_loss.max(downlink.loss_rate) * 100.0;
+        let uplink_jitter = speaking.map(|stats| stats.jitter_us / 1000.0).unwrap_or_default();
+        let jitter_ms = uplink_jitter.max(downlink.jitter_ms);
+
+        Some(ConnectionQuality {
+            user_id: *user_id,
+            rtt_ms: rtt_ms.unwrap_or_default(),
+            loss_percent,
+            jitter_ms,
+            codec: AUDIO_CODEC.to_string(),
+            sample_rate: uplink.as_ref().map(|stats| stats.sample_rate).unwrap_or_default(),
+            channels: uplink.as_ref().map(|stats| stats.channels).unwrap_or_default(),
+            bitrate_kbps: speaking.map(|stats| stats.bitrate_kbps).unwrap_or_default(),
+            signal: ConnectionQuality::signal_bars(rtt_ms, loss_percent, jitter_ms),
+        })
+    }
+
+    /// Qualité de connexion des membres de chaque channel occupé
+    pub fn collect_connection_quality(&self) -> Vec<(Uuid, Vec<ConnectionQuality>)> {
+        let mut channels: HashMap<Uuid, Vec<ConnectionQuality>> = HashMap::new();
+        let members: Vec<(Uuid, Uuid)> = self.user_buffers.iter().map(|entry| *entry.key()).collect();
+        for (user_id, channel_id) in members {
+            if let Some(quality) = self.connection_quality(&user_id) {
+                channels.entry(channel_id).or_default().push(quality);
+            }
+        }
+        channels.into_iter().collect()
+    }
+
+    /// Latence mesurée d'un client
+    pub fn user_latency(&self, user_id: &Uuid) -> Option<UserLatency> {
+        self.latency.get(user_id).map(|latency| *latency)
+    }
+
+    /// Qualité de réception récente d'un client
+    pub fn link_quality(&self, user_id: &Uuid) -> Option<LinkQuality> {
+        self.link_quality.get(user_id)
+            .filter(|entry| entry.1.elapsed() < LINK_REPORT_TTL)
+            .map(|entry| entry.0)
+    }
+
+    /// Pire qualité de réception parmi les membres d'un channel
+    pub fn weakest_link(&self, channel_id: &Uuid) -> Option<LinkQuality> {
+        let members: Vec<Uuid> = self.user_buffers.iter()
+            .filter(|entry| entry.key().1 == *channel_id)
+            .map(|entry| entry.key().0)
+            .collect();
+        members.iter()
+            .filter_map(|user_id| self.link_quality(user_id))
+            .reduce(LinkQuality::worst)
+    }
+
+    /// Profil d'encodage adapté au channel : imposé par son mode configuré, sinon (mode
+    /// adaptatif, celui des channels non configurés) suivant son lien le plus faible,
+    /// toujours borné au débit du channel
+    pub fn recommended_profile(&self, channel_id: &Uuid) -> EncodingProfile {
+        let config = self.channel_config(channel_id);
+        let profile = match config.quality_mode {
+            QualityMode::Adaptive => {
+                let mode = self.weakest_link(channel_id)
+                    .map(|link| link.sustainable_mode())
+                    .unwrap_or(QualityMode::High);
+                EncodingProfile::for_mode(mode)
+            }
+            mode => EncodingProfile::for_mode(mode),
+        };
+        profile.capped(config.bitrate_kbps)
+    }
+
+    /// Profil annoncé d'un channel (recommandé s'il n'a pas encore été annoncé)
+    pub fn channel_profile(&self, channel_id: &Uuid) -> EncodingProfile {
+        self.channel_profiles.get(channel_id)
+            .map(|profile| *profile)
+            .unwrap_or_else(|| self.recommended_profile(channel_id))
+    }
+
+    /// Profils à annoncer depuis le dernier appel. Une dégradation s'applique tout de suite,
+    /// une amélioration remonte d'un seul palier par appel pour éviter les oscillations.
+    pub fn collect_profile_changes(&self) -> Vec<(Uuid, EncodingProfile)> {
+        let channels: Vec<Uuid> = self.stats.iter().map(|entry| *entry.key()).collect();
+        let mut changes = Vec::new();
+
+        for channel_id in channels {
+            let target = self.recommended_profile(&channel_id);
+            let current = self.channel_profiles.get(&channel_id).map(|profile| *profile);
+            let next = match current {
+                Some(current) if target.rank() > current.rank() => {
+                    current.step_up().capped(self.channel_config(&channel_id).bitrate_kbps)
+                }
+                _ => target,
+            };
+            if current != Some(next) {
+                self.channel_profiles.insert(channel_id, next);
+                changes.push((channel_id, next));
+            }
+        }
+        changes
+    }
+
+    /// Cumul du trafic reçu sur tous les channels : (packets, bytes reçus + envoyés)
+    pub fn total_traffic(&self) -> (u64, u64) {
+        self.stats.iter().fold((0, 0), |(packets, bytes), entry| {
+            let stats = entry.value();
+            (packets + stats.packets_received, bytes + stats.bytes_received + stats.bytes_sent)
+        })
+    }
+
+    /// Réinitialise complètement l'état de routage
+    pub fn reset(&self) {
+        self.user_buffers.clear();
+        self.client_addresses.clear();
+        self.client_last_seen.clear();
+        self.address_challenges.clear();
+        self.tunnels.clear();
+        self.link_quality.clear();
+        self.latency.clear();
+        self.uplink.clear();
+        self.channel_profiles.clear();
+        self.stats.clear();
+        self.channel_configs.clear();
+        self.channel_sync.clear();
+        self.voice_activity.clear();
+        self.muted_users.clear();
+        self.deafened_users.clear();
+        self.speaking.clear();
+    }
+
     /// Nettoie périodiquement les buffers (pour UDP server)
     pub fn cleanup_buffers(&self) {
         // Nettoyer les packets expirés dans tous les buffers
@@ -256,18 +1179,18 @@ impl AudioRouter {
     }
 
     /// Recoit un packet et détermine s'il doit être traité
-    pub fn receive_packet(&self, _packet: AudioPacket, _from_addr: SocketAddr) -> bool {
+    pub fn receive_packet(&self, _packet: AudioPacket, _from_addr: Option<SocketAddr>) -> bool {
         // Pour l'instant, accepter tous les packets
         // TODO: Validation de packet, limitation de débit, etc.
         true
     }
 
-    /// Route vers un channel (version simplifiée)
+    /// Route vers un channel selon sa configuration de routage courante
     pub fn route_to_channel(&self, packet: AudioPacket) -> Vec<SocketAddr> {
         let user_id = packet.header.user_id;
         let channel_id = packet.header.channel_id;
         
-        self.route_packet(&packet, user_id, channel_id)
+        self.intelligent_route(&packet, user_id, channel_id)
     }
 
     /// Récupère les packets prêts pour un utilisateur
@@ -285,19 +1208,30 @@ impl AudioRouter {
         self.channel_configs.get(channel_id).map(|config| config.clone())
     }
 
+    /// Configuration effective d'un channel : la sienne, sinon celle par défaut du serveur
+    pub fn channel_config(&self, channel_id: &Uuid) -> ChannelRoutingConfig {
+        self.get_channel_config(channel_id)
+            .unwrap_or_else(|| self.channel_defaults())
+    }
+
+    /// Configuration des channels sans configuration propre
+    pub fn channel_defaults(&self) -> ChannelRoutingConfig {
+        self.channel_defaults.read().unwrap().clone()
+    }
+
+    /// Remplace la configuration des channels sans configuration propre
+    pub fn set_channel_defaults(&self, config: ChannelRoutingConfig) {
+        *self.channel_defaults.write().unwrap() = config;
+    }
+
     /// Route avec intelligence adaptative
     pub fn intelligent_route(&self, packet: &AudioPacket, from_user: Uuid, channel_id: Uuid) -> Vec<SocketAddr> {
+        if self.muted_users.contains(&from_user) {
+            return Vec::new();
+        }
+
         // Récupérer la configuration du channel
-        let config = self.get_channel_config(&channel_id).unwrap_or_else(|| {
-            ChannelRoutingConfig {
-                max_users: 10,
-                quality_mode: QualityMode::Medium,
-                latency_target_ms: 50,
-                enable_echo_cancellation: false,
-                enable_noise_suppression: false,
-                bitrate_kbps: 64,
-            }
-        });
+        let config = self.channel_config(&channel_id);
 
         // Mettre à jour la synchronisation
         self.channel_sync.insert(channel_id, packet.header.timestamp);
@@ -315,19 +1249,22 @@ impl AudioRouter {
     fn route_low_quality(&self, packet: &AudioPacket, from_user: Uuid, channel_id: Uuid) -> Vec<SocketAddr> {
         // Ne router que vers les utilisateurs actifs récemment
         let mut destinations = Vec::new();
+        let mut tunneled = 0;
 
         for entry in self.user_buffers.iter() {
             let (user_id, ch_id) = entry.key();
-            if *ch_id == channel_id && *user_id != from_user {
+            if *ch_id == channel_id && *user_id != from_user && !self.deafened_users.contains(user_id) {
                 // Vérifier l'activité récente (simplifiée pour l'instant)
-                if let Some(addr) = self.client_addresses.get(user_id) {
+                if self.send_via_tunnel(user_id, packet) {
+                    tunneled += 1;
+                } else if let Some(addr) = self.client_addresses.get(user_id) {
                     destinations.push(*addr.value());
                 }
             }
         }
 
         // Mettre à jour les stats
-        self.update_routing_stats(&channel_id, 1, destinations.len(), packet.payload.len());
+        self.update_routing_stats(&channel_id, 1, destinations.len() + tunneled, packet.payload.len());
         destinations
     }
 
@@ -346,21 +1283,11 @@ impl AudioRouter {
 
     /// Routage adaptatif selon les conditions réseau
     fn route_adaptive(&self, packet: &AudioPacket, from_user: Uuid, channel_id: Uuid) -> Vec<SocketAddr> {
-        // Analyser les conditions actuelles
-        if let Some(stats) = self.stats.get(&channel_id) {
-            if stats.packet_loss_rate > 5.0 || stats.jitter_ms > 100.0 {
-                // Conditions dégradées -> mode faible qualité
-                self.route_low_quality(packet, from_user, channel_id)
-            } else if stats.packet_loss_rate < 1.0 && stats.jitter_ms < 20.0 {
-                // Bonnes conditions -> haute qualité
-                self.route_high_quality(packet, from_user, channel_id)
-            } else {
-                // Conditions moyennes -> mode standard
-                self.route_packet(packet, from_user, channel_id)
-            }
-        } else {
-            // Pas de stats -> mode par défaut
-            self.route_packet(packet, from_user, channel_id)
+        // Suivre le niveau annoncé au channel d'après les rapports de réception
+        match self.channel_profile(&channel_id).quality {
+            QualityMode::Low => self.route_low_quality(packet, from_user, channel_id),
+            QualityMode::High => self.route_high_quality(packet, from_user, channel_id),
+            _ => self.route_packet(packet, from_user, channel_id),
         }
     }
 
@@ -370,11 +1297,6 @@ impl AudioRouter {
             stats.packets_received += packets_received as u64;
             stats.packets_routed += packets_routed as u64;
             stats.bytes_received += bytes as u64;
-            
-            // Calculer le taux de perte
-            if stats.packets_received > 0 {
-                stats.packet_loss_rate = 1.0 - (stats.packets_routed as f32 / stats.packets_received as f32);
-            }
         }
     }
 
@@ -421,7 +1343,7 @@ impl AudioRouter {
     fn generate_recommendations(&self, stats: &RoutingStats, config: &Option<ChannelRoutingConfig>) -> Vec<String> {
         let mut recommendations = Vec::new();
 
-        if stats.packet_loss_rate > 5.0 {
+        if stats.packet_loss_rate > 0.05 {
             recommendations.push("Taux de perte élevé - Considérer réduire la qualité audio".to_string());
         }
 
@@ -434,7 +1356,7 @@ impl AudioRouter {
         }
 
         if let Some(cfg) = config {
-            if cfg.quality_mode == QualityMode::High && stats.packet_loss_rate > 2.0 {
+            if cfg.quality_mode == QualityMode::High && stats.packet_loss_rate > 0.02 {
                 recommendations.push("Mode haute qualité non optimal - Basculer en mode adaptatif".to_string());
             }
         }
@@ -445,4 +1367,326 @@ impl AudioRouter {
 
         recommendations
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_tracking() {
+        let router = AudioRouter::new();
+        let user_id = Uuid::new_v4();
+        let channel_id = Uuid::new_v4();
+
+        assert!(router.idle_duration(&user_id).is_none());
+
+        router.add_user_to_channel(user_id, channel_id);
+        assert!(router.idle_users(Duration::from_secs(60)).is_empty());
+        assert_eq!(router.idle_users(Duration::ZERO), vec![user_id]);
+
+        router.remove_user_from_channel(&user_id, &channel_id);
+        assert!(router.idle_duration(&user_id).is_none());
+    }
+
+    #[test]
+    fn test_expire_clients() {
+        let router = AudioRouter::new();
+        let user_id = Uuid::new_v4();
+        router.register_client(user_id, "127.0.0.1:5000".parse().unwrap());
+
+        assert!(router.expire_clients(Duration::from_secs(60)).is_empty());
+        assert_eq!(router.expire_clients(Duration::ZERO), vec![user_id]);
+        assert!(router.expire_clients(Duration::ZERO).is_empty());
+        assert!(!router.client_addresses.contains_key(&user_id));
+    }
+
+    #[test]
+    fn test_address_rebinding_requires_challenge() {
+        let router = AudioRouter::new();
+        let user_id = Uuid::new_v4();
+        let old_addr: SocketAddr = "203.0.113.5:40000".parse().unwrap();
+        let new_addr: SocketAddr = "203.0.113.5:41000".parse().unwrap();
+
+        assert_eq!(router.observe_client(user_id, old_addr), AddressObservation::Known);
+
+        // Nouvelle adresse : défi, l'ancienne reste active
+        assert_eq!(router.observe_client(user_id, new_addr), AddressObservation::Challenge { address: new_addr });
+        assert_eq!(router.observe_client(user_id, new_addr), AddressObservation::Pending);
+        assert_eq!(router.client_address(&user_id), Some(old_addr));
+
+        // Le nonce n'est remis qu'une fois au WebSocket du client, puis après le délai de relance
+        let challenges = router.collect_address_challenges();
+        assert_eq!(challenges.len(), 1);
+        let (challenged, nonce) = challenges[0];
+        assert_eq!(challenged, user_id);
+        assert!(router.collect_address_challenges().is_empty());
+
+        // Mauvais nonce ou mauvaise adresse : refusé
+        assert!(!router.confirm_client_address(user_id, new_addr, Uuid::new_v4()));
+        assert!(!router.confirm_client_address(user_id, "198.51.100.1:41000".parse().unwrap(), nonce));
+
+        assert!(router.confirm_client_address(user_id, new_addr, nonce));
+        assert_eq!(router.client_address(&user_id), Some(new_addr));
+        assert_eq!(router.observe_client(user_id, new_addr), AddressObservation::Known);
+        assert!(!router.confirm_client_address(user_id, new_addr, nonce));
+    }
+
+    #[test]
+    fn test_third_party_cannot_confirm_address() {
+        let router = AudioRouter::new();
+        let victim = Uuid::new_v4();
+        let victim_addr: SocketAddr = "203.0.113.5:40000".parse().unwrap();
+        let attacker_addr: SocketAddr = "198.51.100.7:50000".parse().unwrap();
+        router.observe_client(victim, victim_addr);
+
+        // Le tiers forge l'identifiant de la victime depuis sa propre adresse : le défi
+        // est ouvert, mais son nonce ne part que vers le WebSocket de la victime
+        assert_eq!(router.observe_client(victim, attacker_addr), AddressObservation::Challenge { address: attacker_addr });
+        let (_, nonce) = router.collect_address_challenges()[0];
+
+        // Sans le nonce, le tiers ne peut que deviner
+        for _ in 0..16 {
+            assert!(!router.confirm_client_address(victim, attacker_addr, Uuid::new_v4()));
+        }
+        // La victime répond depuis sa vraie adresse : rien ne change
+        assert!(!router.confirm_client_address(victim, victim_addr, nonce));
+        assert_eq!(router.client_address(&victim), Some(victim_addr));
+
+        // Le défi échoué reste ouvert, l'adresse enregistrée continue de recevoir l'audio
+        assert_eq!(router.observe_client(victim, attacker_addr), AddressObservation::Pending);
+        assert_eq!(router.client_address(&victim), Some(victim_addr));
+    }
+
+    #[test]
+    fn test_speaking_changes() {
+        let router = AudioRouter::new();
+        let user_id = Uuid::new_v4();
+        let channel_id = Uuid::new_v4();
+
+        router.add_user_to_channel(user_id, channel_id);
+        router.record_voice_activity(user_id, channel_id);
+
+        let changes = router.collect_speaking_changes(Duration::from_secs(60));
+        assert_eq!(changes, vec![SpeakingChange { user_id, channel_id, is_speaking: true }]);
+        assert!(router.is_user_speaking(&user_id));
+
+        // Pas de nouveau changement tant que l'utilisateur parle
+        assert!(router.collect_speaking_changes(Duration::from_secs(60)).is_empty());
+
+        let changes = router.collect_speaking_changes(Duration::ZERO);
+        assert_eq!(changes, vec![SpeakingChange { user_id, channel_id, is_speaking: false }]);
+        assert!(!router.is_user_speaking(&user_id));
+    }
+
+    #[test]
+    fn test_muted_user_is_not_routed() {
+        let router = AudioRouter::new();
+        let channel_id = Uuid::new_v4();
+        let speaker = Uuid::new_v4();
+        let listener = Uuid::new_v4();
+
+        router.add_user_to_channel(speaker, channel_id);
+        router.add_user_to_channel(listener, channel_id);
+        router.register_client(listener, "127.0.0.1:9000".parse().unwrap());
+
+        let packet = AudioPacket::audio(speaker, channel_id, 1, bytes::Bytes::from(vec![0u8; 16]), 48000, 1);
+        assert_eq!(router.route_packet(&packet, speaker, channel_id).len(), 1);
+
+        router.set_user_muted(speaker, true);
+        assert!(router.route_packet(&packet, speaker, channel_id).is_empty());
+
+        // Rejoindre un channel lève le mute
+        router.add_user_to_channel(speaker, channel_id);
+        assert!(!router.is_user_muted(&speaker));
+    }
+
+    #[test]
+    fn test_deafened_user_receives_nothing() {
+        let router = AudioRouter::new();
+        let channel_id = Uuid::new_v4();
+        let speaker = Uuid::new_v4();
+        let listener = Uuid::new_v4();
+
+        router.add_user_to_channel(speaker, channel_id);
+        router.add_user_to_channel(listener, channel_id);
+        router.register_client(listener, "127.0.0.1:9000".parse().unwrap());
+        router.set_user_deafened(listener, true);
+
+        let packet = AudioPacket::audio(speaker, channel_id, 1, bytes::Bytes::from(vec![0u8; 16]), 48000, 1);
+        assert!(router.route_packet(&packet, speaker, channel_id).is_empty());
+        assert!(router.route_low_quality(&packet, speaker, channel_id).is_empty());
+
+        // La sourdine survit au changement de channel
+        router.add_user_to_channel(listener, channel_id);
+        assert!(router.is_user_deafened(&listener));
+        router.set_user_deafened(listener, false);
+        assert_eq!(router.route_packet(&packet, speaker, channel_id).len(), 1);
+    }
+
+    #[test]
+    fn test_tunnel_takes_precedence_over_udp() {
+        let router = AudioRouter::new();
+        let channel_id = Uuid::new_v4();
+        let speaker = Uuid::new_v4();
+        let listener = Uuid::new_v4();
+
+        router.add_user_to_channel(speaker, channel_id);
+        router.add_user_to_channel(listener, channel_id);
+        router.register_client(listener, "127.0.0.1:9000".parse().unwrap());
+        let (tunnel, mut tunnel_rx) = mpsc::channel(4);
+        router.register_tunnel(listener, tunnel.clone());
+
+        let packet = AudioPacket::audio(speaker, channel_id, 1, bytes::Bytes::from(vec![0u8; 16]), 48000, 1);
+        assert!(router.route_packet(&packet, speaker, channel_id).is_empty());
+        let delivered = AudioPacket::from_bytes(&tunnel_rx.try_recv().unwrap()).unwrap();
+        assert_eq!(delivered.header.user_id, speaker);
+
+        // Tunnel fermé : retour à l'UDP
+        router.unregister_tunnel(&listener, &tunnel);
+        assert_eq!(router.route_packet(&packet, speaker, channel_id).len(), 1);
+    }
+
+    #[test]
+    fn test_udp_audio_releases_tunnel() {
+        let router = AudioRouter::new();
+        let user_id = Uuid::new_v4();
+        let address: SocketAddr = "127.0.0.1:9000".parse().unwrap();
+        router.register_client(user_id, address);
+        let (tunnel, _tunnel_rx) = mpsc::channel(4);
+        router.register_tunnel(user_id, tunnel);
+
+        // Une adresse non vérifiée ne suffit pas à quitter le tunnel
+        assert!(!router.release_tunnel(&user_id, "127.0.0.1:9001".parse().unwrap()));
+        assert!(router.has_tunnel(&user_id));
+
+        assert!(router.release_tunnel(&user_id, address));
+        assert!(!router.has_tunnel(&user_id));
+        assert!(!router.release_tunnel(&user_id, address));
+    }
+
+    #[test]
+    fn test_receiver_reports_drive_channel_profile() {
+        let router = AudioRouter::new();
+        let channel_id = Uuid::new_v4();
+        let good = Uuid::new_v4();
+        let lossy = Uuid::new_v4();
+        router.add_user_to_channel(good, channel_id);
+        router.add_user_to_channel(lossy, channel_id);
+
+        // Non configuré : le routage et le profil partagent le même mode par défaut
+        assert!(router.get_channel_config(&channel_id).is_none());
+        assert_eq!(ChannelRoutingConfig::default().quality_mode, QualityMode::Adaptive);
+
+        let clean = ReceiverReport { packets_expected: 100, packets_lost: 0, jitter_ms: 5.0, rtt_ms: 40.0 };
+        router.record_receiver_report(good, channel_id, &clean);
+        assert_eq!(router.collect_profile_changes(), vec![(channel_id, EncodingProfile::for_mode(QualityMode::High))]);
+        assert!(router.collect_profile_changes().is_empty());
+
+        // Le lien le plus faible dégrade le channel immédiatement
+        let degraded = ReceiverReport { packets_expected: 100, packets_lost: 20, jitter_ms: 5.0, rtt_ms: 40.0 };
+        router.record_receiver_report(lossy, channel_id, &degraded);
+        assert!(router.get_channel_stats(&channel_id).unwrap().packet_loss_rate > 0.05);
+        assert_eq!(router.collect_profile_changes()[0].1.quality, QualityMode::Low);
+
+        // Puis remonte d'un palier à la fois
+        for _ in 0..8 {
+            router.record_receiver_report(lossy, channel_id, &clean);
+        }
+        assert_eq!(router.collect_profile_changes()[0].1.quality, QualityMode::Medium);
+        assert_eq!(router.collect_profile_changes()[0].1.quality, QualityMode::High);
+    }
+
+    #[test]
+    fn test_bitrate_caps_channel_profile() {
+        let router = AudioRouter::new();
+        let channel_id = Uuid::new_v4();
+        router.add_user_to_channel(Uuid::new_v4(), channel_id);
+
+        // Les valeurs par défaut du serveur s'appliquent aux channels non configurés
+        let mut defaults = router.channel_defaults();
+        defaults.bitrate_kbps = 512;
+        router.set_channel_defaults(defaults);
+        let profile = router.recommended_profile(&channel_id);
+        assert_eq!((profile.max_channels, profile.max_sample_rate), (1, 32000));
+        assert!(profile.bitrate_kbps() <= 512);
+
+        // Un débit suffisant laisse le profil intact
+        router.configure_channel(channel_id, ChannelRoutingConfig { quality_mode: QualityMode::Medium, ..Default::default() });
+        assert_eq!(router.recommended_profile(&channel_id), EncodingProfile::for_mode(QualityMode::Medium));
+
+        router.configure_channel(channel_id, ChannelRoutingConfig {
+            quality_mode: QualityMode::Medium,
+            bitrate_kbps: MIN_BITRATE_KBPS,
+            ..Default::default()
+        });
+        assert_eq!(router.recommended_profile(&channel_id).max_sample_rate, 8000);
+    }
+
+    #[test]
+    fn test_latency_corrects_client_clock() {
+        let router = AudioRouter::new();
+        let channel_id = Uuid::new_v4();
+        let speaker = Uuid::new_v4();
+        router.add_user_to_channel(speaker, channel_id);
+
+        // Horloge du client en retard de 2 s, aller-retour de 40 ms
+        let estimate = ClockEstimate { rtt_us: 40_000, offset_us: 2_000_000 };
+        router.record_clock_estimate(speaker, channel_id, &estimate);
+
+        let mut packet = AudioPacket::audio(speaker, channel_id, 1, bytes::Bytes::new(), 48000, 1);
+        packet.header.timestamp -= 2_000_000 + 20_000;
+        router.record_uplink_latency(speaker, channel_id, &packet.header);
+
+        let latency = router.user_latency(&speaker).unwrap();
+        assert_eq!(latency.rtt_ms, 40.0);
+        assert!((latency.one_way_ms - 20.0).abs() < 5.0);
+
+        let stats = router.get_channel_stats(&channel_id).unwrap();
+        assert_eq!(stats.user_latency[&speaker], latency);
+        assert!((stats.average_latency_ms - 40.0).abs() < 5.0);
+
+        // Une fois l'histogramme branché, chaque packet y est observé
+        let histogram = Arc::new(Mutex::new(LatencyHistogram::default()));
+        router.set_latency_histogram(histogram.clone());
+        router.record_uplink_latency(speaker, channel_id, &packet.header);
+        router.record_uplink_latency(speaker, channel_id, &packet.header);
+        let histogram = histogram.lock().unwrap();
+        assert_eq!(histogram.count, 2);
+        assert!((histogram.sum_ms / 2.0 - 20.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_connection_quality_from_uplink() {
+        let router = AudioRouter::new();
+        let channel_id = Uuid::new_v4();
+        let speaker = Uuid::new_v4();
+        router.add_user_to_channel(speaker, channel_id);
+        assert!(router.connection_quality(&speaker).is_none());
+
+        // 20 packets de 1920 octets, 2 perdus, puis fin de fenêtre
+        for sequence in (0..20).filter(|seq| *seq != 5 && *seq != 12) {
+            let packet = AudioPacket::audio(speaker, channel_id, sequence, bytes::Bytes::from(vec![0u8; 1920]), 48000, 2);
+            router.record_uplink_audio(speaker, &packet);
+        }
+        router.uplink.get_mut(&speaker).unwrap().window_start -= UPLINK_WINDOW;
+        let packet = AudioPacket::audio(speaker, channel_id, 20, bytes::Bytes::from(vec![0u8; 1920]), 48000, 2);
+        router.record_uplink_audio(speaker, &packet);
+
+        let quality = router.connection_quality(&speaker).unwrap();
+        assert!((quality.loss_percent - 2.0 / 21.0 * 100.0).abs() < 0.1);
+        assert!(quality.bitrate_kbps > 0.0);
+        assert_eq!((quality.sample_rate, quality.channels), (48000, 2));
+        assert_eq!(quality.signal, 2);
+
+        let channels = router.collect_connection_quality();
+        assert_eq!(channels, vec![(channel_id, vec![quality])]);
+
+        // Silencieux et tunnelé (RTT inconnu) : plus de pertes ni de gigue, signal plein
+        router.uplink.get_mut(&speaker).unwrap().last_packet -= UPLINK_IDLE;
+        let quality = router.connection_quality(&speaker).unwrap();
+        assert_eq!((quality.loss_percent, quality.jitter_ms, quality.bitrate_kbps), (0.0, 0.0, 0.0));
+        assert_eq!(quality.rtt_ms, 0.0);
+        assert_eq!(quality.signal, 4);
+    }
+}
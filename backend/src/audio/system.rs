@@ -0,0 +1,266 @@
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::sync::OnceLock;
+use std::time::Instant;
+use serde::{Serialize, Deserialize};
+
+/// Valeur usuelle de USER_HZ, si le système ne répond pas
+#[cfg(target_os = "linux")]
+const DEFAULT_CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+/// Ticks d'horloge par seconde utilisés par /proc/self/stat (USER_HZ), lus une seule fois
+#[cfg(target_os = "linux")]
+fn clock_ticks_per_second() -> f64 {
+    static TICKS: OnceLock<f64> = OnceLock::new();
+    *TICKS.get_or_init(|| {
+        // SAFETY: sysconf ne fait que lire une constante de configuration du système
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 { ticks as f64 } else { DEFAULT_CLOCK_TICKS_PER_SECOND }
+    })
+}
+
+/// Instantané des ressources consommées par le processus serveur
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessSnapshot {
+    /// CPU du processus, normalisé sur l'ensemble des cœurs (0-100)
+    pub cpu_usage_percent: f32,
+    /// Mémoire résidente (RSS)
+    pub memory_usage_mb: u64,
+    /// Mémoire totale de la machine
+    pub system_memory_total_mb: u64,
+    pub thread_count: usize,
+    pub open_file_descriptors: usize,
+    pub open_sockets: usize,
+}
+
+/// Métriques du runtime tokio
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeSnapshot {
+    pub workers: usize,
+    pub alive_tasks: usize,
+    pub global_queue_depth: usize,
+}
+
+/// Dernier relevé CPU, pour calculer l'utilisation entre deux collectes
+#[derive(Debug)]
+struct CpuSample {
+    cpu_seconds: f64,
+    taken_at: Instant,
+}
+
+/// Lecteur des métriques système du processus (procfs sous Linux). L'utilisation CPU est
+/// mesurée depuis le relevé précédent de ce moniteur : chaque consommateur a le sien.
+#[derive(Debug)]
+pub struct SystemMonitor {
+    last_cpu_sample: Mutex<Option<CpuSample>>,
+    cpu_count: usize,
+}
+
+impl SystemMonitor {
+    pub fn new() -> Self {
+        let monitor = Self {
+            last_cpu_sample: Mutex::new(None),
+            cpu_count: num_cpus::get().max(1),
+        };
+        // Premier relevé de référence pour que la prochaine mesure CPU soit exploitable
+        monitor.cpu_usage_percent();
+        monitor
+    }
+
+    /// Relève l'état courant du processus
+    pub fn snapshot(&self) -> ProcessSnapshot {
+        let status = read_status();
+        let (open_file_descriptors, open_sockets) = count_file_descriptors();
+
+        ProcessSnapshot {
+            cpu_usage_percent: self.cpu_usage_percent(),
+            memory_usage_mb: status.rss_kb / 1024,
+            system_memory_total_mb: read_memory_total_kb() / 1024,
+            thread_count: status.threads,
+            open_file_descriptors,
+            open_sockets,
+        }
+    }
+
+    /// Métriques du runtime tokio courant (vides hors runtime)
+    pub fn runtime_snapshot(&self) -> RuntimeSnapshot {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let metrics = handle.metrics();
+                RuntimeSnapshot {
+                    workers: metrics.num_workers(),
+                    alive_tasks: metrics.num_alive_tasks(),
+                    global_queue_depth: metrics.global_queue_depth(),
+                }
+            }
+            Err(_) => RuntimeSnapshot::default(),
+        }
+    }
+
+    /// Utilisation CPU depuis le relevé précédent
+    fn cpu_usage_percent(&self) -> f32 {
+        let Some(cpu_seconds) = read_cpu_seconds() else {
+            return 0.0;
+        };
+        let now = Instant::now();
+
+        let mut last = self.last_cpu_sample.lock().unwrap();
+        let usage = match last.as_ref() {
+            Some(previous) => {
+                let wall = now.duration_since(previous.taken_at).as_secs_f64();
+                if wall > 0.0 {
+                    let busy = (cpu_seconds - previous.cpu_seconds).max(0.0);
+                    (busy / wall / self.cpu_count as f64 * 100.0).min(100.0) as f32
+                } else {
+                    0.0
+                }
+            }
+            None => 0.0,
+        };
+
+        *last = Some(CpuSample { cpu_seconds, taken_at: now });
+        usage
+    }
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct ProcessStatus {
+    rss_kb: u64,
+    threads: usize,
+}
+
+/// Temps CPU cumulé (utilisateur + système) du processus
+#[cfg(target_os = "linux")]
+fn read_cpu_seconds() -> Option<f64> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    parse_cpu_ticks(&stat).map(|ticks| ticks as f64 / clock_ticks_per_second())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_cpu_seconds() -> Option<f64> {
+    None
+}
+
+/// Extrait utime + stime de /proc/self/stat (le nom du processus peut contenir des espaces)
+#[cfg(any(target_os = "linux", test))]
+fn parse_cpu_ticks(stat: &str) -> Option<u64> {
+    let after_name = &stat[stat.rfind(')')? + 1..];
+    let fields: Vec<&str> = after_name.split_whitespace().collect();
+    // Champs 14 et 15 du fichier, soit 11 et 12 après le nom
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+#[cfg(target_os = "linux")]
+fn read_status() -> ProcessStatus {
+    std::fs::read_to_string("/proc/self/status")
+        .map(|status| parse_status(&status))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_status() -> ProcessStatus {
+    ProcessStatus::default()
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_status(status: &str) -> ProcessStatus {
+    let mut parsed = ProcessStatus::default();
+    for line in status.lines() {
+        if let Some(value) = line.strip_prefix("VmRSS:") {
+            parsed.rss_kb = parse_kb(value);
+        } else if let Some(value) = line.strip_prefix("Threads:") {
+            parsed.threads = value.trim().parse().unwrap_or(0);
+        }
+    }
+    parsed
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn parse_kb(value: &str) -> u64 {
+    value.split_whitespace().next().and_then(|v| v.parse().ok()).unwrap_or(0)
+}
+
+#[cfg(target_os = "linux")]
+fn read_memory_total_kb() -> u64 {
+    std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| {
+            meminfo.lines()
+                .find_map(|line| line.strip_prefix("MemTotal:").map(parse_kb))
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_memory_total_kb() -> u64 {
+    0
+}
+
+/// Compte les descripteurs ouverts et, parmi eux, les sockets
+#[cfg(target_os = "linux")]
+fn count_file_descriptors() -> (usize, usize) {
+    let Ok(entries) = std::fs::read_dir("/proc/self/fd") else {
+        return (0, 0);
+    };
+
+    let mut descriptors = 0;
+    let mut sockets = 0;
+    for entry in entries.flatten() {
+        descriptors += 1;
+        if let Ok(target) = std::fs::read_link(entry.path()) {
+            if target.to_string_lossy().starts_with("socket:") {
+                sockets += 1;
+            }
+        }
+    }
+    (descriptors, sockets)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn count_file_descriptors() -> (usize, usize) {
+    (0, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "1234 (voice (chat) server) S 1 1234 1234 0 -1 4194560 500 0 0 0 250 130 0 0 20 0 12 0";
+        assert_eq!(parse_cpu_ticks(stat), Some(380));
+
+        let status = "Name:\tvoice-chat\nVmRSS:\t  20480 kB\nThreads:\t12\n";
+        assert_eq!(parse_status(status), ProcessStatus { rss_kb: 20480, threads: 12 });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_snapshot_reads_process() {
+        let monitor = SystemMonitor::new();
+        let _socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let snapshot = monitor.snapshot();
+        assert!(snapshot.memory_usage_mb > 0);
+        assert!(snapshot.thread_count >= 1);
+        assert!(snapshot.open_sockets >= 1);
+        assert!((0.0..=100.0).contains(&snapshot.cpu_usage_percent));
+        assert!(monitor.runtime_snapshot().workers >= 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_clock_ticks_from_system() {
+        let ticks = clock_ticks_per_second();
+        assert!(ticks > 0.0);
+        assert_eq!(ticks, clock_ticks_per_second());
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::time::Duration;
 use crate::Result;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -7,6 +8,10 @@ pub struct Config {
     pub server: ServerConfig,
     pub audio: AudioConfig,
     pub limits: LimitsConfig,
+    #[serde(default)]
+    pub afk: AfkConfig,
+    #[serde(default)]
+    pub session: SessionConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -36,6 +41,79 @@ pub struct LimitsConfig {
     pub max_concurrent_connections: usize,
 }
 
+/// Détection d'inactivité et déplacement automatique vers le channel AFK
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AfkConfig {
+    pub enabled: bool,
+    /// Délai sans activité vocale avant de passer l'utilisateur en `Away`
+    pub idle_timeout_secs: u64,
+    /// Intervalle de vérification de l'inactivité
+    pub check_interval_secs: u64,
+    /// Déplace l'utilisateur inactif dans le channel AFK (audio coupé)
+    pub move_to_afk_channel: bool,
+    pub afk_channel_name: String,
+}
+
+impl Default for AfkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_timeout_secs: 600,
+            check_interval_secs: 10,
+            move_to_afk_channel: true,
+            afk_channel_name: "AFK".to_string(),
+        }
+    }
+}
+
+impl AfkConfig {
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs)
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_secs(self.check_interval_secs.max(1))
+    }
+}
+
+/// Reprise de session après une coupure et détection des connexions mortes
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Délai pendant lequel un utilisateur déconnecté garde son identité et son channel
+    /// (0 = suppression immédiate)
+    pub grace_period_secs: u64,
+    /// Un WebSocket silencieux (ni message ni ping) plus longtemps est fermé
+    pub idle_timeout_secs: u64,
+    /// Une adresse UDP sans packet (audio ou heartbeat) plus longtemps est oubliée
+    pub udp_client_timeout_secs: u64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            grace_period_secs: 30,
+            idle_timeout_secs: 30,
+            udp_client_timeout_secs: 30,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs.max(1))
+    }
+
+    pub fn udp_client_timeout(&self) -> Duration {
+        Duration::from_secs(self.udp_client_timeout_secs.max(1))
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -58,6 +136,8 @@ impl Default for Config {
                 max_channels: 50,
                 max_concurrent_connections: 100,
             },
+            afk: AfkConfig::default(),
+            session: SessionConfig::default(),
         }
     }
 }
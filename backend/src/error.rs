@@ -14,6 +14,10 @@ pub enum Error {
     #[error("Channel error: {0}")]
     Channel(String),
     
+    /// Nom déjà utilisé par une autre ressource
+    #[error("Conflict: {0}")]
+    Conflict(String),
+    
     #[error("Audio error: {0}")]
     Audio(String),
     
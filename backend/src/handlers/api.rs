@@ -66,6 +66,8 @@ impl ApiHandlers {
         // Then remove the user completely
         match handlers.user_service.remove_user(&user_id) {
             Ok(_) => {
+                // Déconnexion explicite : pas de délai de grâce pour la session WebSocket
+                handlers.websocket_handler.force_disconnect(user_id).await;
                 tracing::info!("✅ User {} disconnected successfully", user_id);
                 Ok(Json("User disconnected successfully".to_string()))
             },
@@ -79,42 +81,7 @@ impl ApiHandlers {
     pub async fn list_channels(
         State(handlers): State<Arc<Self>>,
     ) -> Result<Json<Vec<EnrichedChannelResponse>>, (StatusCode, String)> {
-        let channels = handlers.channel_service.list_channels();
-        let mut enriched_channels = Vec::new();
-        
-        for channel_response in channels {
-            // Get detailed channel to have user list
-            if let Ok(detailed_channel) = handlers.channel_service.get_channel(&channel_response.id) {
-                let mut users = Vec::new();
-                
-                // Get user info for each user in channel
-                for user_id in &detailed_channel.current_users {
-                    if let Ok(user) = handlers.user_service.get_user(user_id) {
-                        users.push(crate::models::UserInfo {
-                            id: user.id,
-                            username: user.username,
-                            is_speaking: false, // TODO: Get from audio service
-                            mic_enabled: true,  // TODO: Get from audio service
-                            speaker_enabled: true, // TODO: Get from audio service
-                        });
-                    }
-                }
-                
-                enriched_channels.push(crate::models::EnrichedChannelResponse {
-                    id: detailed_channel.id,
-                    name: detailed_channel.name,
-                    description: detailed_channel.description,
-                    owner_id: detailed_channel.owner_id,
-                    max_users: detailed_channel.max_users,
-                    user_count: detailed_channel.current_users.len(), // Frontend compat
-                    current_users: detailed_channel.current_users,
-                    users,
-                    is_private: detailed_channel.is_private,
-                    has_password: detailed_channel.has_password,
-                    created_at: detailed_channel.created_at,
-                });
-            }
-        }
+        let enriched_channels = handlers.websocket_handler.enriched_channels();
         
         Ok(Json(enriched_channels))
     }
@@ -128,7 +95,10 @@ impl ApiHandlers {
         let owner_id = Uuid::new_v4();
         
         match handlers.channel_service.create_channel(request, owner_id) {
-            Ok(channel) => Ok(Json(channel)),
+            Ok(channel) => {
+                handlers.websocket_handler.notify_channel_created(channel.id).await;
+                Ok(Json(channel))
+            }
             Err(e) => Err((StatusCode::BAD_REQUEST, e.to_string())),
         }
     }
@@ -158,6 +128,7 @@ impl ApiHandlers {
                 average_latency_ms: stats.average_latency_ms,
                 packet_loss_rate: stats.packet_loss_rate,
                 jitter_ms: stats.jitter_ms,
+                user_latency: stats.user_latency,
             };
             Ok(Json(response))
         } else {
@@ -192,6 +163,9 @@ impl ApiHandlers {
                 // Notify audio service that user joined the channel
                 tracing::info!("🎵 Adding user {} to audio routing for channel {}", user_id, channel_id);
                 handlers.audio_service.add_user_to_channel(user_id, channel_id);
+                if let Ok(user) = handlers.user_service.get_user(&user_id) {
+                    handlers.audio_service.set_user_deafened(user_id, user.self_deafened);
+                }
                 
                 // Broadcast WebSocket event to ALL connected users (not just those in the channel)
                 // This allows all clients to update their channel list with new user counts
@@ -202,6 +176,7 @@ impl ApiHandlers {
                 ).await {
                     tracing::warn!("⚠️ Failed to broadcast user joined event: {}", e);
                 }
+                handlers.websocket_handler.notify_channel_updated(channel_id).await;
                 
                 // Get updated channel info to log
                 if let Ok(channel) = handlers.channel_service.get_channel(&channel_id) {
@@ -249,6 +224,7 @@ impl ApiHandlers {
                 ).await {
                     tracing::warn!("⚠️ Failed to broadcast user left event: {}", e);
                 }
+                handlers.websocket_handler.notify_channel_updated(channel_id).await;
                 
                 // Get updated channel info to log
                 if let Ok(channel) = handlers.channel_service.get_channel(&channel_id) {
@@ -267,13 +243,6 @@ impl ApiHandlers {
     pub async fn get_audio_config(
         State(handlers): State<Arc<Self>>,
     ) -> Result<Json<AudioConfigResponse>, (StatusCode, String)> {
-        let response = AudioConfigResponse {
-            sample_rate: handlers.audio_service.get_sample_rate(),
-            channels: handlers.audio_service.get_channels(),
-            buffer_size: handlers.audio_service.get_buffer_size(),
-            max_packet_size: handlers.audio_service.get_max_packet_size(),
-            codec: "Opus".to_string(), // For now, hardcoded
-        };
-        Ok(Json(response))
+        Ok(Json(handlers.audio_service.negotiated_config()))
     }
 }
\ No newline at end of file
@@ -15,11 +15,13 @@ impl WebSocketHandlers {
         // Add message validation logic here
         match message {
             ClientMessage::Authenticate { username } => !username.trim().is_empty(),
+            ClientMessage::Resume { .. } => true,
             ClientMessage::JoinChannel { channel_id, .. } => true,
             ClientMessage::LeaveChannel { channel_id } => true,
             ClientMessage::SetStatus { .. } => true,
             ClientMessage::StartAudio { .. } => true,
             ClientMessage::StopAudio { .. } => true,
+            ClientMessage::SetVoiceState { .. } => true,
             ClientMessage::Ping => true,
         }
     }
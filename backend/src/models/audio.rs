@@ -12,6 +12,8 @@ pub struct AudioStatsResponse {
     pub average_latency_ms: f32,
     pub packet_loss_rate: f32,
     pub jitter_ms: f32,
+    /// Aller-retour et transit mesurés de chaque membre
+    pub user_latency: std::collections::HashMap<Uuid, crate::audio::router::UserLatency>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
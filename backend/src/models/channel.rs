@@ -168,7 +168,7 @@ impl From<Channel> for DetailedChannelResponse {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnrichedChannelResponse {
     pub id: Uuid,
     pub name: String,
@@ -184,7 +184,7 @@ pub struct EnrichedChannelResponse {
     pub created_at: SystemTime,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserInfo {
     pub id: Uuid,
     pub username: String,
@@ -83,19 +83,43 @@ impl Message {
 #[serde(tag = "action", content = "payload")]
 pub enum ClientMessage {
     Authenticate { username: String },
+    /// Reprend une session interrompue avec le jeton reçu à l'authentification
+    Resume { resume_token: Uuid },
     JoinChannel { channel_id: Uuid, password: Option<String> },
     LeaveChannel { channel_id: Uuid },
     SetStatus { status: crate::models::user::UserStatus },
     StartAudio { channel_id: Uuid },
     StopAudio { channel_id: Uuid },
+    /// Micro et son coupés par l'utilisateur lui-même
+    SetVoiceState { muted: bool, deafened: bool },
     Ping,
 }
 
+/// Message client accompagné d'un identifiant de corrélation facultatif
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientEnvelope {
+    #[serde(flatten)]
+    pub message: ClientMessage,
+    #[serde(default)]
+    pub request_id: Option<u64>,
+}
+
+/// Réponse du serveur, rattachée à la requête qui l'a provoquée
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerEnvelope {
+    #[serde(flatten)]
+    pub message: ServerMessage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+}
+
 // Server to client messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "event", content = "data")]
 pub enum ServerMessage {
-    Authenticated { user_id: Uuid },
+    Authenticated { user_id: Uuid, resume_token: Uuid },
+    /// Session reprise : identité et channel conservés, nouveau jeton pour la prochaine reprise
+    Resumed { user_id: Uuid, channel_id: Option<Uuid>, resume_token: Uuid },
     JoinedChannel { channel_id: Uuid },
     LeftChannel { channel_id: Uuid },
     UserJoined { channel_id: Uuid, user_id: Uuid },
@@ -104,6 +128,26 @@ pub enum ServerMessage {
     UserStatusChanged { user_id: Uuid, status: crate::models::user::UserStatus },
     AudioStarted { channel_id: Uuid, user_id: Uuid },
     AudioStopped { channel_id: Uuid, user_id: Uuid },
+    /// Un membre du channel a coupé ou rétabli son micro ou son son
+    VoiceStateChanged { channel_id: Uuid, user_id: Uuid, muted: bool, deafened: bool },
+    /// Liste complète des channels, envoyée à l'authentification
+    ChannelList { channels: Vec<crate::models::EnrichedChannelResponse> },
+    ChannelCreated { channel: crate::models::EnrichedChannelResponse },
+    /// État complet d'un channel après un changement (membres, état de parole...)
+    ChannelUpdated { channel: crate::models::EnrichedChannelResponse },
+    ChannelDeleted { channel_id: Uuid },
+    UserSpeaking { channel_id: Uuid, user_id: Uuid, is_speaking: bool },
+    /// Nouveaux paramètres audio négociés (configuration modifiée à chaud)
+    AudioConfigChanged { config: crate::models::AudioConfigResponse },
+    ChannelRoutingChanged { channel_id: Uuid, config: crate::audio::router::ChannelRoutingConfig },
+    /// Paramètres d'encodage que les émetteurs du channel doivent appliquer
+    EncodingProfileChanged { channel_id: Uuid, profile: crate::audio::router::EncodingProfile },
+    /// Nonce à renvoyer en UDP pour faire adopter la nouvelle adresse d'où arrive notre audio
+    AddressChallenge { nonce: Uuid },
+    /// Qualité de connexion de chaque membre du channel (envoyée périodiquement)
+    ConnectionQuality { channel_id: Uuid, users: Vec<crate::audio::router::ConnectionQuality> },
     Error { message: String },
     Pong,
+    /// Requête traitée sans réponse particulière (statut, début/fin d'audio...)
+    Ack,
 }
\ No newline at end of file
@@ -8,5 +8,5 @@ pub use channel::{
     Channel, ChannelResponse, DetailedChannelResponse, EnrichedChannelResponse, UserInfo,
     CreateChannelRequest, JoinChannelRequest, HttpJoinChannelRequest
 };
-pub use message::{Message, MessageType, ClientMessage, ServerMessage};
+pub use message::{Message, MessageType, ClientMessage, ClientEnvelope, ServerMessage, ServerEnvelope};
 pub use audio::{AudioStatsResponse, UserAudioStatus, AudioConfigResponse};
\ No newline at end of file
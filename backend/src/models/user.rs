@@ -8,6 +8,12 @@ pub struct User {
     pub username: String,
     pub status: UserStatus,
     pub current_channel: Option<Uuid>,
+    /// Micro coupé par l'utilisateur lui-même
+    #[serde(default)]
+    pub self_muted: bool,
+    /// Son du channel coupé par l'utilisateur lui-même
+    #[serde(default)]
+    pub self_deafened: bool,
     pub created_at: SystemTime,
     pub last_seen: SystemTime,
 }
@@ -17,6 +23,8 @@ pub enum UserStatus {
     Online,
     Away,
     InChannel,
+    /// WebSocket coupé, session conservée pendant le délai de grâce
+    Reconnecting,
     Offline,
 }
 
@@ -28,6 +36,8 @@ impl User {
             username,
             status: UserStatus::Online,
             current_channel: None,
+            self_muted: false,
+            self_deafened: false,
             created_at: now,
             last_seen: now,
         }
@@ -53,6 +63,12 @@ impl User {
         self.status = status;
         self.update_last_seen();
     }
+
+    pub fn set_voice_state(&mut self, muted: bool, deafened: bool) {
+        self.self_muted = muted;
+        self.self_deafened = deafened;
+        self.update_last_seen();
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -66,6 +82,8 @@ pub struct UserResponse {
     pub username: String,
     pub status: UserStatus,
     pub current_channel: Option<Uuid>,
+    pub self_muted: bool,
+    pub self_deafened: bool,
 }
 
 impl From<User> for UserResponse {
@@ -75,6 +93,8 @@ impl From<User> for UserResponse {
             username: user.username,
             status: user.status,
             current_channel: user.current_channel,
+            self_muted: user.self_muted,
+            self_deafened: user.self_deafened,
         }
     }
 }
\ No newline at end of file
@@ -2,4 +2,4 @@ pub mod websocket;
 pub mod udp;
 
 pub use websocket::WebSocketHandler;
-pub use udp::UdpServer;
\ No newline at end of file
+pub use udp::{UdpServer, TransportStats, TransportSnapshot};
\ No newline at end of file
@@ -1,39 +1,118 @@
 use tokio::net::UdpSocket;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
 use crate::{
-    config::Config, 
-    audio::{AudioPacket, AudioRouter},
+    config::{AudioConfig, Config},
+    audio::{AddressObservation, AudioPacket, AudioRouter},
     services::{UserService, ChannelService},
-    Result
+    Error, Result
 };
 
+/// Provenance d'un packet audio
+#[derive(Debug, Clone, Copy)]
+enum PacketSource {
+    /// Datagramme reçu de cette adresse
+    Udp(SocketAddr),
+    /// Frame binaire du WebSocket de cet utilisateur authentifié (UDP bloqué)
+    Tunnel(Uuid),
+}
+
+impl PacketSource {
+    fn udp_addr(self) -> Option<SocketAddr> {
+        match self {
+            PacketSource::Udp(addr) => Some(addr),
+            PacketSource::Tunnel(_) => None,
+        }
+    }
+}
+
+/// Compteurs de trafic du transport UDP, mis à jour à chaque packet
+#[derive(Debug, Default)]
+pub struct TransportStats {
+    packets_received: AtomicU64,
+    packets_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    errors: AtomicU64,
+}
+
+/// Valeurs instantanées des compteurs de transport
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransportSnapshot {
+    pub packets_received: u64,
+    pub packets_sent: u64,
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+    pub errors: u64,
+}
+
+impl TransportStats {
+    pub fn record_received(&self, bytes: usize) {
+        self.packets_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_sent(&self, bytes: usize) {
+        self.packets_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> TransportSnapshot {
+        TransportSnapshot {
+            packets_received: self.packets_received.load(Ordering::Relaxed),
+            packets_sent: self.packets_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct UdpServer {
     config: Config,
+    /// Configuration audio modifiable à chaud (partagée avec AudioService)
+    audio_config: Arc<RwLock<AudioConfig>>,
     router: Arc<AudioRouter>,
     user_service: Arc<UserService>,
     channel_service: Arc<ChannelService>,
+    stats: Arc<TransportStats>,
+    /// File des packets sortants, disponible une fois le serveur démarré
+    outgoing: OnceLock<mpsc::Sender<(AudioPacket, SocketAddr)>>,
 }
 
 impl UdpServer {
     pub fn new(
         config: Config,
+        audio_config: Arc<RwLock<AudioConfig>>,
         router: Arc<AudioRouter>,
         user_service: Arc<UserService>,
         channel_service: Arc<ChannelService>,
     ) -> Self {
         Self {
             config,
+            audio_config,
             router,
             user_service,
             channel_service,
+            stats: Arc::new(TransportStats::default()),
+            outgoing: OnceLock::new(),
         }
     }
 
+    /// Compteurs de trafic du transport
+    pub fn transport_stats(&self) -> Arc<TransportStats> {
+        self.stats.clone()
+    }
+
     pub async fn start(&self) -> Result<()> {
         let addr = self.config.udp_addr();
         let socket = Arc::new(UdpSocket::bind(addr).await?);
@@ -42,16 +121,22 @@ impl UdpServer {
 
         // Canal pour les packets sortants
         let (tx, mut rx) = mpsc::channel::<(AudioPacket, SocketAddr)>(10000);
+        let _ = self.outgoing.set(tx.clone());
 
         // Task pour l'envoi des packets
         let send_socket = socket.clone();
+        let send_router = self.router.clone();
+        let send_stats = self.stats.clone();
         let send_task = tokio::spawn(async move {
             while let Some((packet, addr)) = rx.recv().await {
                 let packet_bytes = packet.to_bytes();
                 
                 if let Err(e) = send_socket.send_to(&packet_bytes, addr).await {
+                    send_stats.record_error();
                     tracing::error!("Failed to send UDP packet to {}: {}", addr, e);
                 } else {
+                    send_stats.record_sent(packet_bytes.len());
+                    send_router.record_packet_sent(&packet.header.channel_id, packet_bytes.len());
                     tracing::trace!("Sent audio packet to {} ({}bytes)", addr, packet_bytes.len());
                 }
             }
@@ -62,29 +147,39 @@ impl UdpServer {
         let router_clone = self.router.clone();
         let user_service = self.user_service.clone();
         let channel_service = self.channel_service.clone();
-        let config_clone = self.config.clone();
+        let audio_config = self.audio_config.clone();
         let sender = tx.clone();
+        let recv_stats = self.stats.clone();
         
         let recv_task = tokio::spawn(async move {
-            let mut buf = vec![0u8; 4096]; // Buffer agrandi pour les packets audio (au lieu de 2048)
+            let mut buf = Vec::new();
             
             loop {
+                // Un octet de plus que la limite courante : un datagramme trop grand est détecté
+                // au lieu d'être tronqué silencieusement
+                let max_packet_size = audio_config.read().unwrap().max_packet_size;
+                buf.resize(max_packet_size + 1, 0);
+
                 match recv_socket.recv_from(&mut buf).await {
                     Ok((size, from_addr)) => {
+                        recv_stats.record_received(size);
+
                         // Traiter le packet reçu
                         if let Err(e) = Self::handle_received_packet(
                             &buf[..size],
-                            from_addr,
+                            PacketSource::Udp(from_addr),
                             &router_clone,
                             &user_service,
                             &channel_service,
-                            &config_clone,
+                            &audio_config,
                             &sender,
                         ).await {
+                            recv_stats.record_error();
                             tracing::warn!("Error processing packet from {}: {}", from_addr, e);
                         }
                     }
                     Err(e) => {
+                        recv_stats.record_error();
                         tracing::error!("UDP receive error: {}", e);
                         tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
                     }
@@ -120,15 +215,42 @@ impl UdpServer {
         Ok(())
     }
 
+    /// Traite un packet reçu par le tunnel WebSocket, comme s'il était arrivé en UDP
+    pub async fn handle_tunnel_packet(&self, user_id: Uuid, packet_data: &[u8]) -> Result<()> {
+        let sender = self.outgoing.get()
+            .ok_or_else(|| Error::Audio("UDP server not started".to_string()))?;
+
+        Self::handle_received_packet(
+            packet_data,
+            PacketSource::Tunnel(user_id),
+            &self.router,
+            &self.user_service,
+            &self.channel_service,
+            &self.audio_config,
+            sender,
+        ).await
+    }
+
     async fn handle_received_packet(
         packet_data: &[u8],
-        from_addr: SocketAddr,
+        source: PacketSource,
         router: &Arc<AudioRouter>,
         user_service: &Arc<UserService>,
         channel_service: &Arc<ChannelService>,
-        config: &Config,
+        audio_config: &Arc<RwLock<AudioConfig>>,
         sender: &mpsc::Sender<(AudioPacket, SocketAddr)>,
     ) -> Result<()> {
+        // Horodatage de réception, renvoyé aux heartbeats pour la mesure d'aller-retour
+        let received_us = crate::audio::packet::now_micros();
+
+        let max_packet_size = audio_config.read().unwrap().max_packet_size;
+        if packet_data.len() > max_packet_size {
+            return Err(Error::Audio(format!(
+                "Packet of {} bytes exceeds max_packet_size ({})",
+                packet_data.len(),
+                max_packet_size,
+            )));
+        }
         // Log de réception
                 // println!("📦 UDP received {} bytes from {}", len, addr);
         
@@ -136,7 +258,7 @@ impl UdpServer {
         let packet = match AudioPacket::from_bytes(packet_data) {
             Ok(p) => p,
             Err(e) => {
-                tracing::warn!("❌ Invalid audio packet from {}: {}", from_addr, e);
+                tracing::warn!("❌ Invalid audio packet from {:?}: {}", source, e);
                 return Ok(());
             }
         };
@@ -153,6 +275,14 @@ impl UdpServer {
         let user_id = packet.header.user_id;
         let channel_id = packet.header.channel_id;
 
+        // Un tunnel ne transporte que l'audio de l'utilisateur authentifié sur ce WebSocket
+        if let PacketSource::Tunnel(owner) = source {
+            if owner != user_id {
+                tracing::warn!("❌ Tunnel of user {} sent a packet for user {}", owner, user_id);
+                return Ok(());
+            }
+        }
+
         // Vérifier l'utilisateur
         if user_service.get_user(&user_id).is_err() {
             tracing::warn!("❌ Received packet from unknown user {}", user_id);
@@ -173,19 +303,53 @@ impl UdpServer {
             return Ok(());
         }
 
-        // Enregistrer l'adresse du client s'il n'est pas déjà enregistré
-        router.register_client(user_id, from_addr);
+        let from_addr = source.udp_addr();
+
+        // Réponse à un défi : la nouvelle adresse est joignable, l'adopter
+        if packet.header.packet_type == crate::audio::PacketType::ProbeReply {
+            match (from_addr, packet.probe_nonce()) {
+                (Some(from_addr), Some(nonce)) if router.confirm_client_address(user_id, from_addr, nonce) => {
+                    tracing::info!("User {} verified new UDP address {}, switching to it", user_id, from_addr);
+                }
+                _ => tracing::debug!("Ignoring unexpected probe reply from {:?} for user {}", source, user_id),
+            }
+            return Ok(());
+        }
+
+        // Enregistrer l'adresse du client ; une nouvelle adresse doit d'abord répondre au défi
+        // que le WebSocket du client lui transmet
+        if let Some(from_addr) = from_addr {
+            if let AddressObservation::Challenge { address } = router.observe_client(user_id, from_addr) {
+                tracing::info!("User {} seen at new address {}, challenging it over the WebSocket", user_id, address);
+            }
+        }
 
         // Traiter le packet selon son type
         match packet.header.packet_type {
             crate::audio::PacketType::Audio => {
+                // Le client est revenu à l'UDP : lui renvoyer l'audio par le même chemin
+                if let Some(from_addr) = from_addr {
+                    if router.release_tunnel(&user_id, from_addr) {
+                        tracing::info!("User {} is back on UDP, leaving the WebSocket tunnel", user_id);
+                    }
+                }
+                // Toute transmission vocale réinitialise le délai d'inactivité (AFK)
+                router.record_voice_activity(user_id, channel_id);
+                router.record_uplink_latency(user_id, channel_id, &packet.header);
+                router.record_uplink_audio(user_id, &packet);
+
                 // Mode loopback pour test local
-                if config.audio.loopback_mode {
+                let loopback_mode = audio_config.read().unwrap().loopback_mode;
+                if loopback_mode {
                     println!("🔄 UdpServer: Loopback mode - returning audio to sender");
-                    // En mode loopback, envoyer au port de playback fixe (8083)
-                    let playback_addr = std::net::SocketAddr::new(from_addr.ip(), 8083);
-                    if let Err(e) = sender.send((packet.clone(), playback_addr)).await {
-                        tracing::error!("Failed to send loopback packet: {}", e);
+                    // En mode loopback, renvoyer par le tunnel ou à l'adresse vérifiée de l'émetteur
+                    if router.send_via_tunnel(&user_id, &packet) {
+                        return Ok(());
+                    }
+                    if let Some(playback_addr) = router.client_address(&user_id).or(from_addr) {
+                        if let Err(e) = sender.send((packet.clone(), playback_addr)).await {
+                            tracing::error!("Failed to send loopback packet: {}", e);
+                        }
                     }
                     return Ok(());
                 } // else {
@@ -244,9 +408,44 @@ impl UdpServer {
             }
 
             crate::audio::PacketType::Sync => {
-                println!("💓 UdpServer: Received heartbeat from user {} in channel {} at {}", user_id, channel_id, from_addr);
-                // L'enregistrement client a déjà été fait avec router.register_client() plus haut
-                // Pas besoin de répondre pour un simple heartbeat d'enregistrement
+                tracing::trace!("Heartbeat from user {} in channel {} via {:?}", user_id, channel_id, source);
+                if let Some(estimate) = packet.clock_estimate() {
+                    router.record_clock_estimate(user_id, channel_id, &estimate);
+                }
+                // Renvoyer au client son adresse publique et de quoi mesurer l'aller-retour,
+                // par le chemin du heartbeat (le client ignore l'adresse d'une réponse tunnelée)
+                match from_addr {
+                    Some(from_addr) => {
+                        let reply = AudioPacket::sync_reply(user_id, channel_id, from_addr, &packet.header, received_us);
+                        if let Err(e) = sender.send((reply, from_addr)).await {
+                            tracing::error!("Failed to queue heartbeat reply: {}", e);
+                        }
+                    }
+                    None => {
+                        let observed = router.client_address(&user_id).unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0)));
+                        let reply = AudioPacket::sync_reply(user_id, channel_id, observed, &packet.header, received_us);
+                        if !router.send_via_tunnel(&user_id, &reply) {
+                            tracing::debug!("No tunnel to answer the heartbeat of user {}", user_id);
+                        }
+                    }
+                }
+            }
+
+            crate::audio::PacketType::ReceiverReport => {
+                match packet.report() {
+                    Some(report) => {
+                        let quality = router.record_receiver_report(user_id, channel_id, &report);
+                        tracing::trace!("Receiver report from {}: {:?}", user_id, quality);
+                    }
+                    None => tracing::warn!("Malformed receiver report from user {}", user_id),
+                }
+            }
+
+            crate::audio::PacketType::Probe
+            | crate::audio::PacketType::ProbeReply
+            | crate::audio::PacketType::SyncReply => {
+                // Packets émis par le serveur, jamais attendus d'un client
+                tracing::debug!("Ignoring server-only packet {:?} from {:?}", packet.header.packet_type, source);
             }
         }
 
@@ -272,4 +471,33 @@ impl UdpServer {
     pub fn get_stats(&self, channel_id: &Uuid) -> Option<crate::audio::router::RoutingStats> {
         self.router.get_channel_stats(channel_id)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[tokio::test]
+    async fn test_max_packet_size_enforced() {
+        let config = Config::default();
+        let audio_config = Arc::new(RwLock::new(AudioConfig { max_packet_size: 512, ..config.audio.clone() }));
+        let server = UdpServer::new(
+            config.clone(),
+            audio_config.clone(),
+            Arc::new(AudioRouter::new()),
+            Arc::new(UserService::new()),
+            Arc::new(ChannelService::new(config.limits.clone())),
+        );
+        let (tx, _rx) = mpsc::channel(16);
+        server.outgoing.set(tx).unwrap();
+
+        let user_id = Uuid::new_v4();
+        let packet = AudioPacket::audio(user_id, Uuid::new_v4(), 0, Bytes::from(vec![0u8; 1024]), 48000, 1).to_bytes();
+        assert!(server.handle_tunnel_packet(user_id, &packet).await.is_err());
+
+        // La limite est lue à chaque packet : l'augmenter suffit
+        audio_config.write().unwrap().max_packet_size = 2048;
+        assert!(server.handle_tunnel_packet(user_id, &packet).await.is_ok());
+    }
+}
@@ -5,22 +5,56 @@ use axum::{
 };
 use dashmap::DashMap;
 use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{broadcast, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
 use uuid::Uuid;
 
 use crate::{
-    models::{ClientMessage, ServerMessage},
+    config::AfkConfig,
+    models::{ClientMessage, ClientEnvelope, ServerMessage, ServerEnvelope, EnrichedChannelResponse, UserInfo, user::UserStatus},
     services::{UserService, ChannelService, AudioService},
     Error, Result,
 };
 
+/// Délai sans packet audio avant de considérer qu'un utilisateur a fini de parler
+const SPEAKING_HANGOVER: Duration = Duration::from_millis(300);
+const SPEAKING_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// Délai de grâce par défaut avant de supprimer un utilisateur déconnecté
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(30);
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+const CLIENT_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Fréquence d'envoi des défis d'adresse UDP en attente
+const ADDRESS_CHALLENGE_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+/// Fréquence de réévaluation des profils d'encodage et d'envoi de la qualité des connexions
+const QUALITY_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Packets audio en attente d'écriture sur un tunnel (au-delà, ils sont perdus comme en UDP)
+const TUNNEL_QUEUE_SIZE: usize = 64;
+
+/// Session dont le WebSocket est tombé, en attente de reprise
+#[derive(Debug, Clone)]
+struct SuspendedSession {
+    disconnected_at: Instant,
+    /// Statut à rétablir à la reprise
+    previous_status: UserStatus,
+}
+
+#[derive(Debug)]
 pub struct WebSocketHandler {
     user_service: Arc<UserService>,
     channel_service: Arc<ChannelService>,
     audio_service: Arc<AudioService>,
     connections: Arc<DashMap<Uuid, broadcast::Sender<ServerMessage>>>,
     global_broadcast: broadcast::Sender<ServerMessage>,
+    /// Jeton de reprise -> utilisateur
+    resume_tokens: Arc<DashMap<Uuid, Uuid>>,
+    suspended: Arc<DashMap<Uuid, SuspendedSession>>,
+    /// Signal de fermeture du WebSocket de chaque utilisateur (déconnexion par l'administration)
+    kicks: Arc<DashMap<Uuid, oneshot::Sender<()>>>,
+    grace_period: Duration,
+    /// Un socket sans aucun message (le client envoie des pings) pendant ce délai est considéré mort
+    idle_timeout: Duration,
 }
 
 impl WebSocketHandler {
@@ -37,9 +71,26 @@ impl WebSocketHandler {
             audio_service,
             connections: Arc::new(DashMap::new()),
             global_broadcast,
+            resume_tokens: Arc::new(DashMap::new()),
+            suspended: Arc::new(DashMap::new()),
+            kicks: Arc::new(DashMap::new()),
+            grace_period: DEFAULT_GRACE_PERIOD,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
         }
     }
 
+    /// Délai pendant lequel une session interrompue peut être reprise
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Délai de silence au-delà duquel un WebSocket est fermé
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
     pub async fn handle_upgrade(
         State(handler): State<Arc<Self>>,
         ws: WebSocketUpgrade,
@@ -52,20 +103,53 @@ impl WebSocketHandler {
         let sender = Arc::new(Mutex::new(sender));
         let mut user_id: Option<Uuid> = None;
         let mut broadcast_receiver: Option<broadcast::Receiver<ServerMessage>> = None;
+        // Canal de diffusion de cette connexion (une reprise ailleurs le remplace)
+        let mut own_sender: Option<broadcast::Sender<ServerMessage>> = None;
+        // Audio tunnelé sur ce WebSocket, ouvert au premier frame binaire
+        let mut tunnel: Option<mpsc::Sender<Vec<u8>>> = None;
+        // Fermeture demandée par le serveur, armée à l'authentification
+        let mut kick_rx: Option<oneshot::Receiver<()>> = None;
 
         // Clone sender for broadcast task
         let sender_for_broadcast = sender.clone();
 
         // Handle incoming messages
-        while let Some(msg) = receiver.next().await {
+        loop {
+            let next = tokio::select! {
+                next = tokio::time::timeout(self.idle_timeout, receiver.next()) => next,
+                kicked = Self::kicked(&mut kick_rx) => {
+                    if !kicked {
+                        continue;
+                    }
+                    tracing::info!("Closing WebSocket of user {:?} on server request", user_id);
+                    let close = axum::extract::ws::CloseFrame {
+                        code: axum::extract::ws::close_code::POLICY,
+                        reason: "Disconnected by the server".into(),
+                    };
+                    let _ = sender.lock().await.send(axum::extract::ws::Message::Close(Some(close))).await;
+                    break;
+                }
+            };
+            let msg = match next {
+                Ok(Some(msg)) => msg,
+                Ok(None) => break,
+                Err(_) => {
+                    tracing::info!("WebSocket silent for {:?}, closing dead connection", self.idle_timeout);
+                    break;
+                }
+            };
+
             match msg {
                 Ok(axum::extract::ws::Message::Text(text)) => {
-                    match serde_json::from_str::<ClientMessage>(&text) {
-                        Ok(client_msg) => {
+                    match serde_json::from_str::<ClientEnvelope>(&text) {
+                        Ok(ClientEnvelope { message: client_msg, request_id }) => {
                             match self.handle_client_message(client_msg, &mut user_id, &mut broadcast_receiver).await {
                                 Ok(response_msg) => {
-                                    if let Some(msg) = response_msg {
-                                        if let Ok(msg_text) = serde_json::to_string(&msg) {
+                                    // Une requête corrélée reçoit toujours une réponse
+                                    let response_msg = response_msg
+                                        .or_else(|| request_id.map(|_| ServerMessage::Ack));
+                                    if let Some(message) = response_msg {
+                                        if let Ok(msg_text) = serde_json::to_string(&ServerEnvelope { message, request_id }) {
                                             let mut sender_guard = sender.lock().await;
                                             let _ = sender_guard.send(axum::extract::ws::Message::Text(msg_text)).await;
                                         }
@@ -73,6 +157,13 @@ impl WebSocketHandler {
                                     
                                     // If we just authenticated and have a new receiver, start broadcast listener
                                     if let Some(mut receiver) = broadcast_receiver.take() {
+                                        own_sender = user_id
+                                            .and_then(|uid| self.connections.get(&uid).map(|sender| sender.clone()));
+                                        if let Some(uid) = user_id {
+                                            let (kick_tx, rx) = oneshot::channel();
+                                            self.kicks.insert(uid, kick_tx);
+                                            kick_rx = Some(rx);
+                                        }
                                         let sender_clone = sender_for_broadcast.clone();
                                         tokio::spawn(async move {
                                             while let Ok(broadcast_msg) = receiver.recv().await {
@@ -87,8 +178,9 @@ impl WebSocketHandler {
                                 }
                                 Err(e) => {
                                     tracing::error!("Error handling client message: {}", e);
-                                    let error_msg = ServerMessage::Error {
-                                        message: e.to_string(),
+                                    let error_msg = ServerEnvelope {
+                                        message: ServerMessage::Error { message: e.to_string() },
+                                        request_id,
                                     };
                                     if let Ok(error_text) = serde_json::to_string(&error_msg) {
                                         let mut sender_guard = sender.lock().await;
@@ -109,6 +201,34 @@ impl WebSocketHandler {
                         }
                     }
                 }
+                Ok(axum::extract::ws::Message::Binary(data)) => {
+                    // Packet audio tunnelé : le client n'obtient pas de réponse en UDP
+                    let Some(uid) = user_id else {
+                        tracing::warn!("Ignoring audio frame from unauthenticated WebSocket");
+                        continue;
+                    };
+                    let tunnel_tx = tunnel.get_or_insert_with(|| {
+                        let (tunnel_tx, mut tunnel_rx) = mpsc::channel::<Vec<u8>>(TUNNEL_QUEUE_SIZE);
+                        let sender_clone = sender_for_broadcast.clone();
+                        tokio::spawn(async move {
+                            while let Some(bytes) = tunnel_rx.recv().await {
+                                let mut sender_guard = sender_clone.lock().await;
+                                if sender_guard.send(axum::extract::ws::Message::Binary(bytes)).await.is_err() {
+                                    break;
+                                }
+                            }
+                        });
+                        tunnel_tx
+                    });
+                    // Aussi après un retour à l'UDP, si celui-ci retombe en panne
+                    if !self.audio_service.has_tunnel(&uid) {
+                        self.audio_service.register_tunnel(uid, tunnel_tx.clone());
+                        tracing::info!("User {} switched audio to the WebSocket tunnel", uid);
+                    }
+                    if let Err(e) = self.audio_service.handle_tunnel_packet(uid, &data).await {
+                        tracing::warn!("Error processing tunnelled packet from {}: {}", uid, e);
+                    }
+                }
                 Ok(axum::extract::ws::Message::Close(_)) => break,
                 Err(e) => {
                     tracing::error!("WebSocket error: {}", e);
@@ -118,9 +238,25 @@ impl WebSocketHandler {
             }
         }
 
-        // Cleanup on disconnect
+        // Signal de fermeture de cette connexion, sauf si une autre l'a remplacé
+        drop(kick_rx);
+        if let Some(uid) = user_id {
+            self.kicks.remove_if(&uid, |_, kick| kick.is_closed());
+        }
+
+        // Le tunnel de cette connexion est mort (une reprise en ouvrira un autre)
+        if let (Some(uid), Some(tunnel)) = (user_id, &tunnel) {
+            self.audio_service.unregister_tunnel(&uid, tunnel);
+        }
+
+        // Cleanup on disconnect, sauf si la session a déjà été reprise par une autre connexion
         if let Some(uid) = user_id {
-            self.handle_user_disconnect(uid).await;
+            let is_current = own_sender.is_some_and(|own| {
+                self.connections.get(&uid).is_some_and(|sender| sender.same_channel(&own))
+            });
+            if is_current {
+                self.suspend_session(uid).await;
+            }
         }
     }
 
@@ -138,14 +274,30 @@ impl WebSocketHandler {
                 
                 let uid = existing_user.id;
                 *user_id = Some(uid);
+                self.attach_connection(uid, broadcast_receiver).await?;
 
-                // Create user-specific broadcast channel
-                let (user_sender, user_receiver) = broadcast::channel(100);
-                self.connections.insert(uid, user_sender);
-                *broadcast_receiver = Some(user_receiver);
+                let resume_token = self.issue_resume_token(uid);
 
                 tracing::info!("User {} ({}) authenticated via WebSocket", username, uid);
-                Ok(Some(ServerMessage::Authenticated { user_id: uid }))
+                Ok(Some(ServerMessage::Authenticated { user_id: uid, resume_token }))
+            }
+
+            ClientMessage::Resume { resume_token } => {
+                // Jeton à usage unique : une copie rejouée plus tard ne reprend rien
+                let uid = self.resume_tokens.remove(&resume_token)
+                    .map(|(_, owner)| owner)
+                    .filter(|uid| self.user_service.get_user(uid).is_ok())
+                    .ok_or_else(|| Error::User("Session expired".to_string()))?;
+
+                *user_id = Some(uid);
+                self.attach_connection(uid, broadcast_receiver).await?;
+
+                let user = self.user_service.get_user(&uid)?;
+                let channel_id = user.current_channel;
+                self.audio_service.set_user_deafened(uid, user.self_deafened);
+                let resume_token = self.issue_resume_token(uid);
+                tracing::info!("User {} resumed its session (channel {:?})", uid, channel_id);
+                Ok(Some(ServerMessage::Resumed { user_id: uid, channel_id, resume_token }))
             }
 
             ClientMessage::JoinChannel { channel_id, password } => {
@@ -158,6 +310,9 @@ impl WebSocketHandler {
                 
                 // Update user's current channel
                 self.user_service.user_join_channel(&uid, channel_id)?;
+                self.audio_service.add_user_to_channel(uid, channel_id);
+                let deafened = self.user_service.get_user(&uid)?.self_deafened;
+                self.audio_service.set_user_deafened(uid, deafened);
 
                 // Notify ALL connected users (not just those in channel)
                 // This allows all clients to update their channel list
@@ -165,10 +320,16 @@ impl WebSocketHandler {
                     ServerMessage::UserJoined { channel_id, user_id: uid },
                     None
                 ).await?;
+                self.notify_channel_updated(channel_id).await;
 
                 // Send current user list to the new user
                 let users = self.channel_service.get_users_in_channel(&channel_id)?;
                 self.send_to_user(uid, ServerMessage::ChannelUsers { channel_id, users }).await?;
+                let profile = self.audio_service.channel_profile(&channel_id);
+                self.send_to_user(uid, ServerMessage::EncodingProfileChanged { channel_id, profile }).await?;
+                let mut config = self.audio_service.get_channel_routing_config(&channel_id);
+                config.max_users = self.channel_service.get_channel(&channel_id)?.max_users;
+                self.send_to_user(uid, ServerMessage::ChannelRoutingChanged { channel_id, config }).await?;
 
                 tracing::info!("User {} joined channel {}", uid, channel_id);
                 Ok(Some(ServerMessage::JoinedChannel { channel_id }))
@@ -180,6 +341,7 @@ impl WebSocketHandler {
                 // Leave channel
                 self.channel_service.leave_channel(&channel_id, &uid)?;
                 self.user_service.user_leave_channel(&uid)?;
+                self.audio_service.remove_user_from_channel(&uid, &channel_id);
 
                 // Notify ALL connected users (not just those in channel)
                 // This allows all clients to update their channel list
@@ -187,6 +349,7 @@ impl WebSocketHandler {
                     ServerMessage::UserLeft { channel_id, user_id: uid },
                     None
                 ).await?;
+                self.notify_channel_updated(channel_id).await;
 
                 tracing::info!("User {} left channel {}", uid, channel_id);
                 Ok(Some(ServerMessage::LeftChannel { channel_id }))
@@ -229,12 +392,112 @@ impl WebSocketHandler {
                 Ok(None)
             }
 
+            ClientMessage::SetVoiceState { muted, deafened } => {
+                let uid = user_id.ok_or_else(|| Error::User("Not authenticated".to_string()))?;
+
+                let user = self.user_service.set_voice_state(&uid, muted, deafened)?;
+                self.audio_service.set_user_deafened(uid, deafened);
+
+                if let Some(channel_id) = user.current_channel {
+                    self.broadcast_to_channel(
+                        channel_id,
+                        ServerMessage::VoiceStateChanged { channel_id, user_id: uid, muted, deafened },
+                        None
+                    ).await?;
+                    self.notify_channel_updated(channel_id).await;
+                }
+                tracing::info!("User {} voice state: muted={}, deafened={}", uid, muted, deafened);
+                Ok(None)
+            }
+
             ClientMessage::Ping => {
                 Ok(Some(ServerMessage::Pong))
             }
         }
     }
 
+    /// Attend la demande de fermeture de ce WebSocket ; `false` si elle n'arrivera plus
+    /// (signal remplacé par une autre connexion du même utilisateur)
+    async fn kicked(kick_rx: &mut Option<oneshot::Receiver<()>>) -> bool {
+        match kick_rx {
+            Some(rx) => {
+                let kicked = rx.await.is_ok();
+                *kick_rx = None;
+                kicked
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Associe une nouvelle connexion à l'utilisateur et annule une éventuelle suspension
+    async fn attach_connection(
+        &self,
+        user_id: Uuid,
+        broadcast_receiver: &mut Option<broadcast::Receiver<ServerMessage>>,
+    ) -> Result<()> {
+        // Create user-specific broadcast channel
+        let (user_sender, user_receiver) = broadcast::channel(100);
+        self.connections.insert(user_id, user_sender);
+        *broadcast_receiver = Some(user_receiver);
+
+        if let Some((_, session)) = self.suspended.remove(&user_id) {
+            let status = session.previous_status;
+            if self.user_service.update_user_status(&user_id, status.clone()).is_ok() {
+                let _ = self.broadcast_to_all(ServerMessage::UserStatusChanged { user_id, status }, None).await;
+            }
+        }
+
+        // Snapshot initial des channels, les changements suivants arrivent en diff
+        self.send_to_user(user_id, ServerMessage::ChannelList { channels: self.enriched_channels() }).await
+    }
+
+    /// Coupure du WebSocket : l'utilisateur garde son identité, son channel et sa route UDP
+    /// pendant le délai de grâce, puis il est supprimé s'il n'a pas repris sa session
+    async fn suspend_session(self: Arc<Self>, user_id: Uuid) {
+        self.connections.remove(&user_id);
+
+        let Ok(user) = self.user_service.get_user(&user_id) else {
+            // Déjà supprimé (déconnexion HTTP explicite)
+            self.handle_user_disconnect(user_id).await;
+            return;
+        };
+        if self.grace_period.is_zero() {
+            self.handle_user_disconnect(user_id).await;
+            return;
+        }
+
+        let disconnected_at = Instant::now();
+        self.suspended.insert(user_id, SuspendedSession { disconnected_at, previous_status: user.status });
+        if self.user_service.update_user_status(&user_id, UserStatus::Reconnecting).is_ok() {
+            let _ = self.broadcast_to_all(
+                ServerMessage::UserStatusChanged { user_id, status: UserStatus::Reconnecting },
+                None
+            ).await;
+        }
+        tracing::info!("User {} lost its connection, session kept for {:?}", user_id, self.grace_period);
+
+        let handler = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(handler.grace_period).await;
+            // Toujours suspendu depuis la même coupure : la session expire
+            let expired = handler.suspended
+                .remove_if(&user_id, |_, session| session.disconnected_at == disconnected_at)
+                .is_some();
+            if expired {
+                tracing::info!("Session of user {} expired", user_id);
+                handler.handle_user_disconnect(user_id).await;
+            }
+        });
+    }
+
+    /// Émet un nouveau jeton de reprise pour l'utilisateur ; les précédents ne sont plus valables
+    fn issue_resume_token(&self, user_id: Uuid) -> Uuid {
+        let resume_token = Uuid::new_v4();
+        self.resume_tokens.retain(|_, owner| *owner != user_id);
+        self.resume_tokens.insert(resume_token, user_id);
+        resume_token
+    }
+
     async fn send_to_user(&self, user_id: Uuid, message: ServerMessage) -> Result<()> {
         if let Some(sender) = self.connections.get(&user_id) {
             tracing::debug!("Sending message to user {}: {:?}", user_id, message);
@@ -293,15 +556,292 @@ impl WebSocketHandler {
         Ok(())
     }
 
+    /// Construit la vue enrichie d'un channel (utilisateurs, état de parole)
+    pub fn enriched_channel(&self, channel_id: &Uuid) -> Option<EnrichedChannelResponse> {
+        let channel = self.channel_service.get_channel(channel_id).ok()?;
+
+        let users = channel.current_users.iter()
+            .filter_map(|user_id| self.user_service.get_user(user_id).ok())
+            .map(|user| UserInfo {
+                id: user.id,
+                is_speaking: self.audio_service.is_user_speaking(&user.id),
+                username: user.username,
+                mic_enabled: !(user.self_muted || user.self_deafened),
+                speaker_enabled: !user.self_deafened,
+            })
+            .collect();
+
+        Some(EnrichedChannelResponse {
+            id: channel.id,
+            name: channel.name,
+            description: channel.description,
+            owner_id: channel.owner_id,
+            max_users: channel.max_users,
+            user_count: channel.current_users.len(), // Frontend compat
+            current_users: channel.current_users,
+            users,
+            is_private: channel.is_private,
+            has_password: channel.has_password,
+            created_at: channel.created_at,
+        })
+    }
+
+    /// Liste enrichie des channels publics
+    pub fn enriched_channels(&self) -> Vec<EnrichedChannelResponse> {
+        self.channel_service.list_channels()
+            .iter()
+            .filter_map(|channel| self.enriched_channel(&channel.id))
+            .collect()
+    }
+
+    /// Diffuse la création d'un channel à tous les clients
+    pub async fn notify_channel_created(&self, channel_id: Uuid) {
+        if let Some(channel) = self.enriched_channel(&channel_id).filter(|c| !c.is_private) {
+            let _ = self.broadcast_to_all(ServerMessage::ChannelCreated { channel }, None).await;
+        }
+    }
+
+    /// Diffuse le nouvel état d'un channel à tous les clients
+    pub async fn notify_channel_updated(&self, channel_id: Uuid) {
+        if let Some(channel) = self.enriched_channel(&channel_id).filter(|c| !c.is_private) {
+            let _ = self.broadcast_to_all(ServerMessage::ChannelUpdated { channel }, None).await;
+        }
+    }
+
+    /// Diffuse la suppression d'un channel à tous les clients
+    pub async fn notify_channel_deleted(&self, channel_id: Uuid) {
+        let _ = self.broadcast_to_all(ServerMessage::ChannelDeleted { channel_id }, None).await;
+    }
+
+    /// Démarre la diffusion des changements d'état de parole détectés sur l'UDP
+    pub fn start_speaking_monitor(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SPEAKING_CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                for change in self.audio_service.collect_speaking_changes(SPEAKING_HANGOVER) {
+                    let _ = self.broadcast_to_all(
+                        ServerMessage::UserSpeaking {
+                            channel_id: change.channel_id,
+                            user_id: change.user_id,
+                            is_speaking: change.is_speaking,
+                        },
+                        None
+                    ).await;
+                }
+            }
+        });
+    }
+
+    /// Démarre l'expiration des adresses UDP des clients qui n'envoient plus rien
+    pub fn start_client_expiry_monitor(self: Arc<Self>, timeout: Duration) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CLIENT_EXPIRY_CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                for uid in self.audio_service.expire_clients(timeout) {
+                    tracing::info!("UDP registration of user {} expired after {:?} without packets", uid, timeout);
+                }
+            }
+        });
+    }
+
+    /// Démarre l'envoi des défis d'adresse UDP, uniquement par le WebSocket authentifié
+    /// de l'utilisateur concerné
+    pub fn start_address_challenge_monitor(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ADDRESS_CHALLENGE_CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                for (uid, nonce) in self.audio_service.collect_address_challenges() {
+                    if let Err(e) = self.send_to_user(uid, ServerMessage::AddressChallenge { nonce }).await {
+                        tracing::debug!("Could not send address challenge to user {}: {}", uid, e);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Démarre l'annonce aux émetteurs des profils d'encodage adaptés à leur channel,
+    /// et la diffusion de la qualité de connexion de chaque membre
+    pub fn start_quality_monitor(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(QUALITY_CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                for (channel_id, profile) in self.audio_service.collect_profile_changes() {
+                    tracing::info!("Channel {} encoding profile now {:?}", channel_id, profile);
+                    let _ = self.broadcast_to_channel(
+                        channel_id,
+                        ServerMessage::EncodingProfileChanged { channel_id, profile },
+                        None
+                    ).await;
+                }
+                for (channel_id, users) in self.audio_service.collect_connection_quality() {
+                    let _ = self.broadcast_to_channel(
+                        channel_id,
+                        ServerMessage::ConnectionQuality { channel_id, users },
+                        None
+                    ).await;
+                }
+            }
+        });
+    }
+
+    /// Démarre la surveillance périodique de l'inactivité vocale
+    pub fn start_idle_monitor(self: Arc<Self>, afk: AfkConfig) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(afk.check_interval());
+            // Utilisateurs passés en Away par le serveur -> instant du passage
+            let mut auto_away: HashMap<Uuid, Instant> = HashMap::new();
+
+            loop {
+                interval.tick().await;
+                self.check_idle_users(&afk, &mut auto_away).await;
+            }
+        });
+
+        tracing::info!("AFK idle monitor started");
+    }
+
+    async fn check_idle_users(&self, afk: &AfkConfig, auto_away: &mut HashMap<Uuid, Instant>) {
+        // Restaurer le statut des utilisateurs redevenus actifs depuis leur passage en Away
+        let returned: Vec<Uuid> = auto_away.iter()
+            .filter(|(uid, marked_at)| {
+                self.audio_service.idle_duration(uid)
+                    .is_none_or(|idle| idle < marked_at.elapsed())
+            })
+            .map(|(uid, _)| *uid)
+            .collect();
+
+        for uid in returned {
+            auto_away.remove(&uid);
+
+            let Ok(user) = self.user_service.get_user(&uid) else { continue };
+            // Le statut a déjà été modifié (changement de channel, choix manuel...)
+            if user.status != UserStatus::Away {
+                continue;
+            }
+
+            let status = if user.current_channel.is_some() {
+                UserStatus::InChannel
+            } else {
+                UserStatus::Online
+            };
+
+            if self.user_service.update_user_status(&uid, status.clone()).is_ok() {
+                tracing::info!("User {} is active again", uid);
+                let _ = self.broadcast_to_all(ServerMessage::UserStatusChanged { user_id: uid, status }, None).await;
+            }
+        }
+
+        for uid in self.audio_service.idle_users(afk.idle_timeout()) {
+            if auto_away.contains_key(&uid) {
+                continue;
+            }
+
+            let Ok(user) = self.user_service.get_user(&uid) else { continue };
+            if matches!(user.status, UserStatus::Away | UserStatus::Reconnecting | UserStatus::Offline) {
+                continue;
+            }
+
+            if afk.move_to_afk_channel {
+                if let Some(from_channel) = user.current_channel {
+                    if !ChannelService::is_afk_channel(&from_channel) {
+                        if let Err(e) = self.move_to_afk_channel(uid, from_channel).await {
+                            tracing::warn!("Failed to move idle user {} to AFK channel: {}", uid, e);
+                        }
+                    }
+                }
+            }
+
+            if self.user_service.update_user_status(&uid, UserStatus::Away).is_ok() {
+                auto_away.insert(uid, Instant::now());
+                tracing::info!("User {} marked as away after {}s of inactivity", uid, afk.idle_timeout_secs);
+                let _ = self.broadcast_to_all(
+                    ServerMessage::UserStatusChanged { user_id: uid, status: UserStatus::Away },
+                    None
+                ).await;
+            }
+        }
+
+        // Oublier les utilisateurs déconnectés
+        auto_away.retain(|uid, _| self.user_service.get_user(uid).is_ok());
+    }
+
+    /// Place un utilisateur dans un channel à l'initiative du serveur et notifie les clients
+    pub async fn add_user_to_channel(&self, user_id: Uuid, channel_id: Uuid) -> Result<()> {
+        self.channel_service.join_channel(&channel_id, user_id, None)?;
+        self.user_service.user_join_channel(&user_id, channel_id)?;
+        self.audio_service.add_user_to_channel(user_id, channel_id);
+        self.broadcast_to_all(ServerMessage::UserJoined { channel_id, user_id }, None).await?;
+        self.notify_channel_updated(channel_id).await;
+
+        // Informer le client de son nouveau channel
+        self.send_to_user(user_id, ServerMessage::JoinedChannel { channel_id }).await
+    }
+
+    /// Retire un utilisateur d'un channel à l'initiative du serveur et notifie les clients
+    pub async fn remove_user_from_channel(&self, user_id: Uuid, channel_id: Uuid) -> Result<()> {
+        self.channel_service.leave_channel(&channel_id, &user_id)?;
+        self.user_service.user_leave_channel(&user_id)?;
+        self.audio_service.remove_user_from_channel(&user_id, &channel_id);
+        self.broadcast_to_all(ServerMessage::UserLeft { channel_id, user_id }, None).await?;
+        self.notify_channel_updated(channel_id).await;
+
+        self.send_to_user(user_id, ServerMessage::LeftChannel { channel_id }).await
+    }
+
+    /// Déplace un utilisateur inactif vers le channel AFK avec son audio coupé
+    async fn move_to_afk_channel(&self, user_id: Uuid, from_channel: Uuid) -> Result<()> {
+        let afk_channel_id = ChannelService::get_afk_channel_id();
+
+        self.remove_user_from_channel(user_id, from_channel).await?;
+        self.add_user_to_channel(user_id, afk_channel_id).await?;
+        self.audio_service.set_user_muted(user_id, true);
+
+        tracing::info!("Moved idle user {} from channel {} to AFK channel", user_id, from_channel);
+        Ok(())
+    }
+
+    /// Nombre de connexions WebSocket authentifiées
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Déconnecte un utilisateur côté serveur (administration) et ferme son WebSocket
+    pub async fn force_disconnect(&self, user_id: Uuid) {
+        if let Some((_, kick)) = self.kicks.remove(&user_id) {
+            let _ = kick.send(());
+        }
+        self.handle_user_disconnect(user_id).await;
+    }
+
     async fn handle_user_disconnect(&self, user_id: Uuid) {
+        let user_channels: Vec<Uuid> = self.channel_service.get_user_channels(&user_id)
+            .into_iter()
+            .map(|channel| channel.id)
+            .collect();
+
         // Remove from all channels
         self.channel_service.remove_user_from_all_channels(&user_id);
         
         // Remove user
         let _ = self.user_service.remove_user(&user_id);
+        self.audio_service.remove_user(&user_id);
         
         // Remove connection
         self.connections.remove(&user_id);
+        self.suspended.remove(&user_id);
+        self.resume_tokens.retain(|_, owner| *owner != user_id);
+
+        for channel_id in user_channels {
+            self.notify_channel_updated(channel_id).await;
+        }
 
         // Broadcast disconnect
         let _ = self.global_broadcast.send(ServerMessage::UserStatusChanged {
@@ -311,4 +851,151 @@ impl WebSocketHandler {
 
         tracing::info!("User {} disconnected", user_id);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::CreateUserRequest;
+
+    fn handler(grace_period: Duration) -> Arc<WebSocketHandler> {
+        let config = Config::default();
+        Arc::new(WebSocketHandler::new(
+            Arc::new(UserService::new()),
+            Arc::new(ChannelService::new(config.limits.clone())),
+            Arc::new(AudioService::new(config.audio.clone())),
+        ).with_grace_period(grace_period))
+    }
+
+    /// Crée l'utilisateur, le place dans le channel par défaut et retourne son jeton de reprise
+    async fn connect(handler: &WebSocketHandler, username: &str) -> (Uuid, Uuid) {
+        let user = handler.user_service
+            .create_user(CreateUserRequest { username: username.to_string() })
+            .unwrap();
+        handler.add_user_to_channel(user.id, ChannelService::get_default_channel_id()).await.unwrap();
+
+        let (mut user_id, mut receiver) = (None, None);
+        let authenticate = ClientMessage::Authenticate { username: username.to_string() };
+        match handler.handle_client_message(authenticate, &mut user_id, &mut receiver).await.unwrap() {
+            Some(ServerMessage::Authenticated { resume_token, .. }) => (user.id, resume_token),
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_request_correlation_envelope() {
+        let envelope: ClientEnvelope = serde_json::from_str(
+            r#"{ "action": "LeaveChannel", "payload": { "channel_id": "00000000-0000-0000-0000-000000000001" }, "request_id": 7 }"#
+        ).unwrap();
+        assert_eq!(envelope.request_id, Some(7));
+        assert!(matches!(envelope.message, ClientMessage::LeaveChannel { .. }));
+
+        // Sans identifiant : le format historique reste accepté
+        let envelope: ClientEnvelope = serde_json::from_str(r#"{ "action": "Ping" }"#).unwrap();
+        assert_eq!(envelope.request_id, None);
+
+        let response = serde_json::to_value(ServerEnvelope { message: ServerMessage::Ack, request_id: Some(7) }).unwrap();
+        assert_eq!(response, serde_json::json!({ "event": "Ack", "request_id": 7 }));
+        let event = serde_json::to_value(ServerEnvelope { message: ServerMessage::Pong, request_id: None }).unwrap();
+        assert_eq!(event, serde_json::json!({ "event": "Pong" }));
+    }
+
+    #[tokio::test]
+    async fn test_session_resumed_within_grace_period() {
+        let handler = handler(Duration::from_secs(30));
+        let (uid, resume_token) = connect(&handler, "alice").await;
+        let channel_id = ChannelService::get_default_channel_id();
+
+        handler.clone().suspend_session(uid).await;
+        assert_eq!(handler.user_service.get_user(&uid).unwrap().status, UserStatus::Reconnecting);
+        assert!(handler.channel_service.get_users_in_channel(&channel_id).unwrap().contains(&uid));
+
+        let (mut user_id, mut receiver) = (None, None);
+        let response = handler
+            .handle_client_message(ClientMessage::Resume { resume_token }, &mut user_id, &mut receiver)
+            .await
+            .unwrap();
+        let rotated = match response {
+            Some(ServerMessage::Resumed { user_id, channel_id: Some(channel), resume_token: rotated })
+                if user_id == uid && channel == channel_id => rotated,
+            other => panic!("unexpected response: {:?}", other),
+        };
+        assert_ne!(rotated, resume_token);
+        assert_eq!(handler.user_service.get_user(&uid).unwrap().status, UserStatus::InChannel);
+        assert!(handler.suspended.is_empty());
+
+        // L'ancien jeton ne sert qu'une fois ; le nouveau permet la reprise suivante
+        let (mut user_id, mut receiver) = (None, None);
+        assert!(handler
+            .handle_client_message(ClientMessage::Resume { resume_token }, &mut user_id, &mut receiver)
+            .await
+            .is_err());
+        let response = handler
+            .handle_client_message(ClientMessage::Resume { resume_token: rotated }, &mut user_id, &mut receiver)
+            .await
+            .unwrap();
+        assert!(matches!(response, Some(ServerMessage::Resumed { user_id, .. }) if user_id == uid));
+    }
+
+    #[tokio::test]
+    async fn test_session_expires_after_grace_period() {
+        let handler = handler(Duration::from_millis(20));
+        let (uid, resume_token) = connect(&handler, "bob").await;
+
+        handler.clone().suspend_session(uid).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(handler.user_service.get_user(&uid).is_err());
+        assert!(!handler.channel_service
+            .get_users_in_channel(&ChannelService::get_default_channel_id())
+            .unwrap()
+            .contains(&uid));
+
+        let (mut user_id, mut receiver) = (None, None);
+        let resume = handler
+            .handle_client_message(ClientMessage::Resume { resume_token }, &mut user_id, &mut receiver)
+            .await;
+        assert!(resume.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_force_disconnect_closes_socket() {
+        use tokio_tungstenite::tungstenite::Message;
+
+        let handler = handler(Duration::from_secs(30));
+        let user = handler.user_service
+            .create_user(CreateUserRequest { username: "carol".to_string() })
+            .unwrap();
+
+        let app = axum::Router::new()
+            .route("/ws", axum::routing::get(WebSocketHandler::handle_upgrade))
+            .with_state(handler.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", address)).await.unwrap();
+        let authenticate = serde_json::to_string(&ClientMessage::Authenticate { username: "carol".to_string() }).unwrap();
+        socket.send(Message::Text(authenticate)).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while !handler.kicks.contains_key(&user.id) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("socket never authenticated");
+
+        handler.force_disconnect(user.id).await;
+
+        let closed = tokio::time::timeout(Duration::from_secs(1), async {
+            while let Some(message) = socket.next().await {
+                if let Ok(Message::Close(frame)) = message {
+                    return frame;
+                }
+            }
+            None
+        }).await.expect("socket still open after force_disconnect");
+        assert_eq!(closed.map(|frame| u16::from(frame.code)), Some(axum::extract::ws::close_code::POLICY));
+        assert!(handler.kicks.is_empty());
+        assert!(handler.user_service.get_user(&user.id).is_err());
+    }
+}
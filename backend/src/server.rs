@@ -13,7 +13,7 @@ use crate::{
     handlers::ApiHandlers,
     services::{UserService, ChannelService, AudioService},
     networking::WebSocketHandler,
-    audio::{MetricsCollector, MetricsConfig},
+    audio::{AudioThreadPoolConfig, MetricsCollector, MetricsConfig, SystemMonitor},
     api::{create_metrics_router, MetricsApiState, MetricsApiConfig, create_advanced_router, AdvancedApiState, AdvancedApiConfig},
     Result,
 };
@@ -30,6 +30,9 @@ impl Server {
     pub async fn new(config: Config) -> Result<Self> {
         let user_service = Arc::new(UserService::new());
         let channel_service = Arc::new(ChannelService::new(config.limits.clone()));
+        if config.afk.enabled && config.afk.move_to_afk_channel {
+            channel_service.create_afk_channel(&config.afk.afk_channel_name);
+        }
         
         // Créer le service audio avec les services
         let mut audio_service = AudioService::with_services(
@@ -45,9 +48,17 @@ impl Server {
             channel_service.clone(),
         ).await?;
 
-        // Créer le collecteur de métriques (simplifié pour l'instant)
-        let metrics_config = MetricsConfig::default();
-        let metrics_collector = MetricsCollector::new(metrics_config);
+        audio_service.start_thread_pool(AudioThreadPoolConfig::default()).await;
+
+        // Brancher le collecteur de métriques sur les composants audio réels
+        let mut metrics_collector = MetricsCollector::new(MetricsConfig::default());
+        metrics_collector.register_components(
+            audio_service.thread_pool(),
+            audio_service.transport_stats(),
+            Some(audio_service.mixer()),
+            Some(audio_service.router()),
+        );
+        metrics_collector.start_collection().await;
 
         Ok(Self {
             config,
@@ -64,7 +75,18 @@ impl Server {
             self.user_service.clone(),
             self.channel_service.clone(),
             self.audio_service.clone(),
-        ));
+        ).with_grace_period(self.config.session.grace_period())
+            .with_idle_timeout(self.config.session.idle_timeout()));
+
+        ws_handler.clone().start_speaking_monitor();
+        ws_handler.clone().start_client_expiry_monitor(self.config.session.udp_client_timeout());
+        ws_handler.clone().start_address_challenge_monitor();
+        ws_handler.clone().start_quality_monitor();
+
+        // Surveiller l'inactivité vocale (statut Away / channel AFK)
+        if self.config.afk.enabled {
+            ws_handler.clone().start_idle_monitor(self.config.afk.clone());
+        }
 
         // Create API handlers with WebSocket handler
         let api_handlers = Arc::new(ApiHandlers::new(
@@ -85,7 +107,10 @@ impl Server {
             user_service: self.user_service.clone(),
             channel_service: self.channel_service.clone(),
             audio_service: self.audio_service.clone(),
+            websocket_handler: ws_handler.clone(),
             config: AdvancedApiConfig::default(),
+            started_at: std::time::Instant::now(),
+            system_monitor: Arc::new(SystemMonitor::new()),
         };
 
         // Create routers
@@ -1,23 +1,43 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use uuid::Uuid;
 use crate::{
     config::AudioConfig,
-    audio::AudioRouter,
-    networking::UdpServer,
+    audio::{AudioMixer, AudioPacket, AudioProcessingPriority, AudioRouter, AudioThreadPool, AudioThreadPoolConfig},
+    networking::{TransportStats, UdpServer},
     services::{UserService, ChannelService},
 };
 
+/// Durée d'une trame du mixage de supervision
+const MONITOR_FRAME: Duration = Duration::from_millis(20);
+/// Sans audio depuis ce délai, un channel sort des statistiques du mixeur
+const MONITOR_IDLE: Duration = Duration::from_secs(2);
+/// Packets en attente du mixage de supervision avant d'en perdre
+const MONITOR_QUEUE: usize = 1024;
+
 #[derive(Debug)]
 pub struct AudioService {
-    config: AudioConfig,
+    /// Configuration courante, partagée avec le serveur UDP pour prise en compte à chaud
+    config: Arc<RwLock<AudioConfig>>,
+    /// Configuration chargée au démarrage, utilisée pour les réinitialisations
+    default_config: AudioConfig,
     router: Arc<AudioRouter>,
+    /// Mixeur de supervision (niveaux par channel), partagé avec le pool de traitement
+    mixer: Arc<tokio::sync::RwLock<AudioMixer>>,
+    thread_pool: Option<Arc<AudioThreadPool>>,
     udp_server: Option<Arc<UdpServer>>,
 }
 
 impl AudioService {
     pub fn new(config: AudioConfig) -> Self {
         Self {
-            config,
+            config: Arc::new(RwLock::new(config.clone())),
+            mixer: Self::create_mixer(&config),
+            default_config: config,
             router: Arc::new(AudioRouter::new()),
+            thread_pool: None,
             udp_server: None,
         }
     }
@@ -30,17 +50,94 @@ impl AudioService {
         let router = Arc::new(AudioRouter::new());
         
         Self {
-            config,
+            config: Arc::new(RwLock::new(config.clone())),
+            mixer: Self::create_mixer(&config),
+            default_config: config,
             router,
+            thread_pool: None,
             udp_server: None,
         }
     }
 
+    fn create_mixer(config: &AudioConfig) -> Arc<tokio::sync::RwLock<AudioMixer>> {
+        Arc::new(tokio::sync::RwLock::new(AudioMixer::new(config.sample_rate, config.channels as u8)))
+    }
+
     /// Récupère le routeur audio
     pub fn router(&self) -> Arc<AudioRouter> {
         self.router.clone()
     }
 
+    /// Récupère le mixeur audio
+    pub fn mixer(&self) -> Arc<tokio::sync::RwLock<AudioMixer>> {
+        self.mixer.clone()
+    }
+
+    /// Pool de traitement audio, s'il a été démarré
+    pub fn thread_pool(&self) -> Option<Arc<AudioThreadPool>> {
+        self.thread_pool.clone()
+    }
+
+    /// Compteurs du transport UDP, si le serveur est démarré
+    pub fn transport_stats(&self) -> Option<Arc<TransportStats>> {
+        self.udp_server.as_ref().map(|server| server.transport_stats())
+    }
+
+    /// Démarre le pool de traitement audio et lui confie le mixage de supervision des channels :
+    /// les clients reçoivent toujours les flux séparés, le mix ne sert qu'aux niveaux par channel
+    pub async fn start_thread_pool(&mut self, config: AudioThreadPoolConfig) {
+        let pool = Arc::new(AudioThreadPool::new(config, self.mixer.clone()).await);
+        let (tap, packets) = mpsc::channel(MONITOR_QUEUE);
+        self.router.set_mix_tap(tap);
+        tokio::spawn(Self::run_channel_monitor(packets, pool.clone(), self.mixer.clone()));
+        self.thread_pool = Some(pool);
+        tracing::info!("Audio processing thread pool started");
+    }
+
+    /// Regroupe les packets routés par channel (le dernier de chaque voix) et soumet une trame au pool
+    async fn run_channel_monitor(
+        mut packets: mpsc::Receiver<AudioPacket>,
+        pool: Arc<AudioThreadPool>,
+        mixer: Arc<tokio::sync::RwLock<AudioMixer>>,
+    ) {
+        let mut frames: HashMap<Uuid, HashMap<Uuid, AudioPacket>> = HashMap::new();
+        let mut last_audio: HashMap<Uuid, Instant> = HashMap::new();
+        let mut tick = tokio::time::interval(MONITOR_FRAME);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            tokio::select! {
+                packet = packets.recv() => {
+                    let Some(packet) = packet else { break };
+                    frames.entry(packet.header.channel_id)
+                        .or_default()
+                        .insert(packet.header.user_id, packet);
+                }
+                _ = tick.tick() => {
+                    for (channel_id, voices) in frames.drain() {
+                        last_audio.insert(channel_id, Instant::now());
+                        let voices = voices.into_values().collect();
+                        if let Err(e) = pool.submit_mix_task(channel_id, voices, AudioProcessingPriority::Low).await {
+                            tracing::debug!("Channel {} monitor frame dropped: {}", channel_id, e);
+                        }
+                    }
+
+                    let idle: Vec<Uuid> = last_audio.iter()
+                        .filter(|(_, at)| at.elapsed() >= MONITOR_IDLE)
+                        .map(|(channel_id, _)| *channel_id)
+                        .collect();
+                    if !idle.is_empty() {
+                        let mut mixer = mixer.write().await;
+                        for channel_id in idle {
+                            last_audio.remove(&channel_id);
+                            mixer.remove_channel(&channel_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// Démarre le serveur UDP audio
     pub async fn start_udp_server(
         &mut self,
@@ -50,6 +147,7 @@ impl AudioService {
     ) -> crate::Result<()> {
         let udp_server = Arc::new(UdpServer::new(
             config,
+            self.config.clone(),
             self.router.clone(),
             user_service,
             channel_service,
@@ -96,6 +194,36 @@ impl AudioService {
         }
     }
 
+    /// Coupe ou rétablit la redistribution de l'audio d'un utilisateur
+    pub fn set_user_muted(&self, user_id: uuid::Uuid, muted: bool) {
+        self.router.set_user_muted(user_id, muted);
+    }
+
+    /// Coupe ou rétablit l'envoi de l'audio du channel à un utilisateur (sourdine)
+    pub fn set_user_deafened(&self, user_id: uuid::Uuid, deafened: bool) {
+        self.router.set_user_deafened(user_id, deafened);
+    }
+
+    /// Utilisateurs sans activité vocale depuis au moins `timeout`
+    pub fn idle_users(&self, timeout: std::time::Duration) -> Vec<uuid::Uuid> {
+        self.router.idle_users(timeout)
+    }
+
+    /// Durée écoulée depuis la dernière activité vocale d'un utilisateur
+    pub fn idle_duration(&self, user_id: &uuid::Uuid) -> Option<std::time::Duration> {
+        self.router.idle_duration(user_id)
+    }
+
+    /// Indique si un utilisateur est en train de parler
+    pub fn is_user_speaking(&self, user_id: &uuid::Uuid) -> bool {
+        self.router.is_user_speaking(user_id)
+    }
+
+    /// Changements d'état de parole depuis le dernier appel
+    pub fn collect_speaking_changes(&self, hangover: std::time::Duration) -> Vec<crate::audio::router::SpeakingChange> {
+        self.router.collect_speaking_changes(hangover)
+    }
+
     /// Ajuste la latence pour un channel
     pub fn adjust_channel_latency(&self, channel_id: &uuid::Uuid, latency_ms: u64) {
         self.router.adjust_channel_latency(channel_id, latency_ms);
@@ -110,20 +238,178 @@ impl AudioService {
         }
     }
 
+    /// Nettoie les ressources de routage orphelines
+    pub fn cleanup_stale<U, C>(&self, user_exists: U, channel_exists: C) -> (usize, usize, usize)
+    where
+        U: Fn(&uuid::Uuid) -> bool,
+        C: Fn(&uuid::Uuid) -> bool,
+    {
+        self.router.cleanup_buffers();
+        self.router.cleanup_stale(user_exists, channel_exists)
+    }
+
+    /// Fait passer l'audio d'un client par son WebSocket (UDP bloqué)
+    pub fn register_tunnel(&self, user_id: uuid::Uuid, sender: crate::audio::router::TunnelSender) {
+        self.router.register_tunnel(user_id, sender);
+    }
+
+    /// Indique si l'audio destiné à ce client passe par son WebSocket
+    pub fn has_tunnel(&self, user_id: &uuid::Uuid) -> bool {
+        self.router.has_tunnel(user_id)
+    }
+
+    /// Retire le tunnel WebSocket d'un client
+    pub fn unregister_tunnel(&self, user_id: &uuid::Uuid, sender: &crate::audio::router::TunnelSender) {
+        self.router.unregister_tunnel(user_id, sender);
+    }
+
+    /// Traite un packet audio reçu par le tunnel WebSocket d'un utilisateur
+    pub async fn handle_tunnel_packet(&self, user_id: uuid::Uuid, data: &[u8]) -> crate::Result<()> {
+        match self.udp_server {
+            Some(ref udp_server) => udp_server.handle_tunnel_packet(user_id, data).await,
+            None => Err(crate::Error::Audio("Audio transport not running".to_string())),
+        }
+    }
+
+    /// Défis d'adresse à transmettre par le WebSocket de chaque client : (user_id, nonce)
+    pub fn collect_address_challenges(&self) -> Vec<(uuid::Uuid, uuid::Uuid)> {
+        self.router.collect_address_challenges()
+    }
+
+    /// Oublie les adresses UDP des clients muets depuis plus de `timeout`
+    pub fn expire_clients(&self, timeout: std::time::Duration) -> Vec<uuid::Uuid> {
+        self.router.expire_clients(timeout)
+    }
+
+    /// Profil d'encodage courant d'un channel
+    pub fn channel_profile(&self, channel_id: &uuid::Uuid) -> crate::audio::router::EncodingProfile {
+        self.router.channel_profile(channel_id)
+    }
+
+    /// Profils d'encodage modifiés depuis le dernier appel
+    pub fn collect_profile_changes(&self) -> Vec<(uuid::Uuid, crate::audio::router::EncodingProfile)> {
+        self.router.collect_profile_changes()
+    }
+
+    /// Qualité de connexion des membres de chaque channel occupé
+    pub fn collect_connection_quality(&self) -> Vec<(uuid::Uuid, Vec<crate::audio::router::ConnectionQuality>)> {
+        self.router.collect_connection_quality()
+    }
+
+    /// Analyse les performances de routage d'un channel
+    pub fn analyze_channel_performance(&self, channel_id: &uuid::Uuid) -> Option<crate::audio::router::ChannelPerformanceReport> {
+        self.router.analyze_channel_performance(channel_id)
+    }
+
+    /// Cumul du trafic audio : (packets, bytes)
+    pub fn total_traffic(&self) -> (u64, u64) {
+        self.router.total_traffic()
+    }
+
+    /// Réinitialise l'état de routage audio
+    pub fn reset_routing(&self) {
+        self.router.reset();
+    }
+
+    /// Configuration de routage d'un channel (valeurs par défaut si non configuré)
+    pub fn get_channel_routing_config(&self, channel_id: &uuid::Uuid) -> crate::audio::router::ChannelRoutingConfig {
+        self.router.channel_config(channel_id)
+    }
+
+    /// Indique si un channel a sa propre configuration de routage
+    pub fn has_channel_routing_config(&self, channel_id: &uuid::Uuid) -> bool {
+        self.router.get_channel_config(channel_id).is_some()
+    }
+
+    /// Configuration de routage des channels non configurés
+    pub fn channel_routing_defaults(&self) -> crate::audio::router::ChannelRoutingConfig {
+        self.router.channel_defaults()
+    }
+
+    /// Remplace la configuration de routage des channels non configurés
+    pub fn set_channel_routing_defaults(&self, config: crate::audio::router::ChannelRoutingConfig) {
+        self.router.set_channel_defaults(config);
+    }
+
+    /// Applique une configuration de routage à un channel
+    pub fn set_channel_routing_config(&self, channel_id: uuid::Uuid, config: crate::audio::router::ChannelRoutingConfig) {
+        self.router.configure_channel(channel_id, config);
+    }
+
+    /// Paramètres audio négociés avec les clients
+    pub fn negotiated_config(&self) -> crate::models::AudioConfigResponse {
+        let config = self.config();
+        crate::models::AudioConfigResponse {
+            sample_rate: config.sample_rate,
+            channels: config.channels,
+            buffer_size: config.buffer_size,
+            max_packet_size: config.max_packet_size,
+            codec: "Opus".to_string(), // For now, hardcoded
+        }
+    }
+
+    /// Configuration audio courante
+    pub fn config(&self) -> AudioConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Remplace la configuration audio courante
+    pub fn update_config(&self, config: AudioConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// Restaure la configuration audio chargée au démarrage
+    pub fn reset_config(&self) -> AudioConfig {
+        let config = self.default_config.clone();
+        self.update_config(config.clone());
+        config
+    }
+
     // Méthodes de configuration existantes
     pub fn get_sample_rate(&self) -> u32 {
-        self.config.sample_rate
+        self.config.read().unwrap().sample_rate
     }
 
     pub fn get_channels(&self) -> u16 {
-        self.config.channels
+        self.config.read().unwrap().channels
     }
 
     pub fn get_buffer_size(&self) -> usize {
-        self.config.buffer_size
+        self.config.read().unwrap().buffer_size
     }
 
     pub fn get_max_packet_size(&self) -> usize {
-        self.config.max_packet_size
+        self.config.read().unwrap().max_packet_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_routed_audio_feeds_channel_mixer() {
+        let mut service = AudioService::new(crate::config::Config::default().audio);
+        service.start_thread_pool(AudioThreadPoolConfig { max_workers: 2, ..Default::default() }).await;
+
+        let router = service.router();
+        let (speaker, listener, channel_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        router.add_user_to_channel(speaker, channel_id);
+        router.add_user_to_channel(listener, channel_id);
+        let packet = AudioPacket::audio(speaker, channel_id, 0, bytes::Bytes::from(vec![0x00, 0x40].repeat(480)), 48000, 1);
+        router.route_packet(&packet, speaker, channel_id);
+
+        let mixer = service.mixer();
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while mixer.read().await.get_stats(&channel_id).is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("routed audio never reached the channel mixer");
+
+        let stats = mixer.read().await.get_stats(&channel_id).cloned().unwrap();
+        assert_eq!(stats.active_voices, 1);
+        assert!(stats.peak_level > 0.0);
+        let pool = service.thread_pool().unwrap().get_stats().await;
+        assert_eq!(pool.completed_tasks, 1);
     }
-}
\ No newline at end of file
+}
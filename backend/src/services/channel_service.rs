@@ -14,6 +14,8 @@ use crate::{
 // ID du channel par défaut qui ne peut pas être supprimé
 const DEFAULT_CHANNEL_ID: &str = "00000000-0000-0000-0000-000000000001";
 const SYSTEM_USER_ID: &str = "00000000-0000-0000-0000-000000000000";
+// ID du channel AFK (créé au démarrage si activé dans la configuration)
+const AFK_CHANNEL_ID: &str = "00000000-0000-0000-0000-000000000002";
 
 #[derive(Debug)]
 pub struct ChannelService {
@@ -62,6 +64,29 @@ impl ChannelService {
         tracing::info!("Created default channel 'General' ({})", default_channel_id);
     }
 
+    /// Crée le channel AFK qui accueille les utilisateurs inactifs (non supprimable)
+    pub fn create_afk_channel(&self, name: &str) -> Uuid {
+        let afk_channel_id = Self::get_afk_channel_id();
+        let system_user_id = Uuid::parse_str(SYSTEM_USER_ID)
+            .expect("Failed to parse system user UUID");
+
+        let afk_channel = Channel {
+            id: afk_channel_id,
+            name: name.to_string(),
+            description: Some("Idle users are moved here automatically".to_string()),
+            owner_id: system_user_id,
+            max_users: self.limits.max_concurrent_connections,
+            current_users: Vec::new(),
+            is_private: false,
+            password: None,
+            created_at: std::time::SystemTime::now(),
+        };
+
+        self.channels.insert(afk_channel_id, afk_channel);
+        tracing::info!("Created AFK channel '{}' ({})", name, afk_channel_id);
+        afk_channel_id
+    }
+
     pub fn create_channel(
         &self,
         request: CreateChannelRequest,
@@ -126,6 +151,75 @@ impl ChannelService {
             .collect()
     }
 
+    /// Liste tous les channels, y compris les channels privés (administration)
+    pub fn list_all_channels(&self) -> Vec<ChannelResponse> {
+        self.channels
+            .iter()
+            .map(|entry| ChannelResponse::from(entry.value().clone()))
+            .collect()
+    }
+
+    /// Met à jour les propriétés d'un channel existant
+    pub fn update_channel(
+        &self,
+        channel_id: &Uuid,
+        name: Option<String>,
+        description: Option<String>,
+        max_users: Option<usize>,
+        is_private: Option<bool>,
+    ) -> Result<DetailedChannelResponse> {
+        if let Some(ref name) = name {
+            if name.trim().is_empty() {
+                return Err(Error::Channel("Channel name cannot be empty".to_string()));
+            }
+
+            if name.len() > 100 {
+                return Err(Error::Channel("Channel name too long (max 100 characters)".to_string()));
+            }
+
+            if self.channels.iter().any(|entry| entry.value().name == *name && entry.key() != channel_id) {
+                return Err(Error::Conflict(format!(
+                    "Channel name '{}' is already taken",
+                    name
+                )));
+            }
+        }
+
+        let mut channel = self.channels
+            .get_mut(channel_id)
+            .ok_or_else(|| Error::Channel(format!("Channel {} not found", channel_id)))?;
+
+        if let Some(max_users) = max_users {
+            if max_users == 0 || max_users > self.limits.max_users_per_channel {
+                return Err(Error::Channel(format!(
+                    "max_users must be between 1 and {}",
+                    self.limits.max_users_per_channel
+                )));
+            }
+            if max_users < channel.user_count() {
+                return Err(Error::Channel(format!(
+                    "Channel currently has {} users, cannot lower max_users to {}",
+                    channel.user_count(),
+                    max_users
+                )));
+            }
+            channel.max_users = max_users;
+        }
+
+        if let Some(name) = name {
+            channel.name = name;
+        }
+        if let Some(description) = description {
+            channel.description = Some(description).filter(|d| !d.is_empty());
+        }
+        if let Some(is_private) = is_private {
+            channel.is_private = is_private;
+        }
+
+        tracing::info!("Updated channel: {} ({})", channel.name, channel_id);
+        Ok(DetailedChannelResponse::from(channel.clone()))
+    }
+
     pub fn join_channel(
         &self,
         channel_id: &Uuid,
@@ -178,6 +272,10 @@ impl ChannelService {
         if *channel_id == default_channel_id {
             return Err(Error::Channel("Cannot delete the default channel".to_string()));
         }
+
+        if Self::is_afk_channel(channel_id) {
+            return Err(Error::Channel("Cannot delete the AFK channel".to_string()));
+        }
         
         let channel = self.channels
             .get(channel_id)
@@ -197,6 +295,47 @@ impl ChannelService {
         }
     }
 
+    /// Supprime un channel sans vérification du propriétaire (administration).
+    /// Retourne les utilisateurs qui s'y trouvaient.
+    pub fn force_delete_channel(&self, channel_id: &Uuid) -> Result<Vec<Uuid>> {
+        if Self::is_default_channel(channel_id) {
+            return Err(Error::Channel("Cannot delete the default channel".to_string()));
+        }
+
+        if Self::is_afk_channel(channel_id) {
+            return Err(Error::Channel("Cannot delete the AFK channel".to_string()));
+        }
+
+        match self.channels.remove(channel_id) {
+            Some((_, channel)) => {
+                tracing::info!("Force deleted channel: {} ({})", channel.name, channel_id);
+                Ok(channel.current_users)
+            }
+            None => Err(Error::Channel(format!("Channel {} not found", channel_id))),
+        }
+    }
+
+    /// Supprime tous les channels créés par les utilisateurs et vide les channels système.
+    /// Retourne les IDs des channels supprimés.
+    pub fn reset_channels(&self) -> Vec<Uuid> {
+        let removed: Vec<Uuid> = self.channels
+            .iter()
+            .filter(|entry| !Self::is_default_channel(entry.key()) && !Self::is_afk_channel(entry.key()))
+            .map(|entry| *entry.key())
+            .collect();
+
+        for channel_id in &removed {
+            self.channels.remove(channel_id);
+        }
+
+        for mut channel in self.channels.iter_mut() {
+            channel.current_users.clear();
+        }
+
+        tracing::info!("Reset channels: {} channel(s) removed", removed.len());
+        removed
+    }
+
     pub fn get_user_channels(&self, user_id: &Uuid) -> Vec<ChannelResponse> {
         self.channels
             .iter()
@@ -216,6 +355,17 @@ impl ChannelService {
         *channel_id == Self::get_default_channel_id()
     }
 
+    /// Retourne l'ID du channel AFK
+    pub fn get_afk_channel_id() -> Uuid {
+        Uuid::parse_str(AFK_CHANNEL_ID)
+            .expect("Failed to parse AFK channel UUID")
+    }
+
+    /// Vérifie si un channel est le channel AFK
+    pub fn is_afk_channel(channel_id: &Uuid) -> bool {
+        *channel_id == Self::get_afk_channel_id()
+    }
+
     pub fn get_users_in_channel(&self, channel_id: &Uuid) -> Result<Vec<Uuid>> {
         match self.channels.get(channel_id) {
             Some(channel) => Ok(channel.current_users.clone()),
@@ -223,6 +373,20 @@ impl ChannelService {
         }
     }
 
+    /// Retire des channels les membres qui n'existent plus. Retourne le nombre retiré.
+    pub fn remove_unknown_members<F>(&self, user_exists: F) -> usize
+    where
+        F: Fn(&Uuid) -> bool,
+    {
+        let mut removed = 0;
+        for mut channel in self.channels.iter_mut() {
+            let before = channel.current_users.len();
+            channel.current_users.retain(|user_id| user_exists(user_id));
+            removed += before - channel.current_users.len();
+        }
+        removed
+    }
+
     // Force remove user from all channels (useful when user disconnects)
     pub fn remove_user_from_all_channels(&self, user_id: &Uuid) {
         for mut channel in self.channels.iter_mut() {
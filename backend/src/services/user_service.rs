@@ -77,6 +77,16 @@ impl UserService {
         }
     }
 
+    pub fn set_voice_state(&self, user_id: &Uuid, muted: bool, deafened: bool) -> Result<UserResponse> {
+        match self.users.get_mut(user_id) {
+            Some(mut user) => {
+                user.set_voice_state(muted, deafened);
+                Ok(user.clone().into())
+            }
+            None => Err(Error::User(format!("User {} not found", user_id))),
+        }
+    }
+
     pub fn user_join_channel(&self, user_id: &Uuid, channel_id: Uuid) -> Result<()> {
         match self.users.get_mut(user_id) {
             Some(mut user) => {
@@ -112,6 +122,51 @@ impl UserService {
         }
     }
 
+    /// Renomme un utilisateur en conservant l'unicité des noms
+    pub fn rename_user(&self, user_id: &Uuid, new_username: String) -> Result<UserResponse> {
+        if new_username.trim().is_empty() {
+            return Err(Error::User("Username cannot be empty".to_string()));
+        }
+
+        if new_username.len() > 50 {
+            return Err(Error::User("Username too long (max 50 characters)".to_string()));
+        }
+
+        if let Some(existing_id) = self.username_to_id.get(&new_username) {
+            if *existing_id != *user_id {
+                return Err(Error::User(format!(
+                    "Username '{}' is already taken",
+                    new_username
+                )));
+            }
+        }
+
+        let mut user = self.users
+            .get_mut(user_id)
+            .ok_or_else(|| Error::User(format!("User {} not found", user_id)))?;
+
+        let old_username = std::mem::replace(&mut user.username, new_username.clone());
+        user.update_last_seen();
+        let response = UserResponse::from(user.clone());
+        drop(user);
+
+        self.username_to_id.remove(&old_username);
+        self.username_to_id.insert(new_username, *user_id);
+
+        tracing::info!("Renamed user {} from '{}' to '{}'", user_id, old_username, response.username);
+        Ok(response)
+    }
+
+    /// Recherche les utilisateurs dont le nom contient `query` (insensible à la casse)
+    pub fn search_users(&self, query: &str) -> Vec<UserResponse> {
+        let query = query.to_lowercase();
+        self.users
+            .iter()
+            .filter(|entry| entry.value().username.to_lowercase().contains(&query))
+            .map(|entry| UserResponse::from(entry.value().clone()))
+            .collect()
+    }
+
     pub fn list_users(&self) -> Vec<UserResponse> {
         self.users
             .iter()
@@ -1,13 +1,104 @@
 use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
-    Device, SampleFormat, StreamConfig,
+    Device, Sample, SampleFormat, StreamConfig,
 };
 use anyhow::{Result, Context};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use parking_lot::RwLock;
 use tokio::sync::mpsc;
+use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 use crate::networking::AudioUdpClient;
+use super::noise_suppression::{NoiseSuppressionLevel, NoiseSuppressor};
+use super::echo_cancellation::{EchoCanceller, SharedEchoReference};
+use super::gain::{InputGainProcessor, InputGainSettings, LevelMeter};
+use super::stream_config::{resolve_config, StreamSettings};
+use super::encoding::{EncodingProfile, FrameEncoder};
+
+/// Réglages du traitement du micro, partagés avec la tâche de capture (modifiables à chaud)
+#[derive(Debug, Clone)]
+struct CaptureProcessing {
+    noise_suppression: Arc<RwLock<NoiseSuppressionLevel>>,
+    echo_cancellation: Arc<RwLock<bool>>,
+    // Signal joué par AudioPlaybackManager, à retirer du micro
+    echo_reference: Arc<RwLock<Option<SharedEchoReference>>>,
+    gain: Arc<RwLock<InputGainSettings>>,
+    // Profil d'encodage imposé par le serveur pour le channel
+    encoding: Arc<RwLock<EncodingProfile>>,
+    // Pour émettre les niveaux d'entrée (événement audio-level)
+    app_handle: Arc<RwLock<Option<AppHandle>>>,
+    // Push-to-talk : le micro n'est envoyé que touche enfoncée
+    push_to_talk: Arc<RwLock<bool>>,
+    talk_key_pressed: Arc<AtomicBool>,
+}
+
+impl CaptureProcessing {
+    fn new() -> Self {
+        Self {
+            noise_suppression: Arc::new(RwLock::new(NoiseSuppressionLevel::default())),
+            echo_cancellation: Arc::new(RwLock::new(true)),
+            echo_reference: Arc::new(RwLock::new(None)),
+            gain: Arc::new(RwLock::new(InputGainSettings::default())),
+            encoding: Arc::new(RwLock::new(EncodingProfile::default())),
+            app_handle: Arc::new(RwLock::new(None)),
+            push_to_talk: Arc::new(RwLock::new(false)),
+            talk_key_pressed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Le micro part sur le réseau sauf en push-to-talk touche relâchée
+    fn transmitting(&self) -> bool {
+        !*self.push_to_talk.read() || self.talk_key_pressed.load(Ordering::SeqCst)
+    }
+}
+
+/// État de traitement d'un flux de capture : écho, bruit, gain puis mesure du niveau
+#[derive(Debug, Default)]
+struct CaptureChain {
+    // Format du flux traité ; un changement de périphérique remet la chaîne à zéro
+    format: Option<(u32, u8)>,
+    cancellers: Vec<EchoCanceller>,
+    suppressors: Vec<NoiseSuppressor>,
+    gain: Option<InputGainProcessor>,
+    meter: Option<LevelMeter>,
+}
+
+impl CaptureChain {
+    fn process(&mut self, data: Vec<f32>, sample_rate: u32, channels: u8, processing: &CaptureProcessing) -> Vec<f32> {
+        if self.format != Some((sample_rate, channels)) {
+            *self = Self { format: Some((sample_rate, channels)), ..Default::default() };
+        }
+
+        let echo_reference = processing.echo_reference.read().clone();
+        let mut data = match echo_reference {
+            Some(reference) if *processing.echo_cancellation.read() => {
+                AudioCaptureManager::cancel_echo(&mut self.cancellers, data, sample_rate, channels, &reference)
+            }
+            _ => {
+                self.cancellers.clear();
+                data
+            }
+        };
+
+        let level = *processing.noise_suppression.read();
+        data = AudioCaptureManager::suppress_noise(&mut self.suppressors, data, channels, level);
+
+        let settings = *processing.gain.read();
+        let gain = self.gain.get_or_insert_with(|| InputGainProcessor::new(sample_rate));
+        gain.process(&mut data, channels, &settings);
+
+        let meter = self.meter.get_or_insert_with(|| LevelMeter::new(sample_rate, channels));
+        if let Some(input_level) = meter.push(&data, gain.gain_db()) {
+            if let Some(app_handle) = processing.app_handle.read().as_ref() {
+                let _ = app_handle.emit("audio-level", &input_level);
+            }
+        }
+
+        data
+    }
+}
 
 /// Gestionnaire de capture audio (microphone)
 /// Ne stocke pas le Stream directement pour éviter les problèmes Send/Sync
@@ -20,6 +111,9 @@ pub struct AudioCaptureManager {
     channel_id: Arc<RwLock<Option<Uuid>>>,
     // Utiliser un channel pour contrôler l'enregistrement
     control_tx: Arc<RwLock<Option<mpsc::UnboundedSender<bool>>>>,
+    processing: CaptureProcessing,
+    // Configuration du stream choisie par l'utilisateur
+    stream_settings: Arc<RwLock<StreamSettings>>,
 }
 
 impl AudioCaptureManager {
@@ -31,9 +125,16 @@ impl AudioCaptureManager {
             user_id: Arc::new(RwLock::new(None)),
             channel_id: Arc::new(RwLock::new(None)),
             control_tx: Arc::new(RwLock::new(None)),
+            processing: CaptureProcessing::new(),
+            stream_settings: Arc::new(RwLock::new(StreamSettings::default())),
         }
     }
 
+    /// Configure l'AppHandle pour émettre les niveaux du micro
+    pub fn set_app_handle(&self, app_handle: AppHandle) {
+        *self.processing.app_handle.write() = Some(app_handle);
+    }
+
     /// Configure le client UDP pour l'audio
     pub async fn set_udp_client(&self, client: AudioUdpClient) {
         *self.udp_client.write() = Some(client);
@@ -54,6 +155,83 @@ impl AudioCaptureManager {
         Ok(())
     }
 
+    /// Change de périphérique sans interrompre l'enregistrement (le stream est recréé)
+    pub fn switch_device(&self, device_name: String) {
+        *self.device_name.write() = Some(device_name);
+    }
+
+    /// Configure le niveau de réduction de bruit (appliqué à chaud)
+    pub fn set_noise_suppression(&self, level: NoiseSuppressionLevel) {
+        println!("🎤 AudioCaptureManager: Noise suppression set to {:?}", level);
+        *self.processing.noise_suppression.write() = level;
+    }
+
+    /// Obtient le niveau de réduction de bruit actuel
+    pub fn get_noise_suppression(&self) -> NoiseSuppressionLevel {
+        *self.processing.noise_suppression.read()
+    }
+
+    /// Configure la référence far-end pour l'annulation d'écho
+    pub fn set_echo_reference(&self, reference: SharedEchoReference) {
+        *self.processing.echo_reference.write() = Some(reference);
+    }
+
+    /// Active ou désactive l'annulation d'écho (appliqué à chaud)
+    pub fn set_echo_cancellation(&self, enabled: bool) {
+        println!("🎤 AudioCaptureManager: Echo cancellation {}", if enabled { "enabled" } else { "disabled" });
+        *self.processing.echo_cancellation.write() = enabled;
+    }
+
+    /// Vérifie si l'annulation d'écho est active
+    pub fn is_echo_cancellation_enabled(&self) -> bool {
+        *self.processing.echo_cancellation.read()
+    }
+
+    /// Configure le gain du micro et l'AGC (appliqué à chaud)
+    pub fn set_gain_settings(&self, settings: InputGainSettings) {
+        let settings = settings.clamped();
+        println!("🎤 AudioCaptureManager: Input gain settings updated: {:?}", settings);
+        *self.processing.gain.write() = settings;
+    }
+
+    /// Obtient les réglages de gain actuels
+    pub fn get_gain_settings(&self) -> InputGainSettings {
+        *self.processing.gain.read()
+    }
+
+    /// Applique le profil d'encodage annoncé par le serveur (à chaud)
+    pub fn set_encoding_profile(&self, profile: EncodingProfile) {
+        *self.processing.encoding.write() = profile;
+    }
+
+    pub fn get_encoding_profile(&self) -> EncodingProfile {
+        *self.processing.encoding.read()
+    }
+
+    /// Active ou désactive le push-to-talk (appliqué à chaud)
+    pub fn set_push_to_talk(&self, enabled: bool) {
+        println!("🎤 AudioCaptureManager: Push-to-talk {}", if enabled { "enabled" } else { "disabled" });
+        *self.processing.push_to_talk.write() = enabled;
+    }
+
+    pub fn is_push_to_talk_enabled(&self) -> bool {
+        *self.processing.push_to_talk.read()
+    }
+
+    /// État de la touche de push-to-talk, remonté par le frontend
+    pub fn set_talk_key_pressed(&self, pressed: bool) {
+        self.processing.talk_key_pressed.store(pressed, Ordering::SeqCst);
+    }
+
+    /// Change la configuration du stream (appliquée à chaud, le stream est recréé)
+    pub fn set_stream_settings(&self, settings: StreamSettings) {
+        *self.stream_settings.write() = settings;
+    }
+
+    pub fn get_stream_settings(&self) -> StreamSettings {
+        self.stream_settings.read().clone()
+    }
+
     /// Démarre l'enregistrement audio
     pub fn start_recording(&self) -> Result<()> {
         println!("🎤 AudioCaptureManager: Starting audio recording...");
@@ -68,6 +246,7 @@ impl AudioCaptureManager {
             .context("No audio device configured")?
             .clone();
         println!("🎤 AudioCaptureManager: Using device: {}", device_name);
+        let device_name = self.device_name.clone();
 
         let udp_client = self.udp_client.read().clone()
             .context("No UDP client configured")?;
@@ -86,7 +265,16 @@ impl AudioCaptureManager {
         *self.control_tx.write() = Some(control_tx);
 
         let is_recording = self.is_recording.clone();
+        let processing = self.processing.clone();
+        let stream_settings = self.stream_settings.clone();
+        if let Some(reference) = self.processing.echo_reference.read().as_ref() {
+            // Ne pas aligner le micro sur ce qui a été joué avant la capture
+            reference.lock().clear();
+        }
         
+        // Le thread de capture tourne tant que ce flag reste levé
+        *self.is_recording.write() = true;
+
         // Démarrer l'enregistrement dans une tâche séparée
         println!("🎤 AudioCaptureManager: Spawning capture task...");
         tokio::spawn(async move {
@@ -96,27 +284,30 @@ impl AudioCaptureManager {
                 user_id,
                 channel_id,
                 is_recording,
+                processing,
+                stream_settings,
                 &mut control_rx,
             ).await {
                 eprintln!("❌ Audio capture error: {}", e);
             }
         });
 
-        *self.is_recording.write() = true;
         println!("✅ AudioCaptureManager: Audio recording started successfully");
         Ok(())
     }
 
     /// Tâche de capture audio (isolée du state Tauri)
     async fn start_capture_task(
-        device_name: String,
+        device_name: Arc<RwLock<Option<String>>>,
         udp_client: AudioUdpClient,
         user_id: Uuid,
         channel_id: Uuid,
         is_recording: Arc<RwLock<bool>>,
+        processing: CaptureProcessing,
+        stream_settings: Arc<RwLock<StreamSettings>>,
         control_rx: &mut mpsc::UnboundedReceiver<bool>,
     ) -> Result<()> {
-        println!("🎤 CaptureTask: Starting audio capture task for device: {}", device_name);
+        println!("🎤 CaptureTask: Starting audio capture task for device: {:?}", device_name.read());
         
         // Channel pour les données audio ET métadonnées
         let (audio_tx, mut audio_rx) = mpsc::unbounded_channel::<(Vec<f32>, u32, u8)>();
@@ -131,61 +322,54 @@ impl AudioCaptureManager {
             println!("🎤 CaptureThread: Getting audio host and device...");
             // Obtenir le device CPAL (dans le thread)
             let host = cpal::default_host();
-            let device = if device_name == "default" {
-                host.default_input_device()
-            } else {
-                host.input_devices()
-                    .ok()
-                    .and_then(|mut devices| devices.find(|d| d.name().unwrap_or_default() == device_name))
-            };
-
-            if let Some(device) = device {
-                // println!("🎤 CaptureThread: Found audio device: {:?}", device.name());
-                
-                // Configuration du stream
-                if let Ok(config) = device.default_input_config() {
-                    let sample_rate = config.sample_rate().0;
-                    let channels = config.channels() as u8;
-                    // println!("🎤 CaptureThread: Audio config - Sample rate: {}, Channels: {}", sample_rate, channels);
-
-                    // println!("🎤 CaptureThread: Creating audio stream with format: {:?}", config.sample_format());
-                    
-                    // Créer le stream selon le format
-                    let stream_result = match config.sample_format() {
-                        SampleFormat::F32 => Self::create_stream::<f32>(&device, &config.into(), audio_tx.clone(), sample_rate, channels),
-                        SampleFormat::I16 => Self::create_stream::<i16>(&device, &config.into(), audio_tx.clone(), sample_rate, channels),
-                        SampleFormat::U16 => Self::create_stream::<u16>(&device, &config.into(), audio_tx.clone(), sample_rate, channels),
-                        _ => {
-                            eprintln!("❌ CaptureThread: Unsupported sample format: {:?}", config.sample_format());
-                            return;
-                        }
-                    };
 
-                    if let Ok(stream) = stream_result {
-                        println!("🎤 CaptureThread: Stream created successfully, starting playback...");
-                        if let Err(e) = stream.play() {
-                            eprintln!("❌ CaptureThread: Failed to start audio stream: {}", e);
-                            return;
-                        }
-                        println!("✅ CaptureThread: Audio stream started successfully!");
-                        
-                        // Maintenir le stream vivant jusqu'à l'arrêt
-                        loop {
-                            std::thread::sleep(std::time::Duration::from_millis(100));
-                            
-                            // Vérifier si on doit arrêter (via un flag partagé)
-                            if !*is_recording_capture.read() {
-                                println!("🎤 CaptureThread: Recording stopped, exiting stream loop");
-                                break;
-                            }
-                        }
-                    } else {
-                        eprintln!("❌ CaptureThread: Failed to create audio stream");
+            // Le stream est recréé quand le périphérique change ou tombe en erreur (débranché)
+            while *is_recording_capture.read() {
+                let current_device = device_name.read().clone().unwrap_or_else(|| "default".to_string());
+                let current_settings = stream_settings.read().clone();
+                let stream_failed = Arc::new(AtomicBool::new(false));
+
+                let stream = match Self::open_input_stream(&host, &current_device, &current_settings, audio_tx.clone(), stream_failed.clone()) {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        eprintln!("❌ CaptureThread: Failed to open {}: {}", current_device, e);
+                        std::thread::sleep(Duration::from_millis(500));
+                        continue;
+                    }
+                };
+                println!("✅ CaptureThread: Audio stream started on {}", current_device);
+
+                // Maintenir le stream vivant jusqu'à l'arrêt
+                loop {
+                    std::thread::sleep(Duration::from_millis(100));
+
+                    // Vérifier si on doit arrêter (via un flag partagé)
+                    if !*is_recording_capture.read() {
+                        println!("🎤 CaptureThread: Recording stopped, exiting stream loop");
+                        break;
+                    }
+                    if device_name.read().as_deref().unwrap_or("default") != current_device {
+                        println!("🔄 CaptureThread: Device changed, rebuilding stream");
+                        break;
+                    }
+                    if *stream_settings.read() != current_settings {
+                        println!("🔄 CaptureThread: Stream settings changed, rebuilding stream");
+                        break;
+                    }
+                    if stream_failed.load(Ordering::SeqCst) {
+                        println!("⚠️ CaptureThread: Stream error on {}, rebuilding stream", current_device);
+                        std::thread::sleep(Duration::from_millis(500));
+                        break;
                     }
                 }
+                drop(stream);
             }
         });
 
+        // État du traitement conservé entre les buffers
+        let mut chain = CaptureChain::default();
+        let mut encoder = FrameEncoder::default();
+
         // Boucle principale pour traiter les données
         loop {
             tokio::select! {
@@ -209,6 +393,16 @@ impl AudioCaptureManager {
                 audio_data = audio_rx.recv() => {
                     if let Some((data, sample_rate, channels)) = audio_data {
                         if *is_recording_stream.read() {
+                            let data = chain.process(data, sample_rate, channels, &processing);
+                            // Le niveau reste mesuré touche relâchée ; la trame entamée est abandonnée
+                            if !processing.transmitting() {
+                                encoder = FrameEncoder::default();
+                                continue;
+                            }
+                            let profile = *processing.encoding.read();
+                            let Some((data, sample_rate, channels)) = encoder.encode(data, sample_rate, channels, &profile) else {
+                                continue;
+                            };
                             // println!("🎤 Sending {} samples to UDP (SR: {}Hz, CH: {})", data.len(), sample_rate, channels);
                             if let Err(e) = udp_client.send_audio_data(
                                 user_id,
@@ -229,6 +423,104 @@ impl AudioCaptureManager {
         Ok(())
     }
 
+    /// Retire l'écho des haut-parleurs de chaque canal du micro (avant la réduction de bruit)
+    fn cancel_echo(
+        cancellers: &mut Vec<EchoCanceller>,
+        data: Vec<f32>,
+        sample_rate: u32,
+        channels: u8,
+        reference: &SharedEchoReference,
+    ) -> Vec<f32> {
+        let channels = channels.max(1) as usize;
+        // Un changement de canaux ou de fréquence invalide les filtres appris
+        if cancellers.len() != channels || cancellers.iter().any(|canceller| canceller.sample_rate() != sample_rate) {
+            *cancellers = (0..channels).map(|_| EchoCanceller::new(sample_rate)).collect();
+        }
+
+        let frames = data.len() / channels;
+        let far_end = reference.lock().take(frames, sample_rate);
+
+        let mut output = data.clone();
+        for (channel, canceller) in cancellers.iter_mut().enumerate() {
+            let samples: Vec<f32> = data.iter().skip(channel).step_by(channels).take(frames).copied().collect();
+            let processed = canceller.process(&samples, &far_end);
+            for (i, sample) in processed.into_iter().enumerate() {
+                output[i * channels + channel] = sample;
+            }
+        }
+        output
+    }
+
+    /// Applique la réduction de bruit sur des échantillons entrelacés, canal par canal
+    fn suppress_noise(
+        suppressors: &mut Vec<NoiseSuppressor>,
+        data: Vec<f32>,
+        channels: u8,
+        level: NoiseSuppressionLevel,
+    ) -> Vec<f32> {
+        if level == NoiseSuppressionLevel::Off {
+            // Repartir d'une estimation du bruit neuve à la réactivation
+            suppressors.clear();
+            return data;
+        }
+
+        let channels = channels.max(1) as usize;
+        if suppressors.len() != channels {
+            *suppressors = (0..channels).map(|_| NoiseSuppressor::new(level)).collect();
+        }
+
+        let mut output = vec![0.0; data.len()];
+        for (channel, suppressor) in suppressors.iter_mut().enumerate() {
+            suppressor.set_level(level);
+            let samples: Vec<f32> = data.iter().skip(channel).step_by(channels).copied().collect();
+            let processed = suppressor.process(&samples);
+            for (i, sample) in processed.into_iter().enumerate() {
+                output[i * channels + channel] = sample;
+            }
+        }
+        output
+    }
+
+    /// Ouvre et démarre le stream d'entrée du périphérique demandé
+    fn open_input_stream(
+        host: &cpal::Host,
+        device_name: &str,
+        settings: &StreamSettings,
+        audio_tx: mpsc::UnboundedSender<(Vec<f32>, u32, u8)>,
+        stream_failed: Arc<AtomicBool>,
+    ) -> Result<cpal::Stream> {
+        let device = if device_name == "default" {
+            host.default_input_device()
+        } else {
+            host.input_devices()
+                .ok()
+                .and_then(|mut devices| devices.find(|d| d.name().unwrap_or_default() == device_name))
+        }
+        .context("Input device not found")?;
+
+        // Configuration du stream : réglages utilisateur bornés aux capacités du périphérique
+        let chosen = resolve_config(&device, true, settings)?;
+        let config = chosen.stream_config();
+        let sample_rate = chosen.sample_rate;
+        let channels = chosen.channels as u8;
+        println!("🎤 CaptureThread: Stream config - {}Hz, {} channels, {}, buffer {:?}",
+                 sample_rate, channels, chosen.sample_format, config.buffer_size);
+
+        // Créer le stream selon le format
+        let stream = match chosen.sample_format {
+            SampleFormat::F32 => Self::create_stream::<f32>(&device, &config, audio_tx, sample_rate, channels, stream_failed)?,
+            SampleFormat::F64 => Self::create_stream::<f64>(&device, &config, audio_tx, sample_rate, channels, stream_failed)?,
+            SampleFormat::I32 => Self::create_stream::<i32>(&device, &config, audio_tx, sample_rate, channels, stream_failed)?,
+            SampleFormat::I16 => Self::create_stream::<i16>(&device, &config, audio_tx, sample_rate, channels, stream_failed)?,
+            SampleFormat::U16 => Self::create_stream::<u16>(&device, &config, audio_tx, sample_rate, channels, stream_failed)?,
+            SampleFormat::U8 => Self::create_stream::<u8>(&device, &config, audio_tx, sample_rate, channels, stream_failed)?,
+            format => anyhow::bail!("Unsupported sample format: {:?}", format),
+        };
+
+        stream.play().context("Failed to start audio stream")?;
+        Ok(stream)
+    }
+
     /// Crée un stream audio typé
     fn create_stream<T>(
         device: &Device,
@@ -236,22 +528,27 @@ impl AudioCaptureManager {
         audio_tx: mpsc::UnboundedSender<(Vec<f32>, u32, u8)>,
         sample_rate: u32,
         channels: u8,
+        stream_failed: Arc<AtomicBool>,
     ) -> Result<cpal::Stream>
     where
-        T: cpal::Sample + cpal::SizedSample + Send + 'static,
-        f32: From<T>,
+        T: cpal::SizedSample + Send + 'static,
+        f32: cpal::FromSample<T>,
     {
         // Buffer pour accumuler les échantillons
         let mut sample_buffer = Vec::new();
         const BUFFER_SIZE: usize = 1024; // Environ 21ms à 48kHz
         
-        let err_fn = |err| eprintln!("Audio stream error: {}", err);
+        // Une erreur (périphérique débranché...) fait recréer le stream par le thread de capture
+        let err_fn = move |err| {
+            eprintln!("Audio stream error: {}", err);
+            stream_failed.store(true, Ordering::SeqCst);
+        };
         
         let stream = device.build_input_stream(
             config,
             move |data: &[T], _: &cpal::InputCallbackInfo| {
-                // Convertir les échantillons vers f32
-                let samples: Vec<f32> = data.iter().map(|&s| f32::from(s)).collect();
+                // Convertir les échantillons vers f32 normalisé [-1, 1]
+                let samples: Vec<f32> = data.iter().map(|&s| s.to_sample::<f32>()).collect();
                 sample_buffer.extend(samples);
 
                 // Envoyer quand on a assez d'échantillons
@@ -314,6 +611,8 @@ impl Clone for AudioCaptureManager {
             user_id: self.user_id.clone(),
             channel_id: self.channel_id.clone(),
             control_tx: Arc::new(RwLock::new(None)), // Nouveau channel
+            processing: self.processing.clone(),
+            stream_settings: self.stream_settings.clone(),
         }
     }
 }
@@ -321,6 +620,7 @@ impl Clone for AudioCaptureManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::echo_cancellation::EchoReference;
 
     #[test]
     fn test_audio_capture_manager_creation() {
@@ -337,4 +637,64 @@ mod tests {
         manager.set_device(device_name.clone()).unwrap();
         assert_eq!(manager.get_device_name(), Some(device_name));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_noise_suppression_keeps_interleaved_layout() {
+        let manager = AudioCaptureManager::new();
+        manager.set_noise_suppression(NoiseSuppressionLevel::High);
+        assert_eq!(manager.get_noise_suppression(), NoiseSuppressionLevel::High);
+
+        let mut suppressors = Vec::new();
+        let data = vec![0.1; 1024];
+        let output = AudioCaptureManager::suppress_noise(&mut suppressors, data.clone(), 2, NoiseSuppressionLevel::High);
+        assert_eq!(output.len(), data.len());
+        assert_eq!(suppressors.len(), 2);
+
+        let bypass = AudioCaptureManager::suppress_noise(&mut suppressors, data.clone(), 2, NoiseSuppressionLevel::Off);
+        assert_eq!(bypass, data);
+        assert!(suppressors.is_empty());
+    }
+
+    #[test]
+    fn test_echo_cancellation_without_far_end_is_transparent() {
+        let manager = AudioCaptureManager::new();
+        assert!(manager.is_echo_cancellation_enabled());
+
+        let reference = EchoReference::shared();
+        let mut cancellers = Vec::new();
+        let data: Vec<f32> = (0..1024).map(|i| (i as f32 * 0.05).sin() * 0.2).collect();
+        let output = AudioCaptureManager::cancel_echo(&mut cancellers, data.clone(), 48000, 2, &reference);
+        assert_eq!(output, data);
+        assert_eq!(cancellers.len(), 2);
+
+        AudioCaptureManager::cancel_echo(&mut cancellers, data, 16000, 2, &reference);
+        assert!(cancellers.iter().all(|canceller| canceller.sample_rate() == 16000));
+    }
+
+    #[test]
+    fn test_capture_chain_applies_gain_settings() {
+        let manager = AudioCaptureManager::new();
+        manager.set_noise_suppression(NoiseSuppressionLevel::Off);
+        manager.set_gain_settings(InputGainSettings { manual_gain_db: 6.0, agc_enabled: false, ..Default::default() });
+        assert_eq!(manager.get_gain_settings().manual_gain_db, 6.0);
+
+        let mut chain = CaptureChain::default();
+        let data = vec![0.1; 1024];
+        chain.process(data.clone(), 48000, 1, &manager.processing);
+        let output = chain.process(data, 48000, 1, &manager.processing);
+        assert!(output.iter().all(|s| (s - 0.1995).abs() < 0.001));
+    }
+
+    #[test]
+    fn test_push_to_talk_gates_transmission() {
+        let manager = AudioCaptureManager::new();
+        assert!(manager.processing.transmitting());
+
+        manager.set_push_to_talk(true);
+        assert!(!manager.processing.transmitting());
+        manager.set_talk_key_pressed(true);
+        assert!(manager.processing.transmitting());
+        manager.set_talk_key_pressed(false);
+        assert!(!manager.processing.transmitting());
+    }
+}
@@ -3,48 +3,156 @@ use cpal::{
     Device, Host, SampleFormat, SampleRate, Stream, StreamConfig,
 };
 use crate::state::{AudioDevice, AudioDevices};
+use super::{AudioCaptureManager, AudioPlaybackManager};
+use super::stream_config::device_capabilities;
 use anyhow::{Result, Context};
+use serde::Serialize;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use parking_lot::RwLock;
+use tauri::{AppHandle, Emitter};
+
+/// Nom du périphérique système par défaut
+pub const DEFAULT_DEVICE: &str = "default";
+/// Intervalle entre deux scans du monitoring des périphériques
+const MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Périphériques apparus / disparus entre deux scans (événement audio-devices-changed)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DeviceChanges {
+    pub added_inputs: Vec<String>,
+    pub removed_inputs: Vec<String>,
+    pub added_outputs: Vec<String>,
+    pub removed_outputs: Vec<String>,
+}
+
+impl DeviceChanges {
+    pub fn between(previous: &AudioDevices, current: &AudioDevices) -> Self {
+        let difference = |from: &[AudioDevice], to: &[AudioDevice]| -> Vec<String> {
+            from.iter()
+                .filter(|device| !to.iter().any(|d| d.id == device.id))
+                .map(|device| device.id.clone())
+                .collect()
+        };
+
+        Self {
+            added_inputs: difference(&current.input_devices, &previous.input_devices),
+            removed_inputs: difference(&previous.input_devices, &current.input_devices),
+            added_outputs: difference(&current.output_devices, &previous.output_devices),
+            removed_outputs: difference(&previous.output_devices, &current.output_devices),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_inputs.is_empty()
+            && self.removed_inputs.is_empty()
+            && self.added_outputs.is_empty()
+            && self.removed_outputs.is_empty()
+    }
+}
+
+/// Bascule automatique de périphérique (événement audio-device-switched)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeviceSwitch {
+    pub direction: &'static str,
+    pub from: String,
+    pub to: String,
+    /// "unplugged" (repli sur le défaut) ou "restored" (retour du préféré)
+    pub reason: &'static str,
+}
+
+/// Périphérique vers lequel basculer : le préféré s'il est de retour,
+/// sinon le périphérique par défaut si l'actif a disparu
+pub fn resolve_device(active: &str, preferred: Option<&str>, available: &[AudioDevice]) -> Option<(String, &'static str)> {
+    let is_available = |name: &str| name == DEFAULT_DEVICE || available.iter().any(|d| d.id == name);
+
+    match preferred {
+        Some(preferred) if preferred != active && is_available(preferred) => {
+            Some((preferred.to_string(), "restored"))
+        }
+        _ if !is_available(active) => Some((DEFAULT_DEVICE.to_string(), "unplugged")),
+        _ => None,
+    }
+}
 
 /// Gestionnaire des périphériques audio
 pub struct AudioDeviceManager {
     host: Host,
     devices: Arc<RwLock<AudioDevices>>,
+    // Choix explicites de l'utilisateur, restaurés quand le périphérique revient
+    preferred_input: Arc<RwLock<Option<String>>>,
+    preferred_output: Arc<RwLock<Option<String>>>,
+    monitoring: Arc<AtomicBool>,
 }
 
 impl AudioDeviceManager {
     pub fn new() -> Result<Self> {
         let host = cpal::default_host();
-        
+
         Ok(Self {
             host,
             devices: Arc::new(RwLock::new(AudioDevices::default())),
+            preferred_input: Arc::new(RwLock::new(None)),
+            preferred_output: Arc::new(RwLock::new(None)),
+            monitoring: Arc::new(AtomicBool::new(false)),
         })
     }
 
     /// Scanne et met à jour la liste des périphériques audio
     pub fn scan_devices(&self) -> Result<AudioDevices> {
         println!("Scanning audio devices...");
-        
+
+        let audio_devices = Self::enumerate_devices(
+            &self.host,
+            self.preferred_input.read().as_deref(),
+            self.preferred_output.read().as_deref(),
+            true,
+        );
+
+        println!("Audio scan complete: {} input, {} output devices",
+                 audio_devices.input_devices.len(),
+                 audio_devices.output_devices.len());
+
+        // Mettre à jour l'état interne
+        *self.devices.write() = audio_devices.clone();
+
+        Ok(audio_devices)
+    }
+
+    /// Liste les périphériques ; la sélection suit la préférence si elle est présente
+    ///
+    /// Interroger les capacités ouvre le périphérique sur certains backends :
+    /// elles ne sont lues que lors d'un scan explicite.
+    fn enumerate_devices(
+        host: &Host,
+        preferred_input: Option<&str>,
+        preferred_output: Option<&str>,
+        with_capabilities: bool,
+    ) -> AudioDevices {
         let mut input_devices = Vec::new();
         let mut output_devices = Vec::new();
 
         // Périphériques d'entrée (microphones)
-        match self.host.input_devices() {
+        match host.input_devices() {
             Ok(input_device_iter) => {
                 for device in input_device_iter {
                     if let Ok(name) = device.name() {
-                        let is_default = self.host.default_input_device()
+                        let is_default = host.default_input_device()
                             .map(|d| d.name().unwrap_or_default() == name)
                             .unwrap_or(false);
 
-                        println!("Found input device: {} (default: {})", name, is_default);
-                        
+                        let capabilities = if with_capabilities {
+                            device_capabilities(&device, true)
+                        } else {
+                            Default::default()
+                        };
+
                         input_devices.push(AudioDevice {
                             name: name.clone(),
                             id: name, // Pour CPAL, on utilise le nom comme ID
                             is_default,
+                            capabilities,
                         });
                     }
                 }
@@ -55,20 +163,25 @@ impl AudioDeviceManager {
         }
 
         // Périphériques de sortie (haut-parleurs/casques)
-        match self.host.output_devices() {
+        match host.output_devices() {
             Ok(output_device_iter) => {
                 for device in output_device_iter {
                     if let Ok(name) = device.name() {
-                        let is_default = self.host.default_output_device()
+                        let is_default = host.default_output_device()
                             .map(|d| d.name().unwrap_or_default() == name)
                             .unwrap_or(false);
 
-                        println!("Found output device: {} (default: {})", name, is_default);
-                        
+                        let capabilities = if with_capabilities {
+                            device_capabilities(&device, false)
+                        } else {
+                            Default::default()
+                        };
+
                         output_devices.push(AudioDevice {
                             name: name.clone(),
                             id: name,
                             is_default,
+                            capabilities,
                         });
                     }
                 }
@@ -78,30 +191,99 @@ impl AudioDeviceManager {
             }
         }
 
-        // Sélectionner les périphériques par défaut
-        let selected_input = input_devices.iter()
-            .find(|d| d.is_default)
-            .map(|d| d.id.clone());
-
-        let selected_output = output_devices.iter()
-            .find(|d| d.is_default)
-            .map(|d| d.id.clone());
+        // Sélectionner le périphérique préféré s'il est présent, sinon celui par défaut
+        let select = |devices: &[AudioDevice], preferred: Option<&str>| {
+            devices.iter()
+                .find(|d| Some(d.id.as_str()) == preferred)
+                .or_else(|| devices.iter().find(|d| d.is_default))
+                .map(|d| d.id.clone())
+        };
+        let selected_input = select(&input_devices, preferred_input);
+        let selected_output = select(&output_devices, preferred_output);
 
-        let audio_devices = AudioDevices {
+        AudioDevices {
             input_devices,
             output_devices,
             selected_input,
             selected_output,
-        };
+        }
+    }
 
-        println!("Audio scan complete: {} input, {} output devices", 
-                 audio_devices.input_devices.len(), 
-                 audio_devices.output_devices.len());
+    /// Démarre la surveillance périodique des périphériques (branchement / débranchement)
+    pub fn start_monitoring(
+        &self,
+        app_handle: AppHandle,
+        capture: Arc<AudioCaptureManager>,
+        playback: Arc<AudioPlaybackManager>,
+    ) {
+        if self.monitoring.swap(true, Ordering::SeqCst) {
+            return; // Déjà démarrée
+        }
 
-        // Mettre à jour l'état interne
-        *self.devices.write() = audio_devices.clone();
+        let devices = self.devices.clone();
+        let preferred_input = self.preferred_input.clone();
+        let preferred_output = self.preferred_output.clone();
+        let monitoring = self.monitoring.clone();
 
-        Ok(audio_devices)
+        println!("👀 DeviceMonitor: Watching audio devices every {:?}", MONITOR_INTERVAL);
+        std::thread::spawn(move || {
+            // Le Host CPAL est créé dans le thread de surveillance
+            let host = cpal::default_host();
+
+            while monitoring.load(Ordering::SeqCst) {
+                let mut current = Self::enumerate_devices(
+                    &host,
+                    preferred_input.read().as_deref(),
+                    preferred_output.read().as_deref(),
+                    false,
+                );
+                let changes = DeviceChanges::between(&devices.read(), &current);
+                current.keep_capabilities(&devices.read());
+                *devices.write() = current.clone();
+
+                if !changes.is_empty() {
+                    println!("🔌 DeviceMonitor: Devices changed: {:?}", changes);
+                    let payload = serde_json::json!({ "changes": changes, "devices": current });
+                    if let Err(e) = app_handle.emit("audio-devices-changed", &payload) {
+                        println!("❌ Failed to emit audio-devices-changed event: {}", e);
+                    }
+                }
+
+                // Repli sur le périphérique par défaut / retour du périphérique préféré
+                if let Some(active) = capture.get_device_name() {
+                    let preferred = preferred_input.read().clone();
+                    if let Some((to, reason)) = resolve_device(&active, preferred.as_deref(), &current.input_devices) {
+                        println!("🔄 DeviceMonitor: Input {} -> {} ({})", active, to, reason);
+                        capture.switch_device(to.clone());
+                        Self::emit_switch(&app_handle, DeviceSwitch { direction: "input", from: active, to, reason });
+                    }
+                }
+
+                if let Some(active) = playback.get_device_name() {
+                    let preferred = preferred_output.read().clone();
+                    if let Some((to, reason)) = resolve_device(&active, preferred.as_deref(), &current.output_devices) {
+                        println!("🔄 DeviceMonitor: Output {} -> {} ({})", active, to, reason);
+                        playback.switch_device(to.clone());
+                        Self::emit_switch(&app_handle, DeviceSwitch { direction: "output", from: active, to, reason });
+                    }
+                }
+
+                std::thread::sleep(MONITOR_INTERVAL);
+            }
+
+            println!("👀 DeviceMonitor: Stopped");
+        });
+    }
+
+    /// Arrête la surveillance des périphériques
+    pub fn stop_monitoring(&self) {
+        self.monitoring.store(false, Ordering::SeqCst);
+    }
+
+    fn emit_switch(app_handle: &AppHandle, switch: DeviceSwitch) {
+        if let Err(e) = app_handle.emit("audio-device-switched", &switch) {
+            println!("❌ Failed to emit audio-device-switched event: {}", e);
+        }
     }
 
     /// Obtient les périphériques audio actuellement scannés
@@ -114,8 +296,9 @@ impl AudioDeviceManager {
         let mut devices = self.devices.write();
         
         // Vérifier que le périphérique existe
-        if devices.input_devices.iter().any(|d| d.id == device_id) {
+        if device_id == DEFAULT_DEVICE || devices.input_devices.iter().any(|d| d.id == device_id) {
             devices.selected_input = Some(device_id.to_string());
+            *self.preferred_input.write() = Some(device_id.to_string());
             Ok(())
         } else {
             anyhow::bail!("Input device not found: {}", device_id)
@@ -127,14 +310,31 @@ impl AudioDeviceManager {
         let mut devices = self.devices.write();
         
         // Vérifier que le périphérique existe
-        if devices.output_devices.iter().any(|d| d.id == device_id) {
+        if device_id == DEFAULT_DEVICE || devices.output_devices.iter().any(|d| d.id == device_id) {
             devices.selected_output = Some(device_id.to_string());
+            *self.preferred_output.write() = Some(device_id.to_string());
             Ok(())
         } else {
             anyhow::bail!("Output device not found: {}", device_id)
         }
     }
 
+    /// Restaure les choix de l'utilisateur (réglages persistants), sans vérifier leur présence
+    pub fn set_preferences(&self, input: Option<String>, output: Option<String>) {
+        *self.preferred_input.write() = input;
+        *self.preferred_output.write() = output;
+    }
+
+    /// Périphérique d'entrée choisi par l'utilisateur (le défaut sinon)
+    pub fn preferred_input(&self) -> String {
+        self.preferred_input.read().clone().unwrap_or_else(|| DEFAULT_DEVICE.to_string())
+    }
+
+    /// Périphérique de sortie choisi par l'utilisateur (le défaut sinon)
+    pub fn preferred_output(&self) -> String {
+        self.preferred_output.read().clone().unwrap_or_else(|| DEFAULT_DEVICE.to_string())
+    }
+
     /// Obtient le périphérique d'entrée sélectionné
     pub fn get_input_device(&self) -> Result<Option<Device>> {
         let devices = self.devices.read();
@@ -256,4 +456,49 @@ mod tests {
         let result = manager.select_input_device("nonexistent_device");
         assert!(result.is_err());
     }
+
+    fn device(id: &str) -> AudioDevice {
+        AudioDevice { name: id.to_string(), id: id.to_string(), is_default: false, capabilities: Default::default() }
+    }
+
+    #[test]
+    fn test_device_changes() {
+        let previous = AudioDevices {
+            input_devices: vec![device("Mic USB"), device("Mic interne")],
+            output_devices: vec![device("Casque")],
+            ..Default::default()
+        };
+        let current = AudioDevices {
+            input_devices: vec![device("Mic interne")],
+            output_devices: vec![device("Casque"), device("HDMI")],
+            ..Default::default()
+        };
+
+        let changes = DeviceChanges::between(&previous, &current);
+        assert_eq!(changes.removed_inputs, vec!["Mic USB".to_string()]);
+        assert_eq!(changes.added_outputs, vec!["HDMI".to_string()]);
+        assert!(changes.added_inputs.is_empty() && changes.removed_outputs.is_empty());
+        assert!(DeviceChanges::between(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_failover_and_restore() {
+        let without_usb = vec![device("Mic interne")];
+        let with_usb = vec![device("Mic interne"), device("Mic USB")];
+
+        // Le micro USB débranché : repli sur le périphérique par défaut
+        assert_eq!(
+            resolve_device("Mic USB", Some("Mic USB"), &without_usb),
+            Some((DEFAULT_DEVICE.to_string(), "unplugged"))
+        );
+        // Toujours absent : on reste sur le défaut
+        assert_eq!(resolve_device(DEFAULT_DEVICE, Some("Mic USB"), &without_usb), None);
+        // Rebranché : retour au périphérique préféré
+        assert_eq!(
+            resolve_device(DEFAULT_DEVICE, Some("Mic USB"), &with_usb),
+            Some(("Mic USB".to_string(), "restored"))
+        );
+        assert_eq!(resolve_device("Mic USB", Some("Mic USB"), &with_usb), None);
+        assert_eq!(resolve_device(DEFAULT_DEVICE, None, &without_usb), None);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,339 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use parking_lot::Mutex;
+
+/// Durée maximale de signal de référence conservée (en secondes)
+const REFERENCE_CAPACITY_SECONDS: f32 = 0.5;
+/// Délai d'écho maximal recherché entre haut-parleurs et micro (en secondes)
+const MAX_DELAY_SECONDS: f32 = 0.25;
+/// Longueur du filtre adaptatif (~10ms à 48kHz)
+const FILTER_TAPS: usize = 512;
+/// Marge laissée avant le délai estimé pour absorber les petites dérives
+const PRE_DELAY_TAPS: usize = 32;
+/// Pas d'adaptation du NLMS
+const STEP_SIZE: f32 = 0.3;
+/// Détecteur de double parole de Geigel (perte d'écho supposée >= 6 dB)
+const DOUBLE_TALK_THRESHOLD: f32 = 0.5;
+/// Durée de gel de l'adaptation après détection de double parole (en secondes)
+const DOUBLE_TALK_HOLD_SECONDS: f32 = 0.03;
+/// Facteur de décimation pour l'estimation du délai par intercorrélation
+const DECIMATION: usize = 4;
+/// Fenêtre de micro utilisée pour l'intercorrélation (avant décimation)
+const CORRELATION_WINDOW: usize = 4096;
+/// Intervalle entre deux estimations du délai (en secondes)
+const ESTIMATION_INTERVAL_SECONDS: f32 = 0.5;
+/// Corrélation normalisée minimale pour accepter un délai
+const MIN_CORRELATION: f32 = 0.3;
+/// Énergie moyenne minimale de la référence pour tenter une estimation
+const MIN_REFERENCE_POWER: f32 = 1e-6;
+
+/// Référence partagée entre la lecture (écriture) et la capture (lecture)
+pub type SharedEchoReference = Arc<Mutex<EchoReference>>;
+
+/// Signal réellement envoyé aux haut-parleurs (far-end), en mono
+///
+/// La lecture pousse chaque buffer de sortie, silence compris, et la capture
+/// consomme autant d'échantillons qu'elle en reçoit du micro : les deux flux
+/// avancent au même rythme et l'écart restant est retrouvé par l'estimation du délai.
+#[derive(Debug)]
+pub struct EchoReference {
+    samples: VecDeque<f32>,
+    sample_rate: u32,
+}
+
+impl EchoReference {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            sample_rate: 48000,
+        }
+    }
+
+    pub fn shared() -> SharedEchoReference {
+        Arc::new(Mutex::new(Self::new()))
+    }
+
+    /// Enregistre un buffer joué (échantillons entrelacés)
+    pub fn push_played(&mut self, data: &[f32], channels: usize, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.samples.clear();
+            self.sample_rate = sample_rate;
+        }
+
+        let channels = channels.max(1);
+        self.samples.extend(
+            data.chunks(channels).map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+
+        let capacity = (sample_rate as f32 * REFERENCE_CAPACITY_SECONDS) as usize;
+        if self.samples.len() > capacity {
+            let excess = self.samples.len() - capacity;
+            self.samples.drain(..excess);
+        }
+    }
+
+    /// Consomme la référence correspondant à `count` échantillons micro à `sample_rate`
+    ///
+    /// L'arriéré au-delà d'un buffer est abandonné pour garder la référence en avance
+    /// minimale sur le micro ; les manques sont complétés par du silence.
+    pub fn take(&mut self, count: usize, sample_rate: u32) -> Vec<f32> {
+        let ratio = self.sample_rate as f32 / sample_rate.max(1) as f32;
+        let needed = ((count as f32 * ratio).ceil() as usize).max(1);
+
+        if self.samples.len() > needed * 2 {
+            let excess = self.samples.len() - needed * 2;
+            self.samples.drain(..excess);
+        }
+
+        let available = needed.min(self.samples.len());
+        let mut taken: Vec<f32> = self.samples.drain(..available).collect();
+        taken.resize(needed, 0.0);
+
+        if needed == count {
+            return taken;
+        }
+
+        // Rééchantillonnage linéaire vers la fréquence du micro
+        (0..count)
+            .map(|i| {
+                let position = i as f32 * ratio;
+                let index = position.floor() as usize;
+                let next = (index + 1).min(needed - 1);
+                let frac = position - index as f32;
+                taken[index.min(needed - 1)] * (1.0 - frac) + taken[next] * frac
+            })
+            .collect()
+    }
+
+    /// Vide la référence (au démarrage d'une capture)
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+impl Default for EchoReference {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Annuleur d'écho acoustique (filtre NLMS aligné sur un délai estimé)
+#[derive(Debug)]
+pub struct EchoCanceller {
+    // Les délais et fenêtres sont dimensionnés pour cette fréquence
+    sample_rate: u32,
+    weights: Vec<f32>,
+    reference: VecDeque<f32>,
+    microphone: VecDeque<f32>,
+    max_delay: usize,
+    delay: Option<usize>,
+    double_talk_hold: usize,
+    double_talk_remaining: usize,
+    estimation_interval: usize,
+    since_estimation: usize,
+}
+
+impl EchoCanceller {
+    pub fn new(sample_rate: u32) -> Self {
+        let rate = sample_rate.max(1) as f32;
+        let max_delay = (rate * MAX_DELAY_SECONDS) as usize;
+
+        Self {
+            sample_rate,
+            weights: vec![0.0; FILTER_TAPS],
+            reference: std::iter::repeat_n(0.0, max_delay + FILTER_TAPS + CORRELATION_WINDOW).collect(),
+            microphone: std::iter::repeat_n(0.0, CORRELATION_WINDOW).collect(),
+            max_delay,
+            delay: None,
+            double_talk_hold: (rate * DOUBLE_TALK_HOLD_SECONDS) as usize,
+            double_talk_remaining: 0,
+            estimation_interval: (rate * ESTIMATION_INTERVAL_SECONDS) as usize,
+            since_estimation: 0,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Délai d'écho estimé, en échantillons
+    pub fn delay(&self) -> Option<usize> {
+        self.delay
+    }
+
+    /// Retire l'écho de `reference` présent dans `microphone` (blocs mono de même taille)
+    pub fn process(&mut self, microphone: &[f32], reference: &[f32]) -> Vec<f32> {
+        let block = microphone.len();
+        self.reference.extend(reference.iter().copied().chain(std::iter::repeat(0.0)).take(block));
+        self.microphone.extend(microphone);
+
+        let history = self.max_delay + FILTER_TAPS + CORRELATION_WINDOW + block;
+        if self.reference.len() > history {
+            let excess = self.reference.len() - history;
+            self.reference.drain(..excess);
+        }
+        if self.microphone.len() > CORRELATION_WINDOW {
+            let excess = self.microphone.len() - CORRELATION_WINDOW;
+            self.microphone.drain(..excess);
+        }
+
+        self.since_estimation += block;
+        if self.since_estimation >= self.estimation_interval {
+            self.since_estimation = 0;
+            self.update_delay();
+        }
+
+        let Some(delay) = self.delay else {
+            return microphone.to_vec();
+        };
+
+        // Indice, dans l'historique, de l'échantillon de référence aligné sur le premier échantillon micro
+        let offset = delay.saturating_sub(PRE_DELAY_TAPS);
+        let start = self.reference.len() - block;
+        let reference = self.reference.make_contiguous();
+
+        let mut output = Vec::with_capacity(block);
+        for (n, &near) in microphone.iter().enumerate() {
+            let newest = start + n - offset;
+            let window = &reference[newest + 1 - FILTER_TAPS..=newest];
+
+            // window[FILTER_TAPS - 1] est l'échantillon le plus récent
+            let estimate: f32 = self.weights.iter().rev().zip(window).map(|(w, x)| w * x).sum();
+            let error = near - estimate;
+
+            let far_peak = window.iter().fold(0.0f32, |peak, x| peak.max(x.abs()));
+            if near.abs() > DOUBLE_TALK_THRESHOLD * far_peak.max(f32::EPSILON) && far_peak > 0.0 {
+                self.double_talk_remaining = self.double_talk_hold;
+            }
+
+            if self.double_talk_remaining > 0 {
+                self.double_talk_remaining -= 1;
+            } else {
+                let energy: f32 = window.iter().map(|x| x * x).sum();
+                if energy > MIN_REFERENCE_POWER * FILTER_TAPS as f32 {
+                    let step = STEP_SIZE * error / energy;
+                    for (w, x) in self.weights.iter_mut().rev().zip(window) {
+                        *w += step * x;
+                    }
+                }
+            }
+
+            output.push(error);
+        }
+        output
+    }
+
+    /// Estime le délai par intercorrélation normalisée (signaux décimés)
+    fn update_delay(&mut self) {
+        let microphone = decimate(self.microphone.iter().copied());
+        let reference: Vec<f32> = decimate(self.reference.iter().copied());
+
+        let mic_energy: f32 = microphone.iter().map(|x| x * x).sum();
+        let window = microphone.len();
+        let max_lag = (self.max_delay / DECIMATION).min(reference.len().saturating_sub(window));
+        let end = reference.len();
+
+        let reference_tail = &reference[end - window..];
+        let reference_power = reference_tail.iter().map(|x| x * x).sum::<f32>() / window.max(1) as f32;
+        if mic_energy <= 0.0 || reference_power < MIN_REFERENCE_POWER {
+            return;
+        }
+
+        let mut best = (0usize, 0.0f32);
+        for lag in 0..=max_lag {
+            let segment = &reference[end - window - lag..end - lag];
+            let energy: f32 = segment.iter().map(|x| x * x).sum();
+            if energy <= 0.0 {
+                continue;
+            }
+            let correlation: f32 = microphone.iter().zip(segment).map(|(m, r)| m * r).sum();
+            let normalized = correlation.abs() / (mic_energy * energy).sqrt();
+            if normalized > best.1 {
+                best = (lag, normalized);
+            }
+        }
+
+        if best.1 < MIN_CORRELATION {
+            return;
+        }
+
+        let delay = best.0 * DECIMATION;
+        let moved = self.delay.is_none_or(|current| current.abs_diff(delay) > PRE_DELAY_TAPS / 2);
+        if moved {
+            println!("🔁 EchoCanceller: Echo delay estimated at {} samples", delay);
+            self.delay = Some(delay);
+            self.weights.iter_mut().for_each(|w| *w = 0.0);
+        }
+    }
+}
+
+/// Moyenne par paquets de `DECIMATION` échantillons, alignés sur le plus récent
+fn decimate(samples: impl Iterator<Item = f32>) -> Vec<f32> {
+    let samples: Vec<f32> = samples.collect();
+    samples[samples.len() % DECIMATION..].chunks_exact(DECIMATION)
+        .map(|chunk| chunk.iter().sum::<f32>() / DECIMATION as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn noise(len: usize, amplitude: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reference_follows_capture_rate() {
+        let mut reference = EchoReference::new();
+        reference.push_played(&[0.2, 0.4, 0.2, 0.4], 2, 48000);
+        assert_eq!(reference.take(2, 48000), vec![0.3, 0.3]);
+        assert_eq!(reference.take(2, 48000), vec![0.0, 0.0]);
+
+        reference.push_played(&vec![0.5; 960], 1, 48000);
+        assert_eq!(reference.take(160, 16000).len(), 160);
+    }
+
+    #[test]
+    fn test_cancels_delayed_echo() {
+        let sample_rate = 48000;
+        let echo_delay = 300;
+        let block = 1024;
+        let far_end = noise(sample_rate as usize * 2, 0.3, 7);
+
+        let reference = EchoReference::shared();
+        let mut canceller = EchoCanceller::new(sample_rate);
+        let mut microphone_history = vec![0.0; echo_delay];
+        let mut residual = Vec::new();
+        let mut captured = Vec::new();
+
+        for chunk in far_end.chunks(block) {
+            reference.lock().push_played(chunk, 1, sample_rate);
+
+            // Le micro entend les haut-parleurs atténués et retardés
+            microphone_history.extend(chunk.iter().map(|s| s * 0.5));
+            let microphone: Vec<f32> = microphone_history.drain(..chunk.len()).collect();
+
+            let far = reference.lock().take(microphone.len(), sample_rate);
+            residual.extend(canceller.process(&microphone, &far));
+            captured.extend(microphone);
+        }
+
+        let delay = canceller.delay().expect("delay should be estimated");
+        assert!(delay.abs_diff(echo_delay) <= DECIMATION, "delay {}", delay);
+
+        let tail = captured.len() - sample_rate as usize / 2;
+        let erle = rms(&residual[tail..]) / rms(&captured[tail..]);
+        assert!(erle < 0.1, "residual ratio {}", erle);
+    }
+}
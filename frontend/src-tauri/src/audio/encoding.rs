@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+/// Niveau d'encodage demandé par le serveur (identique au backend)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingQuality {
+    Low,
+    Medium,
+    High,
+    Adaptive,
+}
+
+/// Paramètres d'encodage que le serveur impose aux émetteurs du channel, d'après
+/// la qualité de réception de ses membres
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncodingProfile {
+    pub quality: EncodingQuality,
+    pub max_sample_rate: u32,
+    pub max_channels: u8,
+    /// Durée minimale d'audio par packet
+    pub frame_ms: u32,
+}
+
+impl Default for EncodingProfile {
+    fn default() -> Self {
+        Self {
+            quality: EncodingQuality::High,
+            max_sample_rate: 48000,
+            max_channels: 2,
+            frame_ms: 20,
+        }
+    }
+}
+
+/// Ramène l'audio du micro au profil d'encodage : mixage en mono, réduction de la
+/// fréquence d'échantillonnage d'un facteur entier, regroupement en trames de `frame_ms`
+#[derive(Debug, Default)]
+pub struct FrameEncoder {
+    // Format d'entrée et profil des échantillons en attente ; un changement les abandonne
+    format: Option<(u32, u8, EncodingProfile)>,
+    // Échantillons entrelacés, déjà mixés, pas encore assez pour une trame
+    pending: Vec<f32>,
+}
+
+impl FrameEncoder {
+    /// Retourne les trames complètes disponibles (données, sample rate, canaux)
+    pub fn encode(
+        &mut self,
+        data: Vec<f32>,
+        sample_rate: u32,
+        channels: u8,
+        profile: &EncodingProfile,
+    ) -> Option<(Vec<f32>, u32, u8)> {
+        if sample_rate == 0 || channels == 0 {
+            return None;
+        }
+        if self.format != Some((sample_rate, channels, *profile)) {
+            self.format = Some((sample_rate, channels, *profile));
+            self.pending.clear();
+        }
+
+        let out_channels = channels.min(profile.max_channels.max(1));
+        if out_channels == channels {
+            self.pending.extend_from_slice(&data);
+        } else {
+            // Moyenne de tous les canaux en mono
+            self.pending.extend(data
+                .chunks_exact(channels as usize)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32));
+        }
+
+        let factor = sample_rate.div_ceil(profile.max_sample_rate.max(1)).max(1) as usize;
+        let frame_frames = (sample_rate as usize * profile.frame_ms as usize / 1000).div_ceil(factor).max(1) * factor;
+        let width = out_channels as usize;
+        let available = self.pending.len() / width;
+        let ready = available / frame_frames * frame_frames;
+        if ready == 0 {
+            return None;
+        }
+
+        let taken: Vec<f32> = self.pending.drain(..ready * width).collect();
+        let output = if factor == 1 {
+            taken
+        } else {
+            // Moyenne de `factor` frames successives (filtre passe-bas sommaire)
+            let mut output = Vec::with_capacity(taken.len() / factor);
+            for block in taken.chunks_exact(factor * width) {
+                for channel in 0..width {
+                    let sum: f32 = block.iter().skip(channel).step_by(width).sum();
+                    output.push(sum / factor as f32);
+                }
+            }
+            output
+        };
+
+        Some((output, sample_rate / factor as u32, out_channels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_encoder_applies_profile() {
+        let mut encoder = FrameEncoder::default();
+        let low = EncodingProfile {
+            quality: EncodingQuality::Low,
+            max_sample_rate: 16000,
+            max_channels: 1,
+            frame_ms: 40,
+        };
+
+        // 10 ms de stéréo à 48 kHz : pas encore une trame de 40 ms
+        let stereo = vec![0.5f32; 480 * 2];
+        assert!(encoder.encode(stereo.clone(), 48000, 2, &low).is_none());
+        for _ in 0..2 {
+            assert!(encoder.encode(stereo.clone(), 48000, 2, &low).is_none());
+        }
+
+        let (data, sample_rate, channels) = encoder.encode(stereo, 48000, 2, &low).unwrap();
+        assert_eq!((sample_rate, channels), (16000, 1));
+        assert_eq!(data.len(), 640);
+        assert!(data.iter().all(|s| (s - 0.5).abs() < 1e-6));
+
+        // Profil par défaut : le format d'origine est conservé
+        let (data, sample_rate, channels) = encoder.encode(vec![0.1; 960 * 2], 48000, 2, &EncodingProfile::default()).unwrap();
+        assert_eq!((data.len(), sample_rate, channels), (960 * 2, 48000, 2));
+    }
+}
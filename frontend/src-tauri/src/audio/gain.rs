@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+
+/// Gain minimal appliqué par l'AGC aux micros trop forts
+const AGC_MIN_GAIN_DB: f32 = -20.0;
+/// En dessous de ce niveau le signal est considéré comme du silence (gain figé)
+const AGC_SILENCE_DB: f32 = -50.0;
+/// Constante de temps de lissage du niveau mesuré (en secondes)
+const AGC_LEVEL_SMOOTHING_SECONDS: f32 = 0.4;
+/// Vitesse maximale de remontée / descente du gain (en dB par seconde)
+const AGC_RISE_DB_PER_SECOND: f32 = 6.0;
+const AGC_FALL_DB_PER_SECOND: f32 = 30.0;
+/// Crête maximale tolérée après gain, pour ne jamais saturer
+const MAX_OUTPUT_PEAK: f32 = 0.98;
+/// Fréquence d'émission des niveaux d'entrée (VU-mètre)
+pub const LEVEL_UPDATES_PER_SECOND: u32 = 30;
+/// Niveau plancher rapporté pour le silence
+const SILENCE_FLOOR_DB: f32 = -96.0;
+
+pub fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+pub fn linear_to_db(value: f32) -> f32 {
+    if value > 0.0 {
+        (20.0 * value.log10()).max(SILENCE_FLOOR_DB)
+    } else {
+        SILENCE_FLOOR_DB
+    }
+}
+
+/// Réglages de gain du micro
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputGainSettings {
+    /// Gain manuel appliqué avant l'AGC
+    pub manual_gain_db: f32,
+    pub agc_enabled: bool,
+    /// Niveau RMS visé par l'AGC (dBFS)
+    pub agc_target_db: f32,
+    /// Gain maximal que l'AGC peut ajouter
+    pub agc_max_gain_db: f32,
+}
+
+impl Default for InputGainSettings {
+    fn default() -> Self {
+        Self {
+            manual_gain_db: 0.0,
+            agc_enabled: true,
+            agc_target_db: -20.0,
+            agc_max_gain_db: 20.0,
+        }
+    }
+}
+
+impl InputGainSettings {
+    /// Ramène les valeurs dans des bornes raisonnables
+    pub fn clamped(self) -> Self {
+        Self {
+            manual_gain_db: self.manual_gain_db.clamp(-30.0, 30.0),
+            agc_enabled: self.agc_enabled,
+            agc_target_db: self.agc_target_db.clamp(-40.0, -6.0),
+            agc_max_gain_db: self.agc_max_gain_db.clamp(0.0, 40.0),
+        }
+    }
+}
+
+/// Niveau du micro envoyé à l'interface (événement `audio-level`)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InputLevel {
+    pub rms_db: f32,
+    pub peak_db: f32,
+    /// Gain total appliqué (manuel + AGC)
+    pub gain_db: f32,
+    pub clipping: bool,
+}
+
+/// Gain manuel, AGC et protection contre l'écrêtage, sur des échantillons entrelacés
+#[derive(Debug)]
+pub struct InputGainProcessor {
+    sample_rate: u32,
+    level_db: f32,
+    agc_gain_db: f32,
+    applied_gain: f32,
+}
+
+impl InputGainProcessor {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            level_db: SILENCE_FLOOR_DB,
+            agc_gain_db: 0.0,
+            applied_gain: 1.0,
+        }
+    }
+
+    /// Gain total appliqué au dernier bloc
+    pub fn gain_db(&self) -> f32 {
+        linear_to_db(self.applied_gain)
+    }
+
+    pub fn process(&mut self, data: &mut [f32], channels: u8, settings: &InputGainSettings) {
+        if data.is_empty() {
+            return;
+        }
+        let frames = data.len() / channels.max(1) as usize;
+        let block_seconds = frames as f32 / self.sample_rate as f32;
+        let manual = db_to_linear(settings.manual_gain_db);
+
+        if settings.agc_enabled {
+            let rms = (data.iter().map(|s| s * s).sum::<f32>() / data.len() as f32).sqrt();
+            let block_db = linear_to_db(rms * manual);
+
+            // Lissage du niveau, sauf en silence pour ne pas amplifier le bruit de fond
+            if block_db > AGC_SILENCE_DB {
+                let alpha = 1.0 - (-block_seconds / AGC_LEVEL_SMOOTHING_SECONDS).exp();
+                self.level_db = if self.level_db <= SILENCE_FLOOR_DB {
+                    block_db
+                } else {
+                    self.level_db + alpha * (block_db - self.level_db)
+                };
+
+                let wanted = (settings.agc_target_db - self.level_db)
+                    .clamp(AGC_MIN_GAIN_DB, settings.agc_max_gain_db);
+                let delta = wanted - self.agc_gain_db;
+                self.agc_gain_db += delta.clamp(
+                    -AGC_FALL_DB_PER_SECOND * block_seconds,
+                    AGC_RISE_DB_PER_SECOND * block_seconds,
+                );
+            }
+        } else {
+            self.agc_gain_db = 0.0;
+        }
+
+        let mut target = manual * db_to_linear(self.agc_gain_db);
+        let peak = data.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        if peak * target > MAX_OUTPUT_PEAK {
+            target = MAX_OUTPUT_PEAK / peak;
+        }
+
+        // Rampe de gain sur le bloc pour éviter les clics (baisse immédiate contre la saturation)
+        let start = self.applied_gain.min(target);
+        let channels = channels.max(1) as usize;
+        for (frame, samples) in data.chunks_mut(channels).enumerate() {
+            let gain = start + (target - start) * (frame + 1) as f32 / frames.max(1) as f32;
+            for sample in samples {
+                *sample = (*sample * gain).clamp(-1.0, 1.0);
+            }
+        }
+        self.applied_gain = target;
+    }
+}
+
+/// Mesure RMS / crête accumulée, restituée ~30 fois par seconde
+#[derive(Debug)]
+pub struct LevelMeter {
+    window: usize,
+    sum_squares: f32,
+    peak: f32,
+    measured: usize,
+    count: usize,
+}
+
+impl LevelMeter {
+    pub fn new(sample_rate: u32, channels: u8) -> Self {
+        Self {
+            window: (sample_rate * channels.max(1) as u32 / LEVEL_UPDATES_PER_SECOND).max(1) as usize,
+            sum_squares: 0.0,
+            peak: 0.0,
+            measured: 0,
+            count: 0,
+        }
+    }
+
+    /// Ajoute un bloc et renvoie un niveau quand la fenêtre est complète
+    pub fn push(&mut self, data: &[f32], gain_db: f32) -> Option<InputLevel> {
+        for sample in data {
+            self.sum_squares += sample * sample;
+            self.peak = self.peak.max(sample.abs());
+        }
+        self.measured += data.len();
+        self.count += data.len();
+
+        if self.count < self.window {
+            return None;
+        }
+
+        let level = InputLevel {
+            rms_db: linear_to_db((self.sum_squares / self.measured as f32).sqrt()),
+            peak_db: linear_to_db(self.peak),
+            gain_db,
+            clipping: self.peak >= MAX_OUTPUT_PEAK,
+        };
+        self.sum_squares = 0.0;
+        self.peak = 0.0;
+        self.measured = 0;
+        // Report du surplus pour tenir la cadence moyenne malgré la taille des blocs
+        self.count -= self.window;
+        Some(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(len: usize, amplitude: f32) -> Vec<f32> {
+        (0..len).map(|i| amplitude * (i as f32 * 0.06).sin()).collect()
+    }
+
+    fn rms_db(samples: &[f32]) -> f32 {
+        linear_to_db((samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt())
+    }
+
+    #[test]
+    fn test_agc_brings_quiet_and_loud_inputs_to_target() {
+        let settings = InputGainSettings::default();
+
+        for amplitude in [0.02, 0.9] {
+            let mut processor = InputGainProcessor::new(48000);
+            let mut last = Vec::new();
+            // 5 secondes de signal en blocs de 1024
+            for _ in 0..235 {
+                last = sine(1024, amplitude);
+                processor.process(&mut last, 1, &settings);
+            }
+            let level = rms_db(&last);
+            assert!((level - settings.agc_target_db).abs() < 2.0, "amplitude {} -> {} dB", amplitude, level);
+        }
+    }
+
+    #[test]
+    fn test_manual_gain_never_clips() {
+        let settings = InputGainSettings { manual_gain_db: 30.0, agc_enabled: false, ..Default::default() };
+        let mut processor = InputGainProcessor::new(48000);
+        let mut data = sine(1024, 0.5);
+        processor.process(&mut data, 1, &settings);
+        processor.process(&mut data, 1, &settings);
+        assert!(data.iter().all(|s| s.abs() <= MAX_OUTPUT_PEAK + 1e-6));
+    }
+
+    #[test]
+    fn test_level_meter_rate() {
+        let mut meter = LevelMeter::new(48000, 2);
+        let levels: Vec<InputLevel> = (0..94)
+            .filter_map(|_| meter.push(&sine(1024, 0.5), 0.0))
+            .collect();
+        // ~1 seconde de stéréo : environ 30 mesures
+        assert!((29..=31).contains(&levels.len()), "{} levels", levels.len());
+        assert!((levels[0].peak_db - linear_to_db(0.5)).abs() < 0.5);
+    }
+}
@@ -1,7 +1,16 @@
 pub mod devices;
 pub mod capture;
 pub mod playback;
+pub mod noise_suppression;
+pub mod echo_cancellation;
+pub mod gain;
+pub mod stream_config;
+pub mod encoding;
 
 pub use devices::AudioDeviceManager;
 pub use capture::AudioCaptureManager;
-pub use playback::AudioPlaybackManager;
\ No newline at end of file
+pub use playback::AudioPlaybackManager;
+pub use noise_suppression::NoiseSuppressionLevel;
+pub use gain::InputGainSettings;
+pub use stream_config::StreamSettings;
+pub use encoding::EncodingProfile;
\ No newline at end of file
@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+/// Taille d'une trame d'analyse (puissance de deux pour la FFT)
+const FRAME_SIZE: usize = 512;
+/// Décalage entre deux trames (recouvrement de 50%)
+const HOP_SIZE: usize = FRAME_SIZE / 2;
+/// Nombre de trames considérées comme du bruit au démarrage
+const WARMUP_FRAMES: usize = 8;
+/// Lissage temporel de la puissance par bin
+const POWER_SMOOTHING: f32 = 0.7;
+/// Au-delà de ce rapport puissance/bruit, le bin est considéré comme de la voix
+const SPEECH_RATIO: f32 = 4.0;
+/// Vitesse de suivi du bruit dans les bins sans voix
+const NOISE_TRACKING: f32 = 0.05;
+/// Poids de l'estimation précédente dans le SNR a priori
+const DECISION_DIRECTED: f32 = 0.98;
+/// Remontée lente de l'estimation du bruit par trame (~2 dB/s à 48kHz)
+const NOISE_RISE: f32 = 1.0025;
+
+/// Niveau de réduction de bruit appliqué au micro
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseSuppressionLevel {
+    Off,
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl NoiseSuppressionLevel {
+    /// Facteur de sur-soustraction et gain plancher du niveau
+    fn parameters(self) -> (f32, f32) {
+        match self {
+            NoiseSuppressionLevel::Off => (0.0, 1.0),
+            NoiseSuppressionLevel::Low => (1.0, 0.3),   // ~ -10 dB
+            NoiseSuppressionLevel::Medium => (1.5, 0.1), // -20 dB
+            NoiseSuppressionLevel::High => (2.5, 0.03), // ~ -30 dB
+        }
+    }
+}
+
+/// Réducteur de bruit spectral (filtre de Wiener)
+///
+/// Le signal est découpé en trames fenêtrées, le plancher de bruit est suivi par bin
+/// hors des passages de voix puis atténué avant reconstruction par overlap-add.
+/// Introduit une latence de `FRAME_SIZE` échantillons.
+#[derive(Debug)]
+pub struct NoiseSuppressor {
+    level: NoiseSuppressionLevel,
+    window: Vec<f32>,
+    fft: Fft,
+    input: VecDeque<f32>,
+    output: VecDeque<f32>,
+    overlap: Vec<f32>,
+    power: Vec<f32>,
+    noise: Vec<f32>,
+    gains: Vec<f32>,
+    clean: Vec<f32>,
+    frames: usize,
+}
+
+impl NoiseSuppressor {
+    pub fn new(level: NoiseSuppressionLevel) -> Self {
+        // Racine d'une fenêtre de Hann périodique : analyse + synthèse = reconstruction parfaite
+        let window = (0..FRAME_SIZE)
+            .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f32 / FRAME_SIZE as f32).cos()).sqrt())
+            .collect();
+        let bins = FRAME_SIZE / 2 + 1;
+
+        Self {
+            level,
+            window,
+            fft: Fft::new(FRAME_SIZE),
+            input: VecDeque::with_capacity(FRAME_SIZE * 2),
+            output: std::iter::repeat_n(0.0, FRAME_SIZE).collect(),
+            overlap: vec![0.0; FRAME_SIZE],
+            power: vec![0.0; bins],
+            noise: vec![0.0; bins],
+            gains: vec![1.0; bins],
+            clean: vec![0.0; bins],
+            frames: 0,
+        }
+    }
+
+    pub fn level(&self) -> NoiseSuppressionLevel {
+        self.level
+    }
+
+    /// Change le niveau sans perdre l'estimation du bruit
+    pub fn set_level(&mut self, level: NoiseSuppressionLevel) {
+        self.level = level;
+    }
+
+    /// Traite un bloc mono et renvoie le même nombre d'échantillons
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.input.extend(samples);
+        while self.input.len() >= FRAME_SIZE {
+            self.process_frame();
+            self.input.drain(..HOP_SIZE);
+        }
+        self.output.drain(..samples.len().min(self.output.len())).collect()
+    }
+
+    fn process_frame(&mut self) {
+        let mut re: Vec<f32> = self.input.iter().take(FRAME_SIZE)
+            .zip(&self.window)
+            .map(|(s, w)| s * w)
+            .collect();
+        let mut im = vec![0.0; FRAME_SIZE];
+        self.fft.transform(&mut re, &mut im, false);
+
+        let (over_subtraction, floor) = self.level.parameters();
+        let warming_up = self.frames < WARMUP_FRAMES;
+
+        for bin in 0..self.power.len() {
+            let energy = re[bin] * re[bin] + im[bin] * im[bin];
+            self.power[bin] = if self.frames == 0 {
+                energy
+            } else {
+                POWER_SMOOTHING * self.power[bin] + (1.0 - POWER_SMOOTHING) * energy
+            };
+
+            // Descente immédiate, moyenne hors voix, remontée lente pendant la voix
+            let power = self.power[bin];
+            self.noise[bin] = if warming_up {
+                if self.frames == 0 { power } else { 0.8 * self.noise[bin] + 0.2 * power }
+            } else if power < self.noise[bin] {
+                power
+            } else if power < SPEECH_RATIO * self.noise[bin] {
+                (1.0 - NOISE_TRACKING) * self.noise[bin] + NOISE_TRACKING * power
+            } else {
+                self.noise[bin] * NOISE_RISE + f32::EPSILON
+            };
+
+            // Gain de Wiener avec SNR a priori « decision-directed » (limite le bruit musical)
+            let noise = over_subtraction * self.noise[bin] + f32::EPSILON;
+            let posterior = (energy / noise - 1.0).max(0.0);
+            let prior = DECISION_DIRECTED * self.clean[bin] / noise
+                + (1.0 - DECISION_DIRECTED) * posterior;
+            let gain = (prior / (1.0 + prior)).max(floor);
+            self.gains[bin] = gain;
+            self.clean[bin] = gain * gain * energy;
+        }
+
+        // Spectre symétrique d'un signal réel
+        for bin in 0..FRAME_SIZE {
+            let gain = self.gains[bin.min(FRAME_SIZE - bin)];
+            re[bin] *= gain;
+            im[bin] *= gain;
+        }
+        self.fft.transform(&mut re, &mut im, true);
+
+        for ((overlap, sample), w) in self.overlap.iter_mut().zip(&re).zip(&self.window) {
+            *overlap += sample * w;
+        }
+        self.output.extend(self.overlap.drain(..HOP_SIZE));
+        self.overlap.extend(std::iter::repeat_n(0.0, HOP_SIZE));
+        self.frames += 1;
+    }
+}
+
+/// FFT complexe radix-2 en place
+#[derive(Debug)]
+struct Fft {
+    size: usize,
+    cos: Vec<f32>,
+    sin: Vec<f32>,
+}
+
+impl Fft {
+    fn new(size: usize) -> Self {
+        debug_assert!(size.is_power_of_two());
+        let half = size / 2;
+        Self {
+            size,
+            cos: (0..half).map(|k| (2.0 * PI * k as f32 / size as f32).cos()).collect(),
+            sin: (0..half).map(|k| (2.0 * PI * k as f32 / size as f32).sin()).collect(),
+        }
+    }
+
+    /// Transformée directe, ou inverse normalisée si `inverse`
+    fn transform(&self, re: &mut [f32], im: &mut [f32], inverse: bool) {
+        let n = self.size;
+
+        // Permutation par inversion de bits
+        let mut j = 0;
+        for i in 1..n {
+            let mut bit = n >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                re.swap(i, j);
+                im.swap(i, j);
+            }
+        }
+
+        let sign = if inverse { 1.0 } else { -1.0 };
+        let mut len = 2;
+        while len <= n {
+            let step = n / len;
+            for start in (0..n).step_by(len) {
+                for k in 0..len / 2 {
+                    let wr = self.cos[k * step];
+                    let wi = sign * self.sin[k * step];
+                    let a = start + k;
+                    let b = a + len / 2;
+                    let tr = re[b] * wr - im[b] * wi;
+                    let ti = re[b] * wi + im[b] * wr;
+                    re[b] = re[a] - tr;
+                    im[b] = im[a] - ti;
+                    re[a] += tr;
+                    im[a] += ti;
+                }
+            }
+            len <<= 1;
+        }
+
+        if inverse {
+            let scale = 1.0 / n as f32;
+            re.iter_mut().for_each(|v| *v *= scale);
+            im.iter_mut().for_each(|v| *v *= scale);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// Bruit blanc déterministe (LCG)
+    fn noise(len: usize, amplitude: f32) -> Vec<f32> {
+        let mut state = 12345u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_round_trip() {
+        let fft = Fft::new(64);
+        let original: Vec<f32> = (0..64).map(|i| (i as f32 * 0.3).sin()).collect();
+        let mut re = original.clone();
+        let mut im = vec![0.0; 64];
+        fft.transform(&mut re, &mut im, false);
+        fft.transform(&mut re, &mut im, true);
+        for (a, b) in re.iter().zip(&original) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_suppresses_stationary_noise_and_keeps_voice() {
+        let sample_rate = 48000.0;
+        let background = noise(48000 * 2, 0.05);
+        let mut suppressor = NoiseSuppressor::new(NoiseSuppressionLevel::Medium);
+
+        // Une seconde de bruit seul : le plancher doit être appris puis atténué
+        let mut denoised = Vec::new();
+        for chunk in background[..48000].chunks(1024) {
+            denoised.extend(suppressor.process(chunk));
+        }
+        assert!(rms(&denoised[24000..]) < rms(&background[24000..48000]) * 0.4);
+
+        // Une "voix" (sinus) par-dessus le bruit doit passer quasiment intacte
+        let voice: Vec<f32> = (0..48000)
+            .map(|i| 0.3 * (2.0 * PI * 440.0 * i as f32 / sample_rate).sin())
+            .collect();
+        let mixed: Vec<f32> = voice.iter().zip(&background[48000..]).map(|(v, n)| v + n).collect();
+        let mut output = Vec::new();
+        for chunk in mixed.chunks(1024) {
+            output.extend(suppressor.process(chunk));
+        }
+        let ratio = rms(&output[24000..]) / rms(&voice[24000..]);
+        assert!(ratio > 0.8 && ratio < 1.2, "voice ratio {}", ratio);
+    }
+}
@@ -4,12 +4,17 @@ use cpal::{
     traits::{DeviceTrait, HostTrait, StreamTrait},
     Device, SampleFormat, StreamConfig,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
 use parking_lot::RwLock;
 use tokio::sync::mpsc;
 use tokio::net::UdpSocket;
 use uuid::Uuid;
-use crate::networking::{AudioPacket, PacketType};
+use crate::networking::{AudioPacket, PacketType, UdpControl};
+use super::echo_cancellation::{EchoReference, SharedEchoReference};
+use super::stream_config::{resolve_config, StreamSettings};
 
 /// Gestionnaire de lecture audio (haut-parleurs)
 #[derive(Debug)]
@@ -18,8 +23,21 @@ pub struct AudioPlaybackManager {
     is_playing: Arc<RwLock<bool>>,
     user_id: Arc<RwLock<Option<Uuid>>>,
     control_tx: Arc<RwLock<Option<mpsc::UnboundedSender<bool>>>>,
+    // Signal joué, utilisé par la capture comme référence d'annulation d'écho
+    echo_reference: SharedEchoReference,
+    // Configuration du stream choisie par l'utilisateur
+    stream_settings: Arc<RwLock<StreamSettings>>,
+    // Sourdine : l'audio reçu est jeté mais le socket reste lu
+    deafened: Arc<AtomicBool>,
+    // Volume de lecture par utilisateur, appliqué aux packets reçus
+    user_volumes: Arc<RwLock<HashMap<Uuid, f32>>>,
+    // Audio accumulé (en ms) avant de reprendre la lecture après un manque, fixé par le channel
+    latency_target_ms: Arc<AtomicU64>,
 }
 
+/// Cible du tampon de lecture tant que le serveur n'en a pas annoncé
+const DEFAULT_LATENCY_TARGET_MS: u64 = 50;
+
 impl AudioPlaybackManager {
     pub fn new() -> Self {
         Self {
@@ -27,9 +45,19 @@ impl AudioPlaybackManager {
             is_playing: Arc::new(RwLock::new(false)),
             user_id: Arc::new(RwLock::new(None)),
             control_tx: Arc::new(RwLock::new(None)),
+            echo_reference: EchoReference::shared(),
+            stream_settings: Arc::new(RwLock::new(StreamSettings::default())),
+            deafened: Arc::new(AtomicBool::new(false)),
+            user_volumes: Arc::new(RwLock::new(HashMap::new())),
+            latency_target_ms: Arc::new(AtomicU64::new(DEFAULT_LATENCY_TARGET_MS)),
         }
     }
 
+    /// Référence far-end alimentée par le stream de sortie
+    pub fn echo_reference(&self) -> SharedEchoReference {
+        self.echo_reference.clone()
+    }
+
     /// Configure l'utilisateur
     pub fn set_user(&self, user_id: Uuid) {
         *self.user_id.write() = Some(user_id);
@@ -42,6 +70,50 @@ impl AudioPlaybackManager {
         Ok(())
     }
 
+    /// Change de périphérique sans interrompre la lecture (le stream est recréé)
+    pub fn switch_device(&self, device_name: String) {
+        *self.device_name.write() = Some(device_name);
+    }
+
+    /// Change la configuration du stream (appliquée à chaud, le stream est recréé)
+    pub fn set_stream_settings(&self, settings: StreamSettings) {
+        *self.stream_settings.write() = settings;
+    }
+
+    pub fn get_stream_settings(&self) -> StreamSettings {
+        self.stream_settings.read().clone()
+    }
+
+    /// Coupe ou rétablit le son du channel. L'écoute du socket partagé continue : elle
+    /// seule traite les réponses aux heartbeats du serveur.
+    pub fn set_deafened(&self, deafened: bool) {
+        self.deafened.store(deafened, Ordering::SeqCst);
+    }
+
+    pub fn is_deafened(&self) -> bool {
+        self.deafened.load(Ordering::SeqCst)
+    }
+
+    /// Remplace les volumes par utilisateur (appliqués à chaud)
+    pub fn set_user_volumes(&self, volumes: HashMap<Uuid, f32>) {
+        *self.user_volumes.write() = volumes;
+    }
+
+    /// Règle le volume d'un utilisateur (1.0 = inchangé)
+    pub fn set_user_volume(&self, user_id: Uuid, volume: f32) {
+        let mut volumes = self.user_volumes.write();
+        if volume == 1.0 {
+            volumes.remove(&user_id);
+        } else {
+            volumes.insert(user_id, volume);
+        }
+    }
+
+    /// Règle le tampon de lecture du channel (appliqué à chaud)
+    pub fn set_latency_target(&self, latency_target_ms: u64) {
+        self.latency_target_ms.store(latency_target_ms, Ordering::SeqCst);
+    }
+
     /// Démarre la lecture audio et l'écoute UDP
     pub async fn start_playback(&self, server_addr: std::net::SocketAddr) -> Result<()> {
         // println!("🔊 AudioPlaybackManager: Starting audio playback...");
@@ -69,6 +141,7 @@ impl AudioPlaybackManager {
         let (audio_tx, audio_rx) = mpsc::unbounded_channel::<(Vec<f32>, u32, u8)>();
 
         let is_playing = self.is_playing.clone();
+        *is_playing.write() = true;
         
         // Utiliser le client UDP existant pour l'écoute au lieu de créer un nouveau socket
         // println!("🔊 AudioPlaybackManager: Using shared UDP client for audio reception...");
@@ -90,21 +163,26 @@ impl AudioPlaybackManager {
 
         // Démarrer la lecture dans un thread système (pas une tâche async)
         println!("🔊 AudioPlaybackManager: Starting playback task...");
-        let device_name_clone = device_name;
+        let device_name_clone = self.device_name.clone();
         let is_playing_clone = is_playing.clone();
         let audio_rx_moved = audio_rx;
+        let echo_reference = self.echo_reference.clone();
+        let stream_settings = self.stream_settings.clone();
+        let latency_target_ms = self.latency_target_ms.clone();
         
         std::thread::spawn(move || {
             if let Err(e) = Self::start_playback_task_sync(
                 device_name_clone,
                 is_playing_clone,
                 audio_rx_moved,
+                echo_reference,
+                stream_settings,
+                latency_target_ms,
             ) {
                 eprintln!("❌ Audio playback error: {}", e);
             }
         });
 
-        *self.is_playing.write() = true;
         // println!("✅ AudioPlaybackManager: Audio playback started successfully");
         Ok(())
     }
@@ -114,6 +192,7 @@ impl AudioPlaybackManager {
         &self,
         server_addr: std::net::SocketAddr,
         udp_socket: Arc<tokio::net::UdpSocket>,
+        udp_control: UdpControl,
     ) -> Result<()> {
         if *self.is_playing.read() {
             println!("⚠️ AudioPlaybackManager: Already playing, ignoring start request");
@@ -143,6 +222,8 @@ impl AudioPlaybackManager {
         let user_id_clone = user_id;
         let udp_socket_clone = Arc::clone(&udp_socket);
         let control_rx_clone = control_rx;
+        let deafened = self.deafened.clone();
+        let user_volumes = self.user_volumes.clone();
         tokio::spawn(async move {
             if let Err(e) = Self::start_udp_listener_with_shared_socket(
                 server_addr,
@@ -150,31 +231,40 @@ impl AudioPlaybackManager {
                 audio_tx_clone,
                 control_rx_clone,
                 udp_socket_clone,
+                udp_control,
+                deafened,
+                user_volumes,
             ).await {
                 eprintln!("❌ UDP listener error: {}", e);
             }
         });
 
-        // Créer le receiver partagé pour le thread audio
-        let is_playing = Arc::new(RwLock::new(true));
+        // Le thread de lecture tourne tant que ce flag reste levé
+        let is_playing = self.is_playing.clone();
+        *is_playing.write() = true;
 
         // Démarrer la lecture dans un thread système (pas une tâche async)
         println!("🔊 AudioPlaybackManager: Starting playback task...");
-        let device_name_clone = device_name;
+        let device_name_clone = self.device_name.clone();
         let is_playing_clone = is_playing.clone();
         let audio_rx_moved = audio_rx;
+        let echo_reference = self.echo_reference.clone();
+        let stream_settings = self.stream_settings.clone();
+        let latency_target_ms = self.latency_target_ms.clone();
         
         std::thread::spawn(move || {
             if let Err(e) = Self::start_playback_task_sync(
                 device_name_clone,
                 is_playing_clone,
                 audio_rx_moved,
+                echo_reference,
+                stream_settings,
+                latency_target_ms,
             ) {
                 eprintln!("❌ Audio playback error: {}", e);
             }
         });
 
-        *self.is_playing.write() = true;
         // println!("✅ AudioPlaybackManager: Audio playback started successfully with shared socket");
         Ok(())
     }
@@ -264,6 +354,13 @@ impl AudioPlaybackManager {
         Ok(())
     }
 
+    /// Applique le volume d'un utilisateur en restant dans [-1, 1]
+    fn apply_volume(samples: &mut [f32], volume: f32) {
+        for sample in samples.iter_mut() {
+            *sample = (*sample * volume).clamp(-1.0, 1.0);
+        }
+    }
+
     /// Convertit les bytes PCM 16-bit en échantillons f32
     fn pcm_to_f32(pcm_data: &Bytes) -> Vec<f32> {
         let samples: Vec<f32> = pcm_data
@@ -359,64 +456,88 @@ impl AudioPlaybackManager {
         audio_tx: mpsc::UnboundedSender<(Vec<f32>, u32, u8)>,
         mut control_rx: mpsc::UnboundedReceiver<bool>,
         udp_socket: Arc<tokio::net::UdpSocket>,
+        udp_control: UdpControl,
+        deafened: Arc<AtomicBool>,
+        user_volumes: Arc<RwLock<HashMap<Uuid, f32>>>,
     ) -> Result<()> {
         println!("🔊 UdpListener: Starting UDP listener with shared socket on {:?}", udp_socket.local_addr()?);
         
         let mut buf = vec![0u8; 4096];
+        // Audio reçu par le tunnel WebSocket quand l'UDP est bloqué
+        let mut tunnel_rx = udp_control.subscribe_tunnel();
         
         loop {
-            tokio::select! {
+            let (data, tunneled) = tokio::select! {
                 // Vérifier les commandes d'arrêt
                 cmd = control_rx.recv() => {
                     if cmd.is_none() {
                         println!("🔊 UdpListener: Received stop command, shutting down");
                         break;
                     }
+                    continue;
                 }
                 // Recevoir des packets UDP
                 result = udp_socket.recv_from(&mut buf) => {
                     match result {
-                        Ok((size, from)) => {
+                        Ok((size, from)) if from.ip() == server_addr.ip() && from.port() == server_addr.port() => {
                             // println!("📡 UdpListener: Received {} bytes from {}", size, from);
-                            if from.ip() == server_addr.ip() && from.port() == server_addr.port() {
-                                // Essayer de désérialiser le packet audio
-                                if let Ok(packet) = AudioPacket::from_bytes(&buf[..size]) {
-                                    // Traiter les packets audio de type Audio
-                                    if packet.header.packet_type == PacketType::Audio {
-                                        // En mode normal, on reçoit l'audio d'autres utilisateurs
-                                        // En mode loopback, on reçoit notre propre audio
-                                        let is_own_packet = packet.header.user_id == user_id;
-                                        
-                                        // println!("🔊 UdpListener: Received audio packet from user {} {} - Seq: {}, Payload: {} bytes, SR: {}Hz, CH: {}", 
-                                        //     packet.header.user_id, 
-                                        //     if is_own_packet { "(own)" } else { "(other)" },
-                                        //     packet.header.sequence, packet.payload.len(),
-                                        //     packet.header.sample_rate, packet.header.channels);
-                                        
-                                        // Convertir les bytes PCM en f32
-                                        let audio_samples = Self::pcm_to_f32(&packet.payload);
-                                        
-                                        // Envoyer vers le lecteur audio avec métadonnées pour conversion
-                                        if let Err(_) = audio_tx.send((audio_samples, packet.header.sample_rate, packet.header.channels)) {
-                                            // Channel fermé, arrêter
-                                            break;
-                                        }
-                                    } else {
-                                        println!("🔇 UdpListener: Ignoring non-audio packet type: {:?}", packet.header.packet_type);
-                                    }
-                                } else {
-                                    println!("❌ UdpListener: Failed to parse packet from {}", from);
-                                }
-                            } else {
-                                println!("🚫 UdpListener: Ignoring packet from {} (expecting {}:{})", from, server_addr.ip(), server_addr.port());
-                            }
+                            (buf[..size].to_vec(), false)
+                        }
+                        Ok((_, from)) => {
+                            println!("🚫 UdpListener: Ignoring packet from {} (expecting {}:{})", from, server_addr.ip(), server_addr.port());
+                            continue;
                         }
                         Err(e) => {
                             eprintln!("❌ UDP receive error: {}", e);
                             tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                            continue;
                         }
                     }
                 }
+                Some(data) = tunnel_rx.recv() => (data, true),
+            };
+
+            // Essayer de désérialiser le packet audio
+            let Ok(packet) = AudioPacket::from_bytes(&data) else {
+                println!("❌ UdpListener: Failed to parse audio packet");
+                continue;
+            };
+
+            // Traiter les packets audio de type Audio
+            if packet.header.packet_type == PacketType::Audio {
+                udp_control.record_audio(&packet);
+                if deafened.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                // En mode normal, on reçoit l'audio d'autres utilisateurs
+                // En mode loopback, on reçoit notre propre audio
+                let is_own_packet = packet.header.user_id == user_id;
+                
+                // println!("🔊 UdpListener: Received audio packet from user {} {} - Seq: {}, Payload: {} bytes, SR: {}Hz, CH: {}", 
+                //     packet.header.user_id, 
+                //     if is_own_packet { "(own)" } else { "(other)" },
+                //     packet.header.sequence, packet.payload.len(),
+                //     packet.header.sample_rate, packet.header.channels);
+                
+                // Convertir les bytes PCM en f32
+                let mut audio_samples = Self::pcm_to_f32(&packet.payload);
+                let volume = user_volumes.read().get(&packet.header.user_id).copied();
+                if let Some(volume) = volume {
+                    Self::apply_volume(&mut audio_samples, volume);
+                }
+                
+                // Envoyer vers le lecteur audio avec métadonnées pour conversion
+                if let Err(_) = audio_tx.send((audio_samples, packet.header.sample_rate, packet.header.channels)) {
+                    // Channel fermé, arrêter
+                    break;
+                }
+            } else if tunneled {
+                if !udp_control.handle_tunneled(&packet) {
+                    println!("🔇 UdpListener: Ignoring tunnelled packet type: {:?}", packet.header.packet_type);
+                }
+            } else if !udp_control.handle(&packet) {
+                println!("🔇 UdpListener: Ignoring non-audio packet type: {:?}", packet.header.packet_type);
             }
         }
         
@@ -536,15 +657,74 @@ impl AudioPlaybackManager {
     }
 
     /// Tâche de lecture audio (version synchrone pour thread)
+    ///
+    /// Le stream est recréé quand le périphérique change ou tombe en erreur (débranché).
     fn start_playback_task_sync(
-        device_name: String,
+        device_name: Arc<RwLock<Option<String>>>,
         is_playing: Arc<RwLock<bool>>,
         audio_rx: mpsc::UnboundedReceiver<(Vec<f32>, u32, u8)>,
+        echo_reference: SharedEchoReference,
+        stream_settings: Arc<RwLock<StreamSettings>>,
+        latency_target_ms: Arc<AtomicU64>,
     ) -> Result<()> {
-        println!("🔊 PlaybackTask: Starting audio playback task for device: {}", device_name);
+        println!("🔊 PlaybackTask: Starting audio playback task for device: {:?}", device_name.read());
         
         // Obtenir le device CPAL
         let host = cpal::default_host();
+
+        // Receiver partagé entre les streams successifs
+        let audio_rx = Arc::new(parking_lot::Mutex::new(audio_rx));
+
+        while *is_playing.read() {
+            let current_device = device_name.read().clone().unwrap_or_else(|| "default".to_string());
+            let current_settings = stream_settings.read().clone();
+            let stream_failed = Arc::new(AtomicBool::new(false));
+
+            let stream = match Self::open_output_stream(&host, &current_device, &current_settings, audio_rx.clone(), echo_reference.clone(), latency_target_ms.clone(), stream_failed.clone()) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("❌ PlaybackTask: Failed to open {}: {}", current_device, e);
+                    std::thread::sleep(Duration::from_millis(500));
+                    continue;
+                }
+            };
+            println!("✅ PlaybackTask: Audio stream started on {}", current_device);
+
+            // Maintenir le stream vivant dans ce thread (le stream ne sort jamais de ce thread)
+            while *is_playing.read() {
+                std::thread::sleep(Duration::from_millis(100));
+
+                if device_name.read().as_deref().unwrap_or("default") != current_device {
+                    println!("🔄 PlaybackTask: Device changed, rebuilding stream");
+                    break;
+                }
+                if *stream_settings.read() != current_settings {
+                    println!("🔄 PlaybackTask: Stream settings changed, rebuilding stream");
+                    break;
+                }
+                if stream_failed.load(Ordering::SeqCst) {
+                    println!("⚠️ PlaybackTask: Stream error on {}, rebuilding stream", current_device);
+                    std::thread::sleep(Duration::from_millis(500));
+                    break;
+                }
+            }
+            drop(stream);
+        }
+
+        println!("🔊 PlaybackTask: Playback task stopped");
+        Ok(())
+    }
+
+    /// Ouvre et démarre le stream de sortie du périphérique demandé
+    fn open_output_stream(
+        host: &cpal::Host,
+        device_name: &str,
+        settings: &StreamSettings,
+        audio_rx: Arc<parking_lot::Mutex<mpsc::UnboundedReceiver<(Vec<f32>, u32, u8)>>>,
+        echo_reference: SharedEchoReference,
+        latency_target_ms: Arc<AtomicU64>,
+        stream_failed: Arc<AtomicBool>,
+    ) -> Result<cpal::Stream> {
         let device = if device_name == "default" {
             host.default_output_device()
         } else {
@@ -556,56 +736,55 @@ impl AudioPlaybackManager {
         let device = device.context("No output audio device found")?;
         println!("🔊 PlaybackTask: Found audio device: {:?}", device.name());
 
-        // Configuration du stream
-        let config = device.default_output_config()
-            .context("Failed to get default output config")?;
-        let sample_rate = config.sample_rate().0;
-        let channels = config.channels() as usize;
-        println!("🔊 PlaybackTask: Audio config - Sample rate: {}, Channels: {}", sample_rate, channels);
-
-        // Buffer pour accumuler les échantillons
-        let audio_rx = Arc::new(parking_lot::Mutex::new(audio_rx));
+        // Configuration du stream : réglages utilisateur bornés aux capacités du périphérique
+        let chosen = resolve_config(&device, false, settings)?;
+        let config = chosen.stream_config();
+        let sample_rate = chosen.sample_rate;
+        let channels = chosen.channels as usize;
+        println!("🔊 PlaybackTask: Audio config - Sample rate: {}, Channels: {}, Format: {}, Buffer: {:?}",
+                 sample_rate, channels, chosen.sample_format, config.buffer_size);
 
         // Créer le stream selon le format
-        let stream = match config.sample_format() {
-            SampleFormat::F32 => Self::create_output_stream::<f32>(&device, &config.into(), audio_rx, sample_rate, channels)?,
-            SampleFormat::I16 => Self::create_output_stream_i16(&device, &config.into(), audio_rx, sample_rate, channels)?,
-            SampleFormat::U16 => Self::create_output_stream_u16(&device, &config.into(), audio_rx, sample_rate, channels)?,
-            _ => {
-                return Err(anyhow::anyhow!("Unsupported sample format: {:?}", config.sample_format()));
+        let stream = match chosen.sample_format {
+            SampleFormat::F32 => Self::create_output_stream::<f32>(&device, &config, audio_rx, echo_reference, latency_target_ms, stream_failed, sample_rate, channels)?,
+            SampleFormat::F64 => Self::create_output_stream::<f64>(&device, &config, audio_rx, echo_reference, latency_target_ms, stream_failed, sample_rate, channels)?,
+            SampleFormat::I32 => Self::create_output_stream::<i32>(&device, &config, audio_rx, echo_reference, latency_target_ms, stream_failed, sample_rate, channels)?,
+            SampleFormat::I16 => Self::create_output_stream::<i16>(&device, &config, audio_rx, echo_reference, latency_target_ms, stream_failed, sample_rate, channels)?,
+            SampleFormat::U16 => Self::create_output_stream::<u16>(&device, &config, audio_rx, echo_reference, latency_target_ms, stream_failed, sample_rate, channels)?,
+            SampleFormat::U8 => Self::create_output_stream::<u8>(&device, &config, audio_rx, echo_reference, latency_target_ms, stream_failed, sample_rate, channels)?,
+            format => {
+                return Err(anyhow::anyhow!("Unsupported sample format: {:?}", format));
             }
         };
 
-        println!("🔊 PlaybackTask: Stream created successfully, starting playback...");
         stream.play().context("Failed to start audio stream")?;
-        println!("✅ PlaybackTask: Audio stream started successfully!");
-
-        // Maintenir le stream vivant dans ce thread (le stream ne sort jamais de ce thread)
-        while *is_playing.read() {
-            std::thread::sleep(std::time::Duration::from_millis(100));
-        }
-
-        println!("🔊 PlaybackTask: Playback task stopped");
-        Ok(())
+        Ok(stream)
     }
 
-    /// Crée un stream de sortie audio typé
+    /// Crée un stream de sortie audio typé (conversion depuis f32 selon le format du périphérique)
     fn create_output_stream<T>(
         device: &Device,
         config: &StreamConfig,
         audio_rx: Arc<parking_lot::Mutex<mpsc::UnboundedReceiver<(Vec<f32>, u32, u8)>>>,
+        echo_reference: SharedEchoReference,
+        latency_target_ms: Arc<AtomicU64>,
+        stream_failed: Arc<AtomicBool>,
         output_sample_rate: u32,
         output_channels: usize,
     ) -> Result<cpal::Stream>
     where
-        T: cpal::Sample + cpal::SizedSample + Send + 'static,
-        f32: Into<T>,
+        T: cpal::SizedSample + cpal::FromSample<f32> + Send + 'static,
     {
         let mut output_buffer = Vec::<f32>::new();
+        // Silence tant que le tampon n'a pas atteint la cible (au départ et après un manque)
+        let mut buffering = true;
         let mut last_packet_time = std::time::Instant::now();
         const AUDIO_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100); // 100ms timeout
         
-        let err_fn = |err| eprintln!("Audio output stream error: {}", err);
+        let err_fn = move |err| {
+            eprintln!("Audio output stream error: {}", err);
+            stream_failed.store(true, Ordering::SeqCst);
+        };
         
         let stream = device.build_output_stream(
             config,
@@ -636,79 +815,33 @@ impl AudioPlaybackManager {
                     }
                 }
 
-                // Remplir le buffer de sortie
-                for (i, sample) in data.iter_mut().enumerate() {
-                    if i < output_buffer.len() {
-                        *sample = output_buffer[i].into();
-                    } else {
-                        *sample = T::EQUILIBRIUM; // Silence
-                    }
-                }
-
-                // Retirer les échantillons utilisés
-                if output_buffer.len() >= data.len() {
-                    output_buffer.drain(..data.len());
-                }
-            },
-            err_fn,
-            None,
-        )?;
-
-        Ok(stream)
-    }
-
-    /// Stream pour i16
-    fn create_output_stream_i16(
-        device: &Device,
-        config: &StreamConfig,
-        audio_rx: Arc<parking_lot::Mutex<mpsc::UnboundedReceiver<(Vec<f32>, u32, u8)>>>,
-        output_sample_rate: u32,
-        output_channels: usize,
-    ) -> Result<cpal::Stream> {
-        let mut output_buffer = Vec::<f32>::new();
-        let mut last_packet_time = std::time::Instant::now();
-        const AUDIO_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100); // 100ms timeout
-        
-        let err_fn = |err| eprintln!("Audio output stream error: {}", err);
-        
-        let stream = device.build_output_stream(
-            config,
-            move |data: &mut [i16], _: &cpal::OutputCallbackInfo| {
-                let now = std::time::Instant::now();
-                
-                // Essayer de recevoir de nouveaux échantillons
-                let mut received_new_packet = false;
-                while let Ok((samples, input_sr, input_ch)) = audio_rx.lock().try_recv() {
-                    received_new_packet = true;
-                    last_packet_time = now;
-                    
-                    let converted_samples = Self::convert_audio_format(
-                        samples,
-                        input_sr,
-                        input_ch,
-                        output_sample_rate,
-                        output_channels,
-                    );
-                    output_buffer.extend(converted_samples);
+                let target = Self::latency_target_samples(
+                    latency_target_ms.load(Ordering::Relaxed),
+                    output_sample_rate,
+                    output_channels,
+                );
+                if output_buffer.is_empty() {
+                    buffering = true;
+                } else if buffering && output_buffer.len() >= target {
+                    buffering = false;
                 }
-                
-                // Si timeout atteint sans nouveaux paquets, vider le buffer pour éviter les boucles
-                if !received_new_packet && now.duration_since(last_packet_time) > AUDIO_TIMEOUT {
-                    if !output_buffer.is_empty() {
-                        println!("🔇 Audio timeout reached (i16), clearing buffer to avoid loop");
-                        output_buffer.clear();
-                    }
+                if buffering {
+                    data.fill(T::EQUILIBRIUM);
+                    Self::record_played(&echo_reference, &[], data.len(), output_channels, output_sample_rate);
+                    return;
                 }
 
                 // Remplir le buffer de sortie
                 for (i, sample) in data.iter_mut().enumerate() {
                     if i < output_buffer.len() {
-                        *sample = (output_buffer[i].clamp(-1.0, 1.0) * 32767.0) as i16;
+                        *sample = T::from_sample(output_buffer[i].clamp(-1.0, 1.0));
                     } else {
-                        *sample = 0; // Silence
+                        *sample = T::EQUILIBRIUM; // Silence
                     }
                 }
 
+                Self::record_played(&echo_reference, &output_buffer, data.len(), output_channels, output_sample_rate);
+
                 // Retirer les échantillons utilisés
                 if output_buffer.len() >= data.len() {
                     output_buffer.drain(..data.len());
@@ -721,68 +854,22 @@ impl AudioPlaybackManager {
         Ok(stream)
     }
 
-    /// Stream pour u16
-    fn create_output_stream_u16(
-        device: &Device,
-        config: &StreamConfig,
-        audio_rx: Arc<parking_lot::Mutex<mpsc::UnboundedReceiver<(Vec<f32>, u32, u8)>>>,
-        output_sample_rate: u32,
-        output_channels: usize,
-    ) -> Result<cpal::Stream> {
-        let mut output_buffer = Vec::<f32>::new();
-        let mut last_packet_time = std::time::Instant::now();
-        const AUDIO_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100); // 100ms timeout
-        
-        let err_fn = |err| eprintln!("Audio output stream error: {}", err);
-        
-        let stream = device.build_output_stream(
-            config,
-            move |data: &mut [u16], _: &cpal::OutputCallbackInfo| {
-                let now = std::time::Instant::now();
-                
-                // Essayer de recevoir de nouveaux échantillons
-                let mut received_new_packet = false;
-                while let Ok((samples, input_sr, input_ch)) = audio_rx.lock().try_recv() {
-                    received_new_packet = true;
-                    last_packet_time = now;
-                    
-                    let converted_samples = Self::convert_audio_format(
-                        samples,
-                        input_sr,
-                        input_ch,
-                        output_sample_rate,
-                        output_channels,
-                    );
-                    output_buffer.extend(converted_samples);
-                }
-                
-                // Si timeout atteint sans nouveaux paquets, vider le buffer pour éviter les boucles
-                if !received_new_packet && now.duration_since(last_packet_time) > AUDIO_TIMEOUT {
-                    if !output_buffer.is_empty() {
-                        println!("🔇 Audio timeout reached (u16), clearing buffer to avoid loop");
-                        output_buffer.clear();
-                    }
-                }
-
-                // Remplir le buffer de sortie
-                for (i, sample) in data.iter_mut().enumerate() {
-                    if i < output_buffer.len() {
-                        *sample = ((output_buffer[i].clamp(-1.0, 1.0) + 1.0) * 32767.5) as u16;
-                    } else {
-                        *sample = 32768; // Silence pour unsigned
-                    }
-                }
-
-                // Retirer les échantillons utilisés
-                if output_buffer.len() >= data.len() {
-                    output_buffer.drain(..data.len());
-                }
-            },
-            err_fn,
-            None,
-        )?;
+    /// Nombre d'échantillons entrelacés correspondant à la cible du tampon de lecture
+    fn latency_target_samples(latency_target_ms: u64, sample_rate: u32, channels: usize) -> usize {
+        (sample_rate as u64 * latency_target_ms / 1000) as usize * channels
+    }
 
-        Ok(stream)
+    /// Transmet à la référence d'écho ce qui vient d'être joué (silence compris)
+    fn record_played(
+        echo_reference: &SharedEchoReference,
+        output_buffer: &[f32],
+        len: usize,
+        channels: usize,
+        sample_rate: u32,
+    ) {
+        let mut played = output_buffer[..len.min(output_buffer.len())].to_vec();
+        played.resize(len, 0.0);
+        echo_reference.lock().push_played(&played, channels, sample_rate);
     }
 
     /// Arrête la lecture audio
@@ -827,6 +914,11 @@ impl Clone for AudioPlaybackManager {
             is_playing: Arc::new(RwLock::new(false)),
             user_id: self.user_id.clone(),
             control_tx: Arc::new(RwLock::new(None)),
+            echo_reference: self.echo_reference.clone(),
+            stream_settings: self.stream_settings.clone(),
+            deafened: self.deafened.clone(),
+            user_volumes: self.user_volumes.clone(),
+            latency_target_ms: self.latency_target_ms.clone(),
         }
     }
 }
@@ -853,4 +945,95 @@ mod tests {
         assert!(!manager.is_playing());
         assert!(manager.get_device_name().is_none());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_latency_target_samples() {
+        // 50 ms de stéréo à 48 kHz
+        assert_eq!(AudioPlaybackManager::latency_target_samples(50, 48000, 2), 4800);
+        assert_eq!(AudioPlaybackManager::latency_target_samples(20, 44100, 1), 882);
+
+        let manager = AudioPlaybackManager::new();
+        manager.set_latency_target(120);
+        assert_eq!(manager.clone().latency_target_ms.load(Ordering::SeqCst), 120);
+    }
+
+    #[tokio::test]
+    async fn test_deafened_listener_keeps_udp_transport() {
+        use crate::networking::udp::SyncEcho;
+
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client = Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let (server_addr, client_addr) = (server.local_addr().unwrap(), client.local_addr().unwrap());
+        let control = UdpControl::default();
+        let (audio_tx, mut audio_rx) = mpsc::unbounded_channel();
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+        let listener = tokio::spawn(AudioPlaybackManager::start_udp_listener_with_shared_socket(
+            server_addr,
+            Uuid::new_v4(),
+            audio_tx,
+            control_rx,
+            client,
+            control.clone(),
+            Arc::new(AtomicBool::new(true)),
+            Arc::new(RwLock::new(HashMap::new())),
+        ));
+
+        // En sourdine, l'audio est jeté mais la réponse au heartbeat est traitée
+        let (sender, channel_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let audio = AudioPacket::audio(sender, channel_id, 0, Bytes::from(vec![0u8; 16]), 48000, 1);
+        server.send_to(&audio.to_bytes().unwrap(), client_addr).await.unwrap();
+        let echo = SyncEcho { observed: client_addr, client_sent_us: 0, server_received_us: 0 };
+        let reply = AudioPacket::new(PacketType::SyncReply, sender, channel_id, 0, Bytes::from(bincode::serialize(&echo).unwrap()), 48000, 1);
+        server.send_to(&reply.to_bytes().unwrap(), client_addr).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while control.last_sync_reply().is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.expect("heartbeat reply not handled while deafened");
+        assert_eq!(control.public_addr(), Some(client_addr));
+        assert!(!control.is_tunneled());
+        assert!(audio_rx.try_recv().is_err());
+
+        drop(control_tx);
+        listener.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_user_volume_scales_received_audio() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client = Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let (server_addr, client_addr) = (server.local_addr().unwrap(), client.local_addr().unwrap());
+        let manager = AudioPlaybackManager::new();
+        let (quiet, loud) = (Uuid::new_v4(), Uuid::new_v4());
+        manager.set_user_volume(quiet, 0.5);
+        manager.set_user_volume(loud, 2.0);
+        let (audio_tx, mut audio_rx) = mpsc::unbounded_channel();
+        let (control_tx, control_rx) = mpsc::unbounded_channel();
+        let listener = tokio::spawn(AudioPlaybackManager::start_udp_listener_with_shared_socket(
+            server_addr,
+            Uuid::new_v4(),
+            audio_tx,
+            control_rx,
+            client,
+            UdpControl::default(),
+            manager.deafened.clone(),
+            manager.user_volumes.clone(),
+        ));
+
+        // 16384 / 32767 ≈ 0.5 : réduit de moitié, puis doublé sans dépasser 1.0
+        let payload = Bytes::from(16384i16.to_le_bytes().repeat(4));
+        for sender in [quiet, loud] {
+            let audio = AudioPacket::audio(sender, Uuid::new_v4(), 0, payload.clone(), 48000, 1);
+            server.send_to(&audio.to_bytes().unwrap(), client_addr).await.unwrap();
+        }
+
+        let (samples, _, _) = tokio::time::timeout(Duration::from_secs(1), audio_rx.recv()).await.unwrap().unwrap();
+        assert!(samples.iter().all(|s| (s - 0.25).abs() < 0.001));
+        let (samples, _, _) = tokio::time::timeout(Duration::from_secs(1), audio_rx.recv()).await.unwrap().unwrap();
+        assert!(samples.iter().all(|s| (s - 1.0).abs() < 0.001));
+
+        drop(control_tx);
+        listener.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,278 @@
+use cpal::{
+    traits::DeviceTrait,
+    BufferSize, Device, SampleFormat, SampleRate, StreamConfig, SupportedBufferSize, SupportedStreamConfigRange,
+};
+use anyhow::{Result, Context};
+use serde::{Deserialize, Serialize};
+use crate::state::{BufferSizeRange, DeviceCapabilities};
+
+/// Formats d'échantillons gérés par la capture et la lecture
+pub const SUPPORTED_FORMATS: [SampleFormat; 6] = [
+    SampleFormat::F32,
+    SampleFormat::F64,
+    SampleFormat::I32,
+    SampleFormat::I16,
+    SampleFormat::U16,
+    SampleFormat::U8,
+];
+/// Fréquences proposées à l'utilisateur quand le périphérique les supporte
+const COMMON_SAMPLE_RATES: [u32; 11] = [
+    8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000, 176400, 192000,
+];
+/// Buffer visé en mode faible latence (~2.7ms à 48kHz)
+pub const LOW_LATENCY_BUFFER_FRAMES: u32 = 128;
+
+/// Configuration de stream choisie par l'utilisateur (`None` = valeur par défaut du périphérique)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamSettings {
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    /// Nom du format ("f32", "i16", "u8"...)
+    pub sample_format: Option<String>,
+    /// Taille de buffer en frames
+    pub buffer_size: Option<u32>,
+    /// Plus petit buffer supporté (à partir de `LOW_LATENCY_BUFFER_FRAMES`)
+    pub low_latency: bool,
+}
+
+/// Plage de configurations annoncée par un périphérique
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfigRange {
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: SampleFormat,
+    pub buffer_size: Option<(u32, u32)>,
+}
+
+impl From<&SupportedStreamConfigRange> for ConfigRange {
+    fn from(range: &SupportedStreamConfigRange) -> Self {
+        Self {
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            channels: range.channels(),
+            sample_format: range.sample_format(),
+            buffer_size: match range.buffer_size() {
+                SupportedBufferSize::Range { min, max } => Some((*min, *max)),
+                SupportedBufferSize::Unknown => None,
+            },
+        }
+    }
+}
+
+/// Configuration retenue pour ouvrir un stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChosenConfig {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: SampleFormat,
+    pub buffer_size: Option<u32>,
+}
+
+impl ChosenConfig {
+    pub fn stream_config(&self) -> StreamConfig {
+        StreamConfig {
+            channels: self.channels,
+            sample_rate: SampleRate(self.sample_rate),
+            buffer_size: self.buffer_size.map_or(BufferSize::Default, BufferSize::Fixed),
+        }
+    }
+}
+
+pub fn parse_sample_format(name: &str) -> Option<SampleFormat> {
+    SUPPORTED_FORMATS.into_iter().find(|format| format.to_string() == name.to_lowercase())
+}
+
+/// Résume les plages supportées pour l'interface
+pub fn capabilities(ranges: &[ConfigRange]) -> DeviceCapabilities {
+    let ranges: Vec<&ConfigRange> = ranges.iter()
+        .filter(|range| SUPPORTED_FORMATS.contains(&range.sample_format))
+        .collect();
+
+    let mut sample_rates: Vec<u32> = COMMON_SAMPLE_RATES.into_iter()
+        .filter(|rate| ranges.iter().any(|r| (r.min_sample_rate..=r.max_sample_rate).contains(rate)))
+        .collect();
+    if sample_rates.is_empty() {
+        // Fréquences exotiques : annoncer au moins les bornes
+        sample_rates = ranges.iter().flat_map(|r| [r.min_sample_rate, r.max_sample_rate]).collect();
+        sample_rates.sort_unstable();
+        sample_rates.dedup();
+    }
+
+    let mut channel_counts: Vec<u16> = ranges.iter().map(|r| r.channels).collect();
+    channel_counts.sort_unstable();
+    channel_counts.dedup();
+
+    let sample_formats = SUPPORTED_FORMATS.into_iter()
+        .filter(|format| ranges.iter().any(|r| r.sample_format == *format))
+        .map(|format| format.to_string())
+        .collect();
+
+    let buffer_size = ranges.iter()
+        .filter_map(|r| r.buffer_size)
+        .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+        .map(|(min, max)| BufferSizeRange { min, max });
+
+    DeviceCapabilities { sample_rates, channel_counts, sample_formats, buffer_size }
+}
+
+/// Choisit la plage la plus proche des réglages (fréquence, puis canaux, puis format)
+pub fn choose_config(ranges: &[ConfigRange], default: ChosenConfig, settings: &StreamSettings) -> Option<ChosenConfig> {
+    let sample_rate = settings.sample_rate.unwrap_or(default.sample_rate);
+    let channels = settings.channels.unwrap_or(default.channels);
+    let sample_format = settings.sample_format.as_deref()
+        .and_then(parse_sample_format)
+        .unwrap_or(default.sample_format);
+
+    let buffer_size = |range: Option<(u32, u32)>| {
+        let wanted = settings.buffer_size
+            .or(settings.low_latency.then_some(LOW_LATENCY_BUFFER_FRAMES))?;
+        match range {
+            Some((min, max)) => Some(wanted.clamp(min, max)),
+            // Taille inconnue : seul un choix explicite est tenté
+            None => settings.buffer_size,
+        }
+    };
+
+    if ranges.is_empty() {
+        return SUPPORTED_FORMATS.contains(&default.sample_format).then(|| ChosenConfig {
+            buffer_size: buffer_size(None),
+            ..default
+        });
+    }
+
+    let range = ranges.iter()
+        .filter(|range| SUPPORTED_FORMATS.contains(&range.sample_format))
+        .max_by_key(|range| {
+            let rate_ok = (range.min_sample_rate..=range.max_sample_rate).contains(&sample_rate);
+            let format_rank = SUPPORTED_FORMATS.len()
+                - SUPPORTED_FORMATS.iter().position(|f| *f == range.sample_format).unwrap_or(0);
+            (
+                rate_ok,
+                range.channels == channels,
+                range.sample_format == sample_format,
+                // À défaut, préférer les formats de meilleure qualité
+                format_rank,
+            )
+        })?;
+
+    Some(ChosenConfig {
+        sample_rate: sample_rate.clamp(range.min_sample_rate, range.max_sample_rate),
+        channels: range.channels,
+        sample_format: range.sample_format,
+        buffer_size: buffer_size(range.buffer_size),
+    })
+}
+
+/// Capacités d'entrée et de sortie d'un périphérique
+pub fn device_capabilities(device: &Device, input: bool) -> DeviceCapabilities {
+    capabilities(&supported_ranges(device, input))
+}
+
+fn supported_ranges(device: &Device, input: bool) -> Vec<ConfigRange> {
+    let ranges: Vec<SupportedStreamConfigRange> = if input {
+        device.supported_input_configs().map(|configs| configs.collect()).unwrap_or_default()
+    } else {
+        device.supported_output_configs().map(|configs| configs.collect()).unwrap_or_default()
+    };
+    ranges.iter().map(ConfigRange::from).collect()
+}
+
+/// Résout la configuration à utiliser pour un stream d'entrée ou de sortie
+pub fn resolve_config(device: &Device, input: bool, settings: &StreamSettings) -> Result<ChosenConfig> {
+    let default = if input {
+        device.default_input_config().context("Failed to get default input config")?
+    } else {
+        device.default_output_config().context("Failed to get default output config")?
+    };
+    let default = ChosenConfig {
+        sample_rate: default.sample_rate().0,
+        channels: default.channels(),
+        sample_format: default.sample_format(),
+        buffer_size: None,
+    };
+
+    choose_config(&supported_ranges(device, input), default, settings)
+        .context("No supported stream configuration")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(min: u32, max: u32, channels: u16, sample_format: SampleFormat) -> ConfigRange {
+        ConfigRange {
+            min_sample_rate: min,
+            max_sample_rate: max,
+            channels,
+            sample_format,
+            buffer_size: Some((64, 4096)),
+        }
+    }
+
+    const DEFAULT: ChosenConfig = ChosenConfig {
+        sample_rate: 48000,
+        channels: 2,
+        sample_format: SampleFormat::I16,
+        buffer_size: None,
+    };
+
+    #[test]
+    fn test_capabilities_summary() {
+        let ranges = [
+            range(44100, 48000, 2, SampleFormat::I16),
+            range(8000, 16000, 1, SampleFormat::U8),
+            range(48000, 48000, 2, SampleFormat::I64), // Non géré
+        ];
+        let caps = capabilities(&ranges);
+        assert_eq!(caps.sample_rates, vec![8000, 11025, 16000, 44100, 48000]);
+        assert_eq!(caps.channel_counts, vec![1, 2]);
+        assert_eq!(caps.sample_formats, vec!["i16".to_string(), "u8".to_string()]);
+        assert_eq!(caps.buffer_size, Some(BufferSizeRange { min: 64, max: 4096 }));
+    }
+
+    #[test]
+    fn test_choose_config_follows_settings() {
+        let ranges = [
+            range(44100, 48000, 2, SampleFormat::I16),
+            range(8000, 48000, 1, SampleFormat::I32),
+            range(44100, 96000, 2, SampleFormat::F64),
+        ];
+
+        // Sans réglage : la configuration par défaut
+        let chosen = choose_config(&ranges, DEFAULT, &StreamSettings::default()).unwrap();
+        assert_eq!(chosen, DEFAULT);
+
+        // Format et fréquence demandés, buffer faible latence
+        let settings = StreamSettings {
+            sample_rate: Some(96000),
+            sample_format: Some("f64".to_string()),
+            low_latency: true,
+            ..Default::default()
+        };
+        let chosen = choose_config(&ranges, DEFAULT, &settings).unwrap();
+        assert_eq!(chosen.sample_format, SampleFormat::F64);
+        assert_eq!(chosen.sample_rate, 96000);
+        assert_eq!(chosen.buffer_size, Some(LOW_LATENCY_BUFFER_FRAMES));
+
+        // Mono à 16kHz : seule la plage I32 convient ; buffer borné à la plage
+        let settings = StreamSettings {
+            sample_rate: Some(16000),
+            channels: Some(1),
+            buffer_size: Some(16),
+            ..Default::default()
+        };
+        let chosen = choose_config(&ranges, DEFAULT, &settings).unwrap();
+        assert_eq!((chosen.sample_rate, chosen.channels, chosen.sample_format), (16000, 1, SampleFormat::I32));
+        assert_eq!(chosen.buffer_size, Some(64));
+    }
+
+    #[test]
+    fn test_choose_config_rejects_unsupported_formats() {
+        let ranges = [range(48000, 48000, 2, SampleFormat::I64)];
+        assert!(choose_config(&ranges, DEFAULT, &StreamSettings::default()).is_none());
+        assert_eq!(parse_sample_format("F32"), Some(SampleFormat::F32));
+        assert_eq!(parse_sample_format("i64"), None);
+    }
+}
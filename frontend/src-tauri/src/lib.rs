@@ -2,10 +2,12 @@
 mod state;
 mod networking;
 mod audio;
+mod settings;
 
-use state::AppState;
-use networking::{BackendManager, WebSocketManager};
-use audio::{AudioDeviceManager, AudioCaptureManager, AudioPlaybackManager};
+use state::{AppState, VoiceState};
+use networking::{BackendClient, BackendManager, ClientMessage, NetworkLatency, ReconnectHooks, SessionHook, WebSocketClient, WebSocketManager, WebSocketMessage};
+use settings::{ClientSettings, PushToTalkSettings, ServerProfile, SettingsStore, SETTINGS_FILE};
+use audio::{AudioDeviceManager, AudioCaptureManager, AudioPlaybackManager, NoiseSuppressionLevel, InputGainSettings, StreamSettings};
 
 use tauri::{AppHandle, State, Manager, WindowEvent};
 use anyhow::Result;
@@ -21,6 +23,7 @@ pub struct TauriAppState {
     pub audio_device_manager: Arc<AudioDeviceManager>,
     pub audio_capture_manager: Arc<AudioCaptureManager>,
     pub audio_playback_manager: Arc<AudioPlaybackManager>,
+    pub settings: SettingsStore,
 }
 
 impl TauriAppState {
@@ -40,6 +43,9 @@ impl TauriAppState {
         let audio_capture_manager = Arc::new(AudioCaptureManager::new());
         let audio_playback_manager = Arc::new(AudioPlaybackManager::new());
         
+        // La capture retire du micro ce que joue la lecture (annulation d'écho)
+        audio_capture_manager.set_echo_reference(audio_playback_manager.echo_reference());
+        
         Ok(Self {
             app_state,
             backend_manager,
@@ -47,13 +53,38 @@ impl TauriAppState {
             audio_device_manager,
             audio_capture_manager,
             audio_playback_manager,
+            settings: SettingsStore::new(),
         })
     }
 
-    /// Configure l'AppHandle pour les événements WebSocket
+    /// Applique les réglages persistants aux gestionnaires audio
+    pub fn apply_settings(&self, settings: &ClientSettings) {
+        let audio = &settings.audio;
+        self.audio_device_manager.set_preferences(audio.input_device.clone(), audio.output_device.clone());
+        self.audio_capture_manager.set_noise_suppression(audio.noise_suppression);
+        self.audio_capture_manager.set_echo_cancellation(audio.echo_cancellation);
+        self.audio_capture_manager.set_gain_settings(audio.gain);
+        self.audio_capture_manager.set_stream_settings(audio.input_stream.clone());
+        self.audio_playback_manager.set_stream_settings(audio.output_stream.clone());
+        self.audio_playback_manager.set_user_volumes(settings.user_volumes.clone());
+        self.audio_capture_manager.set_push_to_talk(settings.push_to_talk.is_active());
+    }
+
+    /// Enregistre un changement de réglage (une erreur d'écriture n'annule pas le changement)
+    pub fn persist_settings<F>(&self, change: F)
+    where
+        F: FnOnce(&mut ClientSettings),
+    {
+        if let Err(e) = self.settings.update(change) {
+            eprintln!("❌ Failed to save settings: {}", e);
+        }
+    }
+
+    /// Configure l'AppHandle pour les événements émis par le backend Tauri
     pub fn configure_app_handle(&self, app_handle: tauri::AppHandle) {
-        // Nous n'avons plus besoin de cette logique car nous gérons le WebSocketManager différemment
         // Le WebSocketManager sera créé et configuré dans start_websocket_connection
+        // La capture en a besoin pour émettre les niveaux du micro (audio-level)
+        self.audio_capture_manager.set_app_handle(app_handle);
     }
     
     /// Démarre la connexion WebSocket avec l'AppHandle configuré
@@ -74,7 +105,8 @@ impl TauriAppState {
         };
         
         // Démarrer la connexion WebSocket
-        match ws_manager.start(app_handle.clone(), ws_url.to_string(), username).await {
+        let hooks = self.reconnect_hooks(&app_handle);
+        match ws_manager.start(app_handle.clone(), self.app_state.clone(), ws_url.to_string(), username, hooks).await {
             Ok(()) => {
                 println!("✅ WebSocket connection established successfully");
                 
@@ -91,6 +123,14 @@ impl TauriAppState {
         }
     }
 
+    /// Client typé de la connexion WebSocket en cours
+    pub async fn websocket_client(&self) -> Result<WebSocketClient, String> {
+        self.websocket_manager.lock().await
+            .as_ref()
+            .and_then(|ws_manager| ws_manager.client())
+            .ok_or_else(|| "WebSocket not connected".to_string())
+    }
+
     /// Arrête la connexion WebSocket
     pub async fn stop_websocket_connection(&self) {
         println!("🛑 Stopping WebSocket connection...");
@@ -104,6 +144,206 @@ impl TauriAppState {
         }
     }
 
+    /// Ferme proprement la session du serveur courant (audio, WebSocket, UDP, utilisateur)
+    pub async fn teardown_server_session(&self) {
+        if self.app_state.get_user().is_none() {
+            return;
+        }
+        println!("🧹 Tearing down current server session...");
+
+        let _ = self.audio_capture_manager.stop_recording();
+        let _ = self.audio_playback_manager.stop_playback();
+        self.stop_websocket_connection().await;
+
+        // Quitte le channel et supprime l'utilisateur côté serveur ; le client UDP
+        // disparaît avec l'ancien BackendManager
+        if let Err(e) = self.get_backend_manager().disconnect_user().await {
+            eprintln!("⚠️ Failed to disconnect from previous server: {}", e);
+            self.app_state.clear_user();
+            self.app_state.set_current_channel(None);
+        }
+        self.app_state.update_channels(Vec::new());
+    }
+
+    /// Rejoint un channel puis démarre la lecture et la capture audio
+    pub async fn enter_channel(&self, channel_id: Uuid) -> Result<(), String> {
+        let ws_client = self.websocket_client().await?;
+        self.get_backend_manager().join_channel(&ws_client, channel_id).await
+            .map_err(|e| e.to_string())?;
+        self.persist_settings(|s| s.set_last_channel(Some(channel_id)));
+
+        // Transport de secours pour l'audio si l'UDP s'avère bloqué
+        if let Some(udp_client) = self.get_backend_manager().get_udp_client() {
+            udp_client.control().set_tunnel(ws_client.clone());
+        }
+
+        // Le micro et le son coupés restent coupés d'un channel à l'autre
+        let voice_state = self.app_state.get_voice_state();
+        if voice_state != VoiceState::default() {
+            if let Err(e) = ws_client.request(ClientMessage::SetVoiceState {
+                muted: voice_state.muted,
+                deafened: voice_state.deafened,
+            }).await {
+                eprintln!("⚠️ Failed to restore voice state: {}", e);
+            }
+        }
+    
+        // Démarrer automatiquement la lecture audio après avoir rejoint le channel ;
+        // en sourdine elle tourne quand même, car elle lit les réponses du serveur en UDP
+        self.audio_playback_manager.set_deafened(voice_state.deafened);
+        self.start_channel_playback().await?;
+        // Démarrer automatiquement la capture audio après avoir rejoint le channel
+        if voice_state.capture_enabled() {
+            self.start_channel_capture();
+        }
+        
+        Ok(())
+    }
+
+    /// Démarre la lecture de l'audio du channel
+    async fn start_channel_playback(&self) -> Result<(), String> {
+        if let Some(user) = self.app_state.get_user() {
+            self.audio_playback_manager.set_user(user.id);
+        
+            // Configurer le device de sortie par défaut si pas encore fait
+            if self.audio_playback_manager.get_device_name().is_none() {
+                let _ = self.audio_playback_manager.set_device(self.audio_device_manager.preferred_output());
+            }
+        
+            // Démarrer la lecture audio pour recevoir l'audio du channel
+            // Utiliser le socket partagé du client UDP si disponible
+            let udp_client_option = self.backend_manager.read().unwrap().get_udp_client();
+            if let Some(udp_client) = udp_client_option {
+                let shared_socket = udp_client.get_shared_socket();
+                let server_addr = udp_client.get_server_addr(); // Utiliser la même adresse que le client UDP
+                if let Err(e) = self.audio_playback_manager.start_playback_with_shared_socket(server_addr, shared_socket, udp_client.control()).await {
+                    eprintln!("⚠️ Failed to start audio playback with shared socket: {}", e);
+                    // Fallback vers la méthode normale
+                    if let Err(e) = self.audio_playback_manager.start_playback(server_addr).await {
+                        eprintln!("⚠️ Failed to start audio playback (fallback): {}", e);
+                    }
+                }
+            } else {
+                // Pas de client UDP, utiliser l'adresse par défaut locale
+                let server_addr: std::net::SocketAddr = "127.0.0.1:8082".parse()
+                    .map_err(|e| format!("Invalid server address: {}", e))?;
+                if let Err(e) = self.audio_playback_manager.start_playback(server_addr).await {
+                    eprintln!("⚠️ Failed to start audio playback: {}", e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Démarre la capture du micro vers le channel actuel
+    fn start_channel_capture(&self) {
+        if let (Some(user), Some(channel_id)) = (self.app_state.get_user(), self.app_state.get_current_channel()) {
+            self.audio_capture_manager.set_user_and_channel(user.id, channel_id);
+        
+            // Configurer le device d'entrée par défaut si pas encore fait
+            if let Err(e) = self.audio_capture_manager.set_device(self.audio_device_manager.preferred_input()) {
+                eprintln!("⚠️ Failed to set audio input device: {}", e);
+            }
+        
+            // Démarrer la capture audio pour envoyer notre voix
+            // Ne pas faire échouer le join pour autant
+            if let Err(e) = self.audio_capture_manager.start_recording() {
+                eprintln!("⚠️ Failed to start audio capture: {}", e);
+            }
+        }
+    }
+
+    /// Coupe ou rétablit le micro et le son localement, puis prévient le serveur
+    pub async fn set_voice_state(&self, voice_state: VoiceState) -> Result<VoiceState, String> {
+        let previous = self.app_state.get_voice_state();
+        self.app_state.set_voice_state(voice_state);
+
+        if self.app_state.get_current_channel().is_some() {
+            if voice_state.capture_enabled() != previous.capture_enabled() {
+                if voice_state.capture_enabled() {
+                    self.start_channel_capture();
+                } else if let Err(e) = self.audio_capture_manager.stop_recording() {
+                    eprintln!("⚠️ Failed to stop audio capture: {}", e);
+                }
+            }
+        }
+        // La lecture continue en sourdine : seul l'audio reçu est jeté
+        self.audio_playback_manager.set_deafened(voice_state.deafened);
+
+        // Hors connexion, l'état sera envoyé au prochain channel rejoint
+        if let Ok(ws_client) = self.websocket_client().await {
+            ws_client.request(ClientMessage::SetVoiceState {
+                muted: voice_state.muted,
+                deafened: voice_state.deafened,
+            }).await.map_err(|e| e.to_string())?;
+        }
+
+        println!("🎙️ Voice state: muted={}, deafened={}", voice_state.muted, voice_state.deafened);
+        Ok(voice_state)
+    }
+
+    /// Hooks de reprise de session utilisés par le WebSocket après une coupure
+    fn reconnect_hooks(&self, app_handle: &tauri::AppHandle) -> ReconnectHooks {
+        let app = app_handle.clone();
+        let reauthenticate: SessionHook<String> = Arc::new(move || {
+            let app = app.clone();
+            Box::pin(async move {
+                let state = app.state::<TauriAppState>();
+                let user = state.get_backend_manager().resume_user().await?;
+                Ok(user.username)
+            })
+        });
+
+        let app = app_handle.clone();
+        let restore: SessionHook<()> = Arc::new(move || {
+            let app = app.clone();
+            Box::pin(async move {
+                app.state::<TauriAppState>().restore_channel().await
+                    .map_err(|e| anyhow::anyhow!(e))
+            })
+        });
+
+        ReconnectHooks { reauthenticate, restore }
+    }
+
+    /// Après une reconnexion : rejoint le channel courant si le serveur nous en a retiré,
+    /// sinon se contente de ré-enregistrer l'adresse UDP
+    async fn restore_channel(&self) -> Result<(), String> {
+        let backend_manager = self.get_backend_manager();
+        let channels = backend_manager.get_channels().await
+            .map_err(|e| e.to_string())?;
+        
+        let (Some(user), Some(channel_id)) = (self.app_state.get_user(), self.app_state.get_current_channel()) else {
+            return Ok(());
+        };
+        
+        let still_member = channels.iter()
+            .find(|c| c.id == channel_id)
+            .map(|c| c.users.iter().any(|u| u.id == user.id));
+        
+        match still_member {
+            Some(true) => {
+                println!("💓 Still in channel {}, re-registering UDP address", channel_id);
+                backend_manager.send_heartbeat().await
+                    .map_err(|e| e.to_string())
+            }
+            Some(false) => {
+                println!("🔁 Rejoining channel {} after reconnection", channel_id);
+                // L'utilisateur a pu être recréé : relancer l'audio avec le nouvel identifiant
+                let _ = self.audio_capture_manager.stop_recording();
+                let _ = self.audio_playback_manager.stop_playback();
+                self.enter_channel(channel_id).await
+            }
+            None => {
+                println!("⚠️ Channel {} no longer exists", channel_id);
+                let _ = self.audio_capture_manager.stop_recording();
+                let _ = self.audio_playback_manager.stop_playback();
+                self.app_state.set_current_channel(None);
+                Ok(())
+            }
+        }
+    }
+
     /// Met à jour le BackendManager avec une nouvelle URL
     pub fn update_backend_manager(&self, url: &str) {
         let new_manager = Arc::new(BackendManager::new(url, self.app_state.clone()));
@@ -126,7 +366,6 @@ impl TauriAppState {
         // Configurer l'AudioCaptureManager avec le client UDP
         if let Some(udp_client) = backend_manager.get_udp_client() {
             self.audio_capture_manager.set_udp_client(udp_client).await;
-            // println!("Audio UDP configured successfully");
         }
         
         Ok(())
@@ -141,6 +380,13 @@ async fn initialize_app(app: tauri::AppHandle, state: State<'_, TauriAppState>)
     
     // Configurer l'AppHandle pour les événements WebSocket
     state.configure_app_handle(app.clone());
+
+    // Surveiller les périphériques audio (bascule automatique au débranchement)
+    state.audio_device_manager.start_monitoring(
+        app.clone(),
+        state.audio_capture_manager.clone(),
+        state.audio_playback_manager.clone(),
+    );
     
     // Stocker l'AppHandle pour une utilisation ultérieure
     // Note: On peut utiliser app.clone() dans connect_to_server quand on en a besoin
@@ -170,6 +416,9 @@ async fn stop_websocket(state: State<'_, TauriAppState>) -> Result<(), String> {
 
 #[tauri::command]
 async fn connect_to_server(server_url: String, username: String, state: State<'_, TauriAppState>) -> Result<serde_json::Value, String> {
+    // Une seule session à la fois : fermer celle du serveur précédent
+    state.teardown_server_session().await;
+    
     // Mettre à jour le BackendManager avec la nouvelle URL
     state.update_backend_manager(&server_url);
     let backend_manager = state.get_backend_manager();
@@ -180,6 +429,8 @@ async fn connect_to_server(server_url: String, username: String, state: State<'_
             // Connecter l'utilisateur
             match backend_manager.connect_user(&username).await {
                 Ok(_) => {
+                    state.persist_settings(|s| s.remember_server(&server_url, &username));
+                    
                     // Configurer l'audio UDP
                     let parsed_url = server_url.replace("http://", "").replace("https://", "");
                     let backend_host = parsed_url.split(':').next().unwrap_or("localhost");
@@ -266,89 +517,32 @@ async fn join_channel(channel_id: String, state: State<'_, TauriAppState>) -> Re
     let uuid = Uuid::parse_str(&channel_id)
         .map_err(|e| format!("Invalid channel ID: {}", e))?;
     
-    // Rejoindre le channel
-    state.get_backend_manager().join_channel(uuid).await
-        .map_err(|e| e.to_string())?;
-    
-    // println!("🎵 Successfully joined channel {}, starting audio playback and capture...", channel_id);
-    
-    // Démarrer automatiquement la lecture audio après avoir rejoint le channel
-    if let Some(user) = state.app_state.get_user() {
-        state.audio_playback_manager.set_user(user.id);
-        
-        // Configurer le device de sortie par défaut si pas encore fait
-        if state.audio_playback_manager.get_device_name().is_none() {
-            let _ = state.audio_playback_manager.set_device("default".to_string());
-        }
-        
-        // Démarrer la lecture audio pour recevoir l'audio du channel
-        // Utiliser le socket partagé du client UDP si disponible
-        let udp_client_option = state.backend_manager.read().unwrap().get_udp_client();
-        if let Some(udp_client) = udp_client_option {
-            let shared_socket = udp_client.get_shared_socket();
-            let server_addr = udp_client.get_server_addr(); // Utiliser la même adresse que le client UDP
-            if let Err(e) = state.audio_playback_manager.start_playback_with_shared_socket(server_addr, shared_socket).await {
-                // println!("⚠️ Warning: Failed to start audio playback with shared socket: {}", e);
-                // Fallback vers la méthode normale
-                if let Err(e2) = state.audio_playback_manager.start_playback(server_addr).await {
-                    // println!("⚠️ Warning: Failed to start audio playback (fallback): {}", e2);
-                }
-            } else {
-                // println!("✅ Audio playback started successfully with shared socket");
-            }
-        } else {
-            // Pas de client UDP, utiliser l'adresse par défaut locale
-            let server_addr: std::net::SocketAddr = "127.0.0.1:8082".parse()
-                .map_err(|e| format!("Invalid server address: {}", e))?;
-            if let Err(e) = state.audio_playback_manager.start_playback(server_addr).await {
-                // println!("⚠️ Warning: Failed to start audio playback: {}", e);
-            } else {
-                // println!("✅ Audio playback started successfully");
-            }
-        }
-        
-        // Démarrer automatiquement la capture audio après avoir rejoint le channel
-        if let Some(channel_id) = state.app_state.get_current_channel() {
-            state.audio_capture_manager.set_user_and_channel(user.id, channel_id);
-            
-            // Configurer le device d'entrée par défaut si pas encore fait
-            if let Err(e) = state.audio_capture_manager.set_device("default".to_string()) {
-                // println!("⚠️ Warning: Failed to set audio input device: {}", e);
-            }
-            
-            // Démarrer la capture audio pour envoyer notre voix
-            if let Err(e) = state.audio_capture_manager.start_recording() {
-                // println!("⚠️ Warning: Failed to start audio capture: {}", e);
-                // Ne pas faire échouer le join pour autant
-            } else {
-                // println!("✅ Audio capture started successfully");
-            }
-        }
-    }
-    
-    Ok(())
+    state.enter_channel(uuid).await
+}
+
+#[tauri::command]
+async fn websocket_request(message: ClientMessage, state: State<'_, TauriAppState>) -> Result<WebSocketMessage, String> {
+    state.websocket_client().await?
+        .request(message).await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn leave_current_channel(state: State<'_, TauriAppState>) -> Result<(), String> {
     // Quitter le channel
-    state.get_backend_manager().leave_current_channel().await
+    let ws_client = state.websocket_client().await?;
+    state.get_backend_manager().leave_current_channel(&ws_client).await
         .map_err(|e| e.to_string())?;
-    
-    // println!("🎵 Left channel, stopping audio playback and capture...");
+    state.persist_settings(|s| s.set_last_channel(None));
     
     // Arrêter la capture audio quand on quitte le channel
     if let Err(e) = state.audio_capture_manager.stop_recording() {
-        // println!("⚠️ Warning: Failed to stop audio capture: {}", e);
-    } else {
-        // println!("✅ Audio capture stopped successfully");
+        eprintln!("⚠️ Failed to stop audio capture: {}", e);
     }
     
     // Arrêter la lecture audio quand on quitte le channel
     if let Err(e) = state.audio_playback_manager.stop_playback() {
-        // println!("⚠️ Warning: Failed to stop audio playback: {}", e);
-    } else {
-        // println!("✅ Audio playback stopped successfully");
+        eprintln!("⚠️ Failed to stop audio playback: {}", e);
     }
     
     Ok(())
@@ -363,13 +557,23 @@ async fn scan_audio_devices(state: State<'_, TauriAppState>) -> Result<state::Au
 #[tauri::command]
 async fn select_input_device(device_id: String, state: State<'_, TauriAppState>) -> Result<(), String> {
     state.audio_device_manager.select_input_device(&device_id)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    let preferred = state.audio_device_manager.preferred_input();
+    state.persist_settings(|s| s.audio.input_device = Some(preferred));
+    // Bascule à chaud si la capture est en cours
+    state.audio_capture_manager.switch_device(device_id);
+    Ok(())
 }
 
 #[tauri::command]
 async fn select_output_device(device_id: String, state: State<'_, TauriAppState>) -> Result<(), String> {
-    state.audio_playback_manager.set_device(device_id)
-        .map_err(|e| e.to_string())
+    state.audio_device_manager.select_output_device(&device_id)
+        .map_err(|e| e.to_string())?;
+    let preferred = state.audio_device_manager.preferred_output();
+    state.persist_settings(|s| s.audio.output_device = Some(preferred));
+    // Bascule à chaud si la lecture est en cours
+    state.audio_playback_manager.switch_device(device_id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -381,15 +585,14 @@ async fn start_audio_capture(state: State<'_, TauriAppState>) -> Result<(), Stri
     ) {
         state.audio_capture_manager.set_user_and_channel(user.id, channel_id);
         
-        // Utiliser le périphérique par défaut ou celui configuré
-        let device_name = "default".to_string();
+        // Utiliser le périphérique choisi par l'utilisateur, ou celui par défaut
+        let device_name = state.audio_device_manager.preferred_input();
         state.audio_capture_manager.set_device(device_name)
             .map_err(|e| format!("Failed to set audio device: {}", e))?;
             
         state.audio_capture_manager.start_recording()
             .map_err(|e| format!("Failed to start audio recording: {}", e))?;
-            
-        // println!("Audio capture started for user {} in channel {}", user.username, channel_id);
+
         Ok(())
     } else {
         Err("No user connected or no channel joined".to_string())
@@ -400,11 +603,184 @@ async fn start_audio_capture(state: State<'_, TauriAppState>) -> Result<(), Stri
 async fn stop_audio_capture(state: State<'_, TauriAppState>) -> Result<(), String> {
     state.audio_capture_manager.stop_recording()
         .map_err(|e| format!("Failed to stop audio recording: {}", e))?;
-        
-    // println!("Audio capture stopped");
     Ok(())
 }
 
+#[tauri::command]
+async fn set_noise_suppression(level: NoiseSuppressionLevel, state: State<'_, TauriAppState>) -> Result<(), String> {
+    state.audio_capture_manager.set_noise_suppression(level);
+    state.persist_settings(|s| s.audio.noise_suppression = level);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_noise_suppression(state: State<'_, TauriAppState>) -> Result<NoiseSuppressionLevel, String> {
+    Ok(state.audio_capture_manager.get_noise_suppression())
+}
+
+#[tauri::command]
+async fn set_echo_cancellation(enabled: bool, state: State<'_, TauriAppState>) -> Result<(), String> {
+    state.audio_capture_manager.set_echo_cancellation(enabled);
+    state.persist_settings(|s| s.audio.echo_cancellation = enabled);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_echo_cancellation(state: State<'_, TauriAppState>) -> Result<bool, String> {
+    Ok(state.audio_capture_manager.is_echo_cancellation_enabled())
+}
+
+#[tauri::command]
+async fn set_input_gain_settings(settings: InputGainSettings, state: State<'_, TauriAppState>) -> Result<(), String> {
+    state.audio_capture_manager.set_gain_settings(settings);
+    let settings = state.audio_capture_manager.get_gain_settings();
+    state.persist_settings(|s| s.audio.gain = settings);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_input_gain_settings(state: State<'_, TauriAppState>) -> Result<InputGainSettings, String> {
+    Ok(state.audio_capture_manager.get_gain_settings())
+}
+
+#[tauri::command]
+async fn set_input_stream_settings(settings: StreamSettings, state: State<'_, TauriAppState>) -> Result<(), String> {
+    state.audio_capture_manager.set_stream_settings(settings.clone());
+    state.persist_settings(|s| s.audio.input_stream = settings);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_input_stream_settings(state: State<'_, TauriAppState>) -> Result<StreamSettings, String> {
+    Ok(state.audio_capture_manager.get_stream_settings())
+}
+
+#[tauri::command]
+async fn set_output_stream_settings(settings: StreamSettings, state: State<'_, TauriAppState>) -> Result<(), String> {
+    state.audio_playback_manager.set_stream_settings(settings.clone());
+    state.persist_settings(|s| s.audio.output_stream = settings);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_output_stream_settings(state: State<'_, TauriAppState>) -> Result<StreamSettings, String> {
+    Ok(state.audio_playback_manager.get_stream_settings())
+}
+
+#[tauri::command]
+async fn set_user_volume(user_id: String, volume: f32, state: State<'_, TauriAppState>) -> Result<f32, String> {
+    let id = Uuid::parse_str(&user_id)
+        .map_err(|e| format!("Invalid user ID: {}", e))?;
+    state.persist_settings(|s| s.set_user_volume(id, volume));
+    let volume = state.settings.get().user_volumes.get(&id).copied().unwrap_or(1.0);
+    state.audio_playback_manager.set_user_volume(id, volume);
+    Ok(volume)
+}
+
+#[tauri::command]
+async fn set_push_to_talk(settings: PushToTalkSettings, state: State<'_, TauriAppState>) -> Result<PushToTalkSettings, String> {
+    state.persist_settings(|s| s.push_to_talk = settings);
+    let settings = state.settings.get().push_to_talk;
+    state.audio_capture_manager.set_push_to_talk(settings.is_active());
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn set_push_to_talk_pressed(pressed: bool, state: State<'_, TauriAppState>) -> Result<(), String> {
+    state.audio_capture_manager.set_talk_key_pressed(pressed);
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_servers(state: State<'_, TauriAppState>) -> Result<Vec<ServerProfile>, String> {
+    Ok(state.settings.get().servers)
+}
+
+#[tauri::command]
+async fn save_server(server: ServerProfile, state: State<'_, TauriAppState>) -> Result<ServerProfile, String> {
+    let mut saved = None;
+    state.settings.update(|s| saved = Some(s.save_server(server)))
+        .map_err(|e| e.to_string())?;
+    saved.ok_or_else(|| "Failed to save server".to_string())
+}
+
+#[tauri::command]
+async fn remove_server(server_id: String, state: State<'_, TauriAppState>) -> Result<(), String> {
+    let id = Uuid::parse_str(&server_id)
+        .map_err(|e| format!("Invalid server ID: {}", e))?;
+    state.settings.update(|s| s.remove_server(id))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Sonde /health de chaque serveur enregistré, en parallèle
+#[tauri::command]
+async fn probe_servers(state: State<'_, TauriAppState>) -> Result<serde_json::Value, String> {
+    let servers = state.settings.get().servers;
+    let probes = servers.iter().map(|server| async move {
+        let status = BackendClient::new(&server.url).probe().await;
+        serde_json::json!({ "serverId": server.id, "status": status })
+    });
+    Ok(serde_json::Value::Array(futures_util::future::join_all(probes).await))
+}
+
+/// Bascule vers un serveur enregistré : ferme la session courante puis se connecte
+#[tauri::command]
+async fn switch_server(server_id: String, state: State<'_, TauriAppState>) -> Result<serde_json::Value, String> {
+    let id = Uuid::parse_str(&server_id)
+        .map_err(|e| format!("Invalid server ID: {}", e))?;
+    let settings = state.settings.get();
+    let server = settings.server(id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown server: {}", server_id))?;
+    let username = server.username.clone()
+        .or(settings.username)
+        .ok_or_else(|| "No username saved for this server".to_string())?;
+
+    println!("🔀 Switching to server {} ({})", server.name, server.url);
+    let mut result = connect_to_server(server.url.clone(), username, state.clone()).await?;
+    
+    // Le frontend rejoint le dernier channel une fois le WebSocket démarré
+    result["server"] = serde_json::json!(server);
+    result["lastChannel"] = serde_json::json!(server.last_channel);
+    Ok(result)
+}
+
+#[tauri::command]
+async fn get_settings(state: State<'_, TauriAppState>) -> Result<ClientSettings, String> {
+    Ok(state.settings.get())
+}
+
+#[tauri::command]
+async fn update_settings(settings: ClientSettings, state: State<'_, TauriAppState>) -> Result<ClientSettings, String> {
+    let settings = state.settings.replace(settings)
+        .map_err(|e| e.to_string())?;
+    state.apply_settings(&settings);
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn reset_settings(state: State<'_, TauriAppState>) -> Result<ClientSettings, String> {
+    let settings = state.settings.replace(ClientSettings::default())
+        .map_err(|e| e.to_string())?;
+    state.apply_settings(&settings);
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn export_settings(state: State<'_, TauriAppState>) -> Result<String, String> {
+    state.settings.export_json()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn import_settings(json: String, state: State<'_, TauriAppState>) -> Result<ClientSettings, String> {
+    let settings = state.settings.import_json(&json)
+        .map_err(|e| e.to_string())?;
+    state.apply_settings(&settings);
+    Ok(settings)
+}
+
 #[tauri::command]
 async fn start_audio_playback(state: State<'_, TauriAppState>) -> Result<(), String> {
     // Configurer l'utilisateur actuel
@@ -424,7 +800,7 @@ async fn start_audio_playback(state: State<'_, TauriAppState>) -> Result<(), Str
         // Utiliser le socket partagé du client UDP si disponible
         if let Some(udp_client) = udp_client_option {
             let shared_socket = udp_client.get_shared_socket();
-            state.audio_playback_manager.start_playback_with_shared_socket(server_addr, shared_socket).await
+            state.audio_playback_manager.start_playback_with_shared_socket(server_addr, shared_socket, udp_client.control()).await
                 .map_err(|e| e.to_string())
         } else {
             // Pas de client UDP, utiliser la méthode normale
@@ -459,6 +835,28 @@ async fn get_current_user(state: State<'_, TauriAppState>) -> Result<Option<stat
     Ok(state.app_state.get_user())
 }
 
+#[tauri::command]
+async fn get_network_latency(state: State<'_, TauriAppState>) -> Result<Option<NetworkLatency>, String> {
+    Ok(state.get_backend_manager().get_udp_client().and_then(|client| client.control().latency()))
+}
+
+#[tauri::command]
+async fn set_self_mute(muted: bool, state: State<'_, TauriAppState>) -> Result<VoiceState, String> {
+    let voice_state = state.app_state.get_voice_state();
+    state.set_voice_state(VoiceState { muted, ..voice_state }).await
+}
+
+#[tauri::command]
+async fn set_self_deafen(deafened: bool, state: State<'_, TauriAppState>) -> Result<VoiceState, String> {
+    let voice_state = state.app_state.get_voice_state();
+    state.set_voice_state(VoiceState { deafened, ..voice_state }).await
+}
+
+#[tauri::command]
+async fn get_voice_state(state: State<'_, TauriAppState>) -> Result<VoiceState, String> {
+    Ok(state.app_state.get_voice_state())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialiser l'état de l'application
@@ -468,6 +866,18 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(tauri_state)
+        .setup(|app| {
+            // Charger le profil persistant depuis le répertoire de configuration
+            let state = app.state::<TauriAppState>();
+            match app.path().app_config_dir() {
+                Ok(dir) => {
+                    let settings = state.settings.load(dir.join(SETTINGS_FILE));
+                    state.apply_settings(&settings);
+                }
+                Err(e) => eprintln!("❌ No config directory, settings will not be saved: {}", e),
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             initialize_app,
             initialize_backend,
@@ -479,16 +889,44 @@ pub fn run() {
             get_channels,
             join_channel,
             leave_current_channel,
+            websocket_request,
             scan_audio_devices,
             select_input_device,
             select_output_device,
             start_audio_capture,
             stop_audio_capture,
+            set_noise_suppression,
+            get_noise_suppression,
+            set_echo_cancellation,
+            get_echo_cancellation,
+            set_input_gain_settings,
+            get_input_gain_settings,
+            set_input_stream_settings,
+            get_input_stream_settings,
+            set_output_stream_settings,
+            get_output_stream_settings,
+            set_user_volume,
+            set_push_to_talk,
+            set_push_to_talk_pressed,
+            list_servers,
+            save_server,
+            remove_server,
+            probe_servers,
+            switch_server,
+            get_settings,
+            update_settings,
+            reset_settings,
+            export_settings,
+            import_settings,
             start_audio_playback,
             stop_audio_playback,
             play_test_sound,
             get_connection_state,
-            get_current_user
+            get_current_user,
+            get_network_latency,
+            set_self_mute,
+            set_self_deafen,
+            get_voice_state
         ])
         .on_window_event(|window, event| {
             match event {
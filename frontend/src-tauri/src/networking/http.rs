@@ -3,7 +3,24 @@ use anyhow::{Result, Context};
 use reqwest::Client;
 use serde_json::Value;
 use uuid::Uuid;
+use super::protocol::ClientMessage;
 use super::udp::AudioUdpClient;
+use super::websocket::WebSocketClient;
+
+/// Délai maximal d'une sonde /health
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+/// Intervalle des heartbeats UDP (le serveur oublie les adresses silencieuses)
+const UDP_HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+/// Heartbeats consécutifs sans réponse avant de faire passer l'audio par le WebSocket
+const UDP_FALLBACK_AFTER: u32 = 2;
+
+/// Résultat d'une sonde /health sur un serveur enregistré
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServerStatus {
+    pub online: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
 
 /// Client HTTP pour communiquer avec le backend
 pub struct BackendClient {
@@ -29,6 +46,30 @@ impl BackendClient {
         }
     }
 
+    /// Sonde /health avec un délai court et mesure la latence
+    pub async fn probe(&self) -> ServerStatus {
+        let url = format!("{}/health", self.base_url);
+        let started = std::time::Instant::now();
+
+        match self.client.get(&url).timeout(PROBE_TIMEOUT).send().await {
+            Ok(response) if response.status().is_success() => ServerStatus {
+                online: true,
+                latency_ms: Some(started.elapsed().as_millis() as u64),
+                error: None,
+            },
+            Ok(response) => ServerStatus {
+                online: false,
+                latency_ms: None,
+                error: Some(format!("HTTP {}", response.status())),
+            },
+            Err(e) => ServerStatus {
+                online: false,
+                latency_ms: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
     /// Connecte un utilisateur avec son username
     pub async fn connect_user(&self, username: &str) -> Result<UserState> {
         let url = format!("{}/api/users", self.base_url);
@@ -62,6 +103,44 @@ impl BackendClient {
         })
     }
 
+    /// Vérifie que le serveur connaît toujours l'utilisateur
+    pub async fn user_exists(&self, user_id: Uuid) -> Result<bool> {
+        let url = format!("{}/api/users/{}", self.base_url, user_id);
+        
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to check user")?;
+        
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => anyhow::bail!("Failed to check user: {}", status),
+        }
+    }
+
+    /// Taille maximale d'un packet audio acceptée par le serveur
+    pub async fn get_max_packet_size(&self) -> Result<usize> {
+        let url = format!("{}/api/audio/config", self.base_url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch audio config")?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to get audio config: {}", response.status());
+        }
+
+        let config: Value = response.json().await
+            .context("Failed to parse audio config")?;
+        config["max_packet_size"].as_u64()
+            .map(|size| size as usize)
+            .context("Missing max_packet_size in audio config")
+    }
+
     /// Déconnecte un utilisateur du serveur
     pub async fn disconnect_user(&self, user_id: Uuid) -> Result<()> {
         let url = format!("{}/api/users/{}/disconnect", self.base_url, user_id);
@@ -114,62 +193,6 @@ impl BackendClient {
         Ok(channels)
     }
 
-    /// Rejoint un channel
-    pub async fn join_channel(&self, user_id: Uuid, channel_id: Uuid) -> Result<()> {
-        let url = format!("{}/api/channels/{}/join", self.base_url, channel_id);
-        
-        println!("🔗 Joining channel: {} for user: {}", channel_id, user_id);
-        println!("📡 POST {}", url);
-        
-        let payload = serde_json::json!({
-            "user_id": user_id.to_string()
-        });
-        
-        println!("📦 Request payload: {}", payload);
-        
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to join channel")?;
-        
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to join channel: {}", response.status());
-        }
-        
-        Ok(())
-    }
-
-    /// Quitte un channel
-    pub async fn leave_channel(&self, user_id: Uuid, channel_id: Uuid) -> Result<()> {
-        let url = format!("{}/api/channels/{}/leave", self.base_url, channel_id);
-        
-        println!("🚪 Leaving channel: {} for user: {}", channel_id, user_id);
-        println!("📡 POST {}", url);
-        
-        let payload = serde_json::json!({
-            "user_id": user_id.to_string()
-        });
-        
-        println!("📦 Request payload: {}", payload);
-        
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .context("Failed to leave channel")?;
-        
-        println!("📡 Leave channel response status: {}", response.status());
-        
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to leave channel: {}", response.status());
-        }
-        
-        Ok(())
-    }
-
     /// Parse les données d'un channel depuis JSON
     fn parse_channel_info(&self, data: &Value) -> Result<ChannelInfo> {
         // Parser les utilisateurs du channel
@@ -216,13 +239,15 @@ impl BackendClient {
 }
 
 use std::sync::Arc;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
 /// Gestionnaire de la communication avec le backend
 pub struct BackendManager {
     client: BackendClient,
     app_state: AppState,
     udp_client: Arc<RwLock<Option<AudioUdpClient>>>,
+    /// Heartbeats UDP périodiques, arrêtés avec le gestionnaire
+    heartbeat_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
 }
 
 impl BackendManager {
@@ -231,6 +256,7 @@ impl BackendManager {
             client: BackendClient::new(backend_url),
             app_state,
             udp_client: Arc::new(RwLock::new(None)),
+            heartbeat_task: Mutex::new(None),
         }
     }
 
@@ -264,13 +290,64 @@ impl BackendManager {
             
         let udp_client = AudioUdpClient::new(server_addr).await
             .context("Failed to create UDP client")?;
+
+        match self.client.get_max_packet_size().await {
+            Ok(size) => udp_client.control().set_max_packet_size(size),
+            Err(e) => println!("⚠️ Failed to fetch server packet size, keeping default: {}", e),
+        }
             
         *self.udp_client.write() = Some(udp_client);
         
         println!("UDP client configured for {}:{}", backend_host, udp_port);
+        self.start_udp_heartbeat();
         Ok(())
     }
 
+    /// Envoie un heartbeat UDP à intervalle régulier tant qu'on est dans un channel,
+    /// pour garder l'adresse enregistrée côté serveur (et le mapping NAT ouvert).
+    /// Si le serveur ne répond jamais, l'UDP est bloqué : l'audio passe par le WebSocket,
+    /// et revient à l'UDP dès que le serveur y répond de nouveau.
+    fn start_udp_heartbeat(&self) {
+        let app_state = self.app_state.clone();
+        let udp_client = self.udp_client.clone();
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(UDP_HEARTBEAT_INTERVAL);
+            let mut last_sent: Option<std::time::Instant> = None;
+            let mut unanswered = 0;
+            loop {
+                interval.tick().await;
+                let control = udp_client.read().as_ref().map(|client| client.control());
+                let Some(control) = control.filter(|_| app_state.get_current_channel().is_some()) else {
+                    last_sent = None;
+                    unanswered = 0;
+                    continue;
+                };
+                if let Err(e) = Self::receiver_report(&app_state, &udp_client).await {
+                    println!("⚠️ Failed to send receiver report: {}", e);
+                }
+
+                if let Some(sent_at) = last_sent {
+                    let answered = control.last_sync_reply().is_some_and(|reply| reply >= sent_at);
+                    unanswered = if answered { 0 } else { unanswered + 1 };
+                    if answered && control.deactivate_tunnel() {
+                        println!("📡 UDP heartbeat answered again, audio back on UDP");
+                    } else if unanswered >= UDP_FALLBACK_AFTER && control.activate_tunnel() {
+                        println!("📡 No UDP heartbeat reply from server, tunnelling audio over WebSocket");
+                    }
+                }
+
+                match Self::heartbeat(&app_state, &udp_client).await {
+                    Ok(()) => last_sent = Some(std::time::Instant::now()),
+                    Err(e) => println!("⚠️ Periodic UDP heartbeat failed: {}", e),
+                }
+            }
+        });
+
+        if let Some(previous) = self.heartbeat_task.lock().replace(task) {
+            previous.abort();
+        }
+    }
+
     /// Obtient le client UDP pour l'audio
     pub fn get_udp_client(&self) -> Option<AudioUdpClient> {
         self.udp_client.read().clone()
@@ -285,18 +362,61 @@ impl BackendManager {
         Ok(())
     }
 
+    /// Reprend la session après une coupure : l'utilisateur est recréé sous le même
+    /// nom si le serveur l'a oublié (le channel courant est conservé pour le rejoindre)
+    pub async fn resume_user(&self) -> Result<UserState> {
+        let user = self.app_state.get_user()
+            .context("No user to resume")?;
+        
+        if self.client.user_exists(user.id).await? {
+            return Ok(user);
+        }
+        
+        println!("🔄 Server forgot user {}, recreating it", user.username);
+        let user = self.client.connect_user(&user.username).await
+            .context("Failed to recreate user")?;
+        self.app_state.set_user(user.clone());
+        Ok(user)
+    }
+
+    /// Ré-enregistre l'adresse UDP auprès du serveur pour le channel courant
+    pub async fn send_heartbeat(&self) -> Result<()> {
+        Self::heartbeat(&self.app_state, &self.udp_client).await
+    }
+
+    async fn heartbeat(app_state: &AppState, udp_client: &RwLock<Option<AudioUdpClient>>) -> Result<()> {
+        let (Some(user), Some(channel_id)) = (
+            app_state.get_user(),
+            app_state.get_current_channel()
+        ) else {
+            anyhow::bail!("No user or channel for heartbeat")
+        };
+        let udp_client = udp_client.read().clone()
+            .context("No UDP client configured")?;
+        
+        udp_client.send_heartbeat(user.id, channel_id).await
+    }
+
+    /// Remonte au serveur la qualité de l'audio reçu, pour adapter l'encodage du channel
+    async fn receiver_report(app_state: &AppState, udp_client: &RwLock<Option<AudioUdpClient>>) -> Result<()> {
+        let (Some(user), Some(channel_id)) = (
+            app_state.get_user(),
+            app_state.get_current_channel()
+        ) else {
+            anyhow::bail!("No user or channel for receiver report")
+        };
+        let udp_client = udp_client.read().clone()
+            .context("No UDP client configured")?;
+
+        udp_client.send_receiver_report(user.id, channel_id).await
+    }
+
     /// Déconnecte l'utilisateur actuel
     pub async fn disconnect_user(&self) -> Result<()> {
         if let Some(user) = self.app_state.get_user() {
             println!("🔄 Starting disconnect process for user: {} ({})", user.username, user.id);
             
-            // Quitter le channel actuel si présent
-            if self.app_state.get_current_channel().is_some() {
-                println!("📤 Leaving current channel before disconnect");
-                let _ = self.leave_current_channel().await;
-            }
-            
-            // Déconnecter du serveur
+            // Déconnecter du serveur (qui retire aussi l'utilisateur de son channel)
             println!("🌐 Calling backend disconnect for user: {}", user.id);
             self.client.disconnect_user(user.id).await
                 .context("Failed to disconnect user")?;
@@ -314,12 +434,12 @@ impl BackendManager {
         }
     }
 
-    /// Rejoint un channel
-    pub async fn join_channel(&self, channel_id: Uuid) -> Result<()> {
+    /// Rejoint un channel (requête WebSocket)
+    pub async fn join_channel(&self, ws_client: &WebSocketClient, channel_id: Uuid) -> Result<()> {
         if let Some(user) = self.app_state.get_user() {
             println!("🏠 BackendManager: Joining channel {} for user {}", channel_id, user.id);
             
-            self.client.join_channel(user.id, channel_id).await
+            ws_client.request(ClientMessage::JoinChannel { channel_id, password: None }).await
                 .context("Failed to join channel")?;
             
             println!("✅ Successfully joined channel: {}", channel_id);
@@ -346,13 +466,13 @@ impl BackendManager {
         }
     }
 
-    /// Quitte le channel actuel
-    pub async fn leave_current_channel(&self) -> Result<()> {
-        if let (Some(user), Some(channel_id)) = (
+    /// Quitte le channel actuel (requête WebSocket)
+    pub async fn leave_current_channel(&self, ws_client: &WebSocketClient) -> Result<()> {
+        if let (Some(_), Some(channel_id)) = (
             self.app_state.get_user(),
             self.app_state.get_current_channel()
         ) {
-            self.client.leave_channel(user.id, channel_id).await
+            ws_client.request(ClientMessage::LeaveChannel { channel_id }).await
                 .context("Failed to leave channel")?;
             
             self.app_state.set_current_channel(None);
@@ -382,6 +502,14 @@ impl BackendManager {
     }
 }
 
+impl Drop for BackendManager {
+    fn drop(&mut self) {
+        if let Some(task) = self.heartbeat_task.lock().take() {
+            task.abort();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
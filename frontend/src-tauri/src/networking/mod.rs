@@ -1,7 +1,9 @@
 pub mod http;
+pub mod protocol;
 pub mod websocket;
 pub mod udp;
 
 pub use http::{BackendClient, BackendManager};
-pub use websocket::{WebSocketManager, WebSocketMessage};
-pub use udp::{AudioUdpClient, AudioPacket, AudioHeader, PacketType};
\ No newline at end of file
+pub use protocol::{ClientMessage, UserStatus};
+pub use websocket::{ReconnectHooks, SessionHook, WebSocketClient, WebSocketManager, WebSocketMessage};
+pub use udp::{AudioUdpClient, AudioPacket, AudioHeader, NetworkLatency, PacketType, UdpControl};
\ No newline at end of file
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Messages client -> serveur (miroir de `ClientMessage` du backend)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "payload")]
+pub enum ClientMessage {
+    Authenticate { username: String },
+    Resume { resume_token: Uuid },
+    JoinChannel { channel_id: Uuid, password: Option<String> },
+    LeaveChannel { channel_id: Uuid },
+    SetStatus { status: UserStatus },
+    StartAudio { channel_id: Uuid },
+    StopAudio { channel_id: Uuid },
+    SetVoiceState { muted: bool, deafened: bool },
+    Ping,
+}
+
+impl ClientMessage {
+    /// Nom de l'action, pour les logs
+    pub fn action(&self) -> &'static str {
+        match self {
+            ClientMessage::Authenticate { .. } => "Authenticate",
+            ClientMessage::Resume { .. } => "Resume",
+            ClientMessage::JoinChannel { .. } => "JoinChannel",
+            ClientMessage::LeaveChannel { .. } => "LeaveChannel",
+            ClientMessage::SetStatus { .. } => "SetStatus",
+            ClientMessage::StartAudio { .. } => "StartAudio",
+            ClientMessage::StopAudio { .. } => "StopAudio",
+            ClientMessage::SetVoiceState { .. } => "SetVoiceState",
+            ClientMessage::Ping => "Ping",
+        }
+    }
+}
+
+/// Statut d'un utilisateur (miroir de `UserStatus` du backend)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserStatus {
+    Online,
+    Away,
+    InChannel,
+    Reconnecting,
+    Offline,
+}
+
+/// Message client avec identifiant de corrélation ; la réponse du serveur porte le même `request_id`
+#[derive(Debug, Serialize)]
+pub struct ClientEnvelope<'a> {
+    #[serde(flatten)]
+    pub message: &'a ClientMessage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_envelope_format() {
+        let channel_id = Uuid::new_v4();
+        let message = ClientMessage::JoinChannel { channel_id, password: None };
+        let json = serde_json::to_value(ClientEnvelope { message: &message, request_id: Some(3) }).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "action": "JoinChannel",
+            "payload": { "channel_id": channel_id, "password": null },
+            "request_id": 3,
+        }));
+
+        let json = serde_json::to_value(ClientEnvelope { message: &ClientMessage::Ping, request_id: None }).unwrap();
+        assert_eq!(json, serde_json::json!({ "action": "Ping" }));
+    }
+}
@@ -1,10 +1,15 @@
 use anyhow::{Result, Context};
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
 use uuid::Uuid;
+use super::websocket::WebSocketClient;
 
 /// Types de packets audio (identique au backend)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,6 +24,59 @@ pub enum PacketType {
     AudioStop = 3,
     /// Packet de synchronisation/heartbeat
     Sync = 4,
+    /// Ancien défi UDP, plus émis : le nonce arrive par le WebSocket
+    Probe = 5,
+    /// Réponse à un défi d'adresse (payload : nonce reçu)
+    ProbeReply = 6,
+    /// Réponse du serveur à un heartbeat (payload : `SyncEcho`)
+    SyncReply = 7,
+    /// Rapport de réception envoyé au serveur (payload : `ReceiverReport`)
+    ReceiverReport = 8,
+}
+
+/// Qualité de l'audio reçu depuis le rapport précédent (identique au backend)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReceiverReport {
+    pub packets_expected: u32,
+    pub packets_lost: u32,
+    pub jitter_ms: f32,
+    pub rtt_ms: f32,
+}
+
+/// Aller-retour et décalage d'horloge joints aux heartbeats (identique au backend)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClockEstimate {
+    pub rtt_us: u64,
+    /// Horloge du serveur moins la nôtre
+    pub offset_us: i64,
+}
+
+/// Réponse du serveur à un heartbeat (identique au backend)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SyncEcho {
+    pub observed: SocketAddr,
+    pub client_sent_us: u64,
+    pub server_received_us: u64,
+}
+
+/// Latence vers le serveur, pour l'interface
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct NetworkLatency {
+    pub rtt_ms: f32,
+    pub clock_offset_ms: f32,
+}
+
+/// Poids d'une nouvelle mesure dans l'estimation d'horloge lissée
+const CLOCK_SMOOTHING: f64 = 0.25;
+
+/// Taille maximale d'un packet tant que le serveur n'a pas annoncé la sienne
+const DEFAULT_MAX_PACKET_SIZE: usize = 1400;
+
+fn now_micros() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_micros() as u64)
+        .unwrap_or_default()
 }
 
 /// Header du packet audio - 32 bytes (identique au backend)
@@ -59,10 +117,7 @@ impl AudioHeader {
             user_id,
             channel_id,
             sequence,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_micros() as u64,
+            timestamp: now_micros(),
             payload_size,
             sample_rate,
             channels,
@@ -158,23 +213,57 @@ impl AudioPacket {
         )
     }
 
-    /// Crée un packet de synchronisation/heartbeat
+    /// Crée un packet de synchronisation/heartbeat, avec notre dernière estimation
+    /// d'horloge pour le serveur
     pub fn sync(
         user_id: Uuid,
         channel_id: Uuid,
         sequence: u32,
+        estimate: Option<ClockEstimate>,
     ) -> Self {
+        let payload = estimate
+            .and_then(|estimate| bincode::serialize(&estimate).ok())
+            .map(Bytes::from)
+            .unwrap_or_default();
         Self::new(
             PacketType::Sync,
             user_id,
             channel_id,
             sequence,
-            Bytes::new(), // Pas de payload pour sync
+            payload,
             48000, // Valeurs par défaut
             2,
         )
     }
 
+    /// Crée la réponse à un défi d'adresse : le nonce reçu par le WebSocket, renvoyé en UDP
+    pub fn probe_reply(user_id: Uuid, channel_id: Uuid, nonce: Uuid) -> Self {
+        Self::new(
+            PacketType::ProbeReply,
+            user_id,
+            channel_id,
+            0,
+            Bytes::copy_from_slice(nonce.as_bytes()),
+            48000,
+            1,
+        )
+    }
+
+    /// Crée un rapport de réception
+    pub fn receiver_report(user_id: Uuid, channel_id: Uuid, report: &ReceiverReport) -> Result<Self> {
+        let payload = bincode::serialize(report)
+            .context("Failed to serialize receiver report")?;
+        Ok(Self::new(
+            PacketType::ReceiverReport,
+            user_id,
+            channel_id,
+            0,
+            Bytes::from(payload),
+            48000,
+            1,
+        ))
+    }
+
     /// Sérialise le packet en bytes (compatible backend)
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         let header_bytes = self.header.to_bytes()?;
@@ -185,12 +274,256 @@ impl AudioPacket {
     }
 }
 
+/// Recul de séquence au-delà duquel un émetteur est considéré comme redémarré
+const SEQUENCE_RESTART_GAP: u32 = 1000;
+
+/// Réception de l'audio d'un émetteur depuis le dernier rapport
+#[derive(Debug, Default)]
+struct SourceReception {
+    /// Plus grand numéro de séquence reçu
+    highest_sequence: u32,
+    /// `highest_sequence` au moment du rapport précédent
+    reported_sequence: u32,
+    received: u32,
+    /// Dernier temps de transit (arrivée - horodatage de l'émetteur) en microsecondes
+    last_transit: i64,
+    /// Gigue lissée (RFC 3550) en microsecondes
+    jitter_us: f64,
+}
+
+/// Statistiques de réception alimentant les rapports envoyés au serveur
+#[derive(Debug, Default)]
+struct ReceptionStats {
+    sources: HashMap<Uuid, SourceReception>,
+    /// Aller-retour et décalage d'horloge mesurés sur les heartbeats
+    clock: Option<ClockEstimate>,
+}
+
+impl ReceptionStats {
+    fn record(&mut self, sender: Uuid, sequence: u32, sent_at_us: u64, arrival_us: u64) {
+        let transit = arrival_us as i64 - sent_at_us as i64;
+        let source = match self.sources.get_mut(&sender) {
+            // Un grand retour en arrière signifie que l'émetteur a recommencé sa numérotation
+            Some(source) if !Self::restarted(source.highest_sequence, sequence) => source,
+            _ => {
+                self.sources.insert(sender, SourceReception {
+                    highest_sequence: sequence,
+                    reported_sequence: sequence.wrapping_sub(1),
+                    received: 1,
+                    last_transit: transit,
+                    jitter_us: 0.0,
+                });
+                return;
+            }
+        };
+
+        source.received += 1;
+        if sequence.wrapping_sub(source.highest_sequence) < u32::MAX / 2 {
+            source.highest_sequence = sequence;
+        }
+        let delta = (transit - source.last_transit).unsigned_abs() as f64;
+        source.jitter_us += (delta - source.jitter_us) / 16.0;
+        source.last_transit = transit;
+    }
+
+    fn restarted(highest: u32, sequence: u32) -> bool {
+        let behind = highest.wrapping_sub(sequence);
+        behind > SEQUENCE_RESTART_GAP && behind < u32::MAX / 2
+    }
+
+    fn take_report(&mut self) -> ReceiverReport {
+        let rtt_ms = self.clock.map(|clock| clock.rtt_us as f32 / 1000.0).unwrap_or_default();
+        let mut report = ReceiverReport { rtt_ms, ..Default::default() };
+        for source in self.sources.values_mut() {
+            let expected = source.highest_sequence.wrapping_sub(source.reported_sequence);
+            report.packets_expected += expected;
+            report.packets_lost += expected.saturating_sub(source.received);
+            report.jitter_ms = report.jitter_ms.max((source.jitter_us / 1000.0) as f32);
+            source.reported_sequence = source.highest_sequence;
+            source.received = 0;
+        }
+        report
+    }
+}
+
+/// Traitement des packets de contrôle du serveur et choix du transport (UDP ou tunnel
+/// WebSocket), partagé par les tâches qui lisent le socket
+#[derive(Clone, Default)]
+pub struct UdpControl {
+    /// Notre adresse telle que vue par le serveur (mapping NAT)
+    public_addr: Arc<parking_lot::RwLock<Option<SocketAddr>>>,
+    /// Dernière réponse du serveur à un heartbeat
+    last_sync_reply: Arc<parking_lot::RwLock<Option<Instant>>>,
+    /// WebSocket utilisable comme transport de secours
+    tunnel: Arc<parking_lot::RwLock<Option<WebSocketClient>>>,
+    /// L'audio passe par le tunnel (UDP bloqué)
+    tunneled: Arc<AtomicBool>,
+    /// Entrée de la tâche d'écoute, qui reçoit aussi l'audio tunnelé
+    tunnel_sink: Arc<parking_lot::Mutex<Option<mpsc::UnboundedSender<Vec<u8>>>>>,
+    /// Pertes, gigue et aller-retour observés, remontés au serveur
+    reception: Arc<parking_lot::Mutex<ReceptionStats>>,
+    /// Taille maximale d'un packet acceptée par le serveur (0 : pas encore annoncée)
+    max_packet_size: Arc<AtomicUsize>,
+}
+
+impl std::fmt::Debug for UdpControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UdpControl")
+            .field("public_addr", &self.public_addr())
+            .field("tunneled", &self.is_tunneled())
+            .finish()
+    }
+}
+
+impl UdpControl {
+    /// Adresse publique annoncée par le serveur lors du dernier heartbeat
+    pub fn public_addr(&self) -> Option<SocketAddr> {
+        *self.public_addr.read()
+    }
+
+    /// Date de la dernière réponse à un heartbeat (aucune si l'UDP est bloqué)
+    pub fn last_sync_reply(&self) -> Option<Instant> {
+        *self.last_sync_reply.read()
+    }
+
+    /// Taille maximale d'un packet acceptée par le serveur
+    pub fn max_packet_size(&self) -> usize {
+        match self.max_packet_size.load(Ordering::Relaxed) {
+            0 => DEFAULT_MAX_PACKET_SIZE,
+            size => size,
+        }
+    }
+
+    /// Retient la taille maximale annoncée par le serveur
+    pub fn set_max_packet_size(&self, size: usize) {
+        self.max_packet_size.store(size, Ordering::Relaxed);
+    }
+
+    /// Comptabilise un packet audio reçu pour le prochain rapport de réception
+    pub fn record_audio(&self, packet: &AudioPacket) {
+        self.reception.lock().record(packet.header.user_id, packet.header.sequence, packet.header.timestamp, now_micros());
+    }
+
+    /// Estimation d'horloge courante (aucune avant la première réponse à un heartbeat)
+    pub fn clock_estimate(&self) -> Option<ClockEstimate> {
+        self.reception.lock().clock
+    }
+
+    pub fn latency(&self) -> Option<NetworkLatency> {
+        self.clock_estimate().map(|clock| NetworkLatency {
+            rtt_ms: clock.rtt_us as f32 / 1000.0,
+            clock_offset_ms: clock.offset_us as f32 / 1000.0,
+        })
+    }
+
+    /// Intègre une réponse à un heartbeat reçue à `received_us` (calcul façon NTP)
+    fn record_sync_echo(&self, echo: &SyncEcho, server_sent_us: u64, received_us: u64) {
+        let (t0, t1, t2, t3) = (echo.client_sent_us as i64, echo.server_received_us as i64, server_sent_us as i64, received_us as i64);
+        let rtt_us = ((t3 - t0) - (t2 - t1)).max(0) as f64;
+        let offset_us = ((t1 - t0) + (t2 - t3)) as f64 / 2.0;
+
+        let mut reception = self.reception.lock();
+        let (rtt_us, offset_us) = match reception.clock {
+            Some(previous) => (
+                previous.rtt_us as f64 + (rtt_us - previous.rtt_us as f64) * CLOCK_SMOOTHING,
+                previous.offset_us as f64 + (offset_us - previous.offset_us as f64) * CLOCK_SMOOTHING,
+            ),
+            None => (rtt_us, offset_us),
+        };
+        reception.clock = Some(ClockEstimate { rtt_us: rtt_us as u64, offset_us: offset_us as i64 });
+    }
+
+    /// Rapport de réception depuis l'appel précédent
+    pub fn take_report(&self) -> ReceiverReport {
+        self.reception.lock().take_report()
+    }
+
+    /// Fournit le WebSocket à utiliser si l'UDP s'avère bloqué
+    pub fn set_tunnel(&self, client: WebSocketClient) {
+        if let Some(sink) = self.tunnel_sink.lock().as_ref() {
+            client.set_audio_sink(sink.clone());
+        }
+        *self.tunnel.write() = Some(client);
+    }
+
+    /// Bascule l'audio sur le tunnel ; retourne `false` si aucun WebSocket n'est disponible
+    /// ou si le tunnel est déjà actif
+    pub fn activate_tunnel(&self) -> bool {
+        self.tunnel.read().is_some() && !self.tunneled.swap(true, Ordering::SeqCst)
+    }
+
+    /// Rend l'audio à l'UDP (le serveur répond de nouveau aux heartbeats) ; retourne
+    /// `false` si le tunnel n'était pas actif
+    pub fn deactivate_tunnel(&self) -> bool {
+        self.tunneled.swap(false, Ordering::SeqCst)
+    }
+
+    pub fn is_tunneled(&self) -> bool {
+        self.tunneled.load(Ordering::SeqCst)
+    }
+
+    /// WebSocket par lequel envoyer l'audio, si le tunnel est actif
+    pub fn active_tunnel(&self) -> Option<WebSocketClient> {
+        if self.is_tunneled() {
+            self.tunnel.read().clone()
+        } else {
+            None
+        }
+    }
+
+    /// Canal des packets reçus par le tunnel, pour la tâche d'écoute (remplace le précédent)
+    pub fn subscribe_tunnel(&self) -> mpsc::UnboundedReceiver<Vec<u8>> {
+        let (sink, receiver) = mpsc::unbounded_channel();
+        if let Some(client) = self.tunnel.read().as_ref() {
+            client.set_audio_sink(sink.clone());
+        }
+        *self.tunnel_sink.lock() = Some(sink);
+        receiver
+    }
+
+    /// Traite un packet de contrôle ; retourne `false` si le packet n'en est pas un
+    pub fn handle(&self, packet: &AudioPacket) -> bool {
+        match packet.header.packet_type {
+            PacketType::SyncReply => {
+                let received_us = now_micros();
+                *self.last_sync_reply.write() = Some(Instant::now());
+                match bincode::deserialize::<SyncEcho>(&packet.payload) {
+                    Ok(echo) => {
+                        self.record_sync_echo(&echo, packet.header.timestamp, received_us);
+                        let previous = self.public_addr.write().replace(echo.observed);
+                        if previous != Some(echo.observed) {
+                            println!("🌐 UdpClient: Public UDP mapping is {}", echo.observed);
+                        }
+                    }
+                    Err(e) => println!("⚠️ UdpClient: Invalid heartbeat reply: {}", e),
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Traite un packet de contrôle reçu par le tunnel. Une réponse au heartbeat n'y sert
+    /// qu'à mesurer l'aller-retour : elle ne prouve pas que l'UDP passe.
+    pub fn handle_tunneled(&self, packet: &AudioPacket) -> bool {
+        if packet.header.packet_type != PacketType::SyncReply {
+            return false;
+        }
+        match bincode::deserialize::<SyncEcho>(&packet.payload) {
+            Ok(echo) => self.record_sync_echo(&echo, packet.header.timestamp, now_micros()),
+            Err(e) => println!("⚠️ UdpClient: Invalid tunnelled heartbeat reply: {}", e),
+        }
+        true
+    }
+}
+
 /// Client UDP pour l'audio
 #[derive(Debug)]
 pub struct AudioUdpClient {
     socket: Arc<UdpSocket>,
     server_addr: SocketAddr,
     sequence: std::sync::atomic::AtomicU32,
+    control: UdpControl,
 }
 
 impl Clone for AudioUdpClient {
@@ -199,6 +532,7 @@ impl Clone for AudioUdpClient {
             socket: Arc::clone(&self.socket),
             server_addr: self.server_addr,
             sequence: std::sync::atomic::AtomicU32::new(0),
+            control: self.control.clone(),
         }
     }
 }
@@ -206,35 +540,39 @@ impl Clone for AudioUdpClient {
 impl AudioUdpClient {
     /// Crée un nouveau client UDP audio
     pub async fn new(server_addr: SocketAddr) -> Result<Self> {
-        // Utiliser le port 8083 pour que le backend puisse nous renvoyer l'audio sur le même port
-        println!("🔗 AudioUdpClient: Attempting to bind to port 8083...");
-        let socket = match UdpSocket::bind("0.0.0.0:8083").await {
-            Ok(sock) => {
-                println!("✅ AudioUdpClient: Successfully bound to port 8083");
-                sock
-            }
-            Err(e) => {
-                println!("❌ AudioUdpClient: Failed to bind to port 8083: {}", e);
-                println!("🔗 AudioUdpClient: Trying dynamic port...");
-                let sock = UdpSocket::bind("0.0.0.0:0").await
-                    .context("Failed to bind UDP socket on any port")?;
-                println!("✅ AudioUdpClient: Bound to dynamic port {:?}", sock.local_addr()?);
-                println!("⚠️ WARNING: Backend will NOT be able to route audio back to this client!");
-                println!("⚠️ The backend expects audio clients on port 8083, but we're on {:?}", sock.local_addr()?);
-                sock
-            }
-        };
+        // Port éphémère : le serveur apprend notre adresse (et ses changements) depuis nos packets
+        let socket = UdpSocket::bind("0.0.0.0:0").await
+            .context("Failed to bind UDP socket")?;
+        println!("✅ AudioUdpClient: Bound to {:?}", socket.local_addr()?);
             
         Ok(Self {
             socket: Arc::new(socket),
             server_addr,
             sequence: std::sync::atomic::AtomicU32::new(0),
+            control: UdpControl::default(),
         })
     }
 
-    /// Envoie un packet audio au serveur
+    /// Traitement des packets de contrôle, à passer aux tâches qui lisent le socket partagé
+    pub fn control(&self) -> UdpControl {
+        self.control.clone()
+    }
+
+    /// Adresse publique annoncée par le serveur (connue après un heartbeat)
+    pub fn public_addr(&self) -> Option<SocketAddr> {
+        self.control.public_addr()
+    }
+
+    /// Envoie un packet audio au serveur, par le tunnel WebSocket si l'UDP est bloqué
     pub async fn send_audio_packet(&self, packet: AudioPacket) -> Result<()> {
         let bytes = packet.to_bytes()?;
+
+        if let Some(tunnel) = self.control.active_tunnel() {
+            if !tunnel.send_audio(bytes) {
+                anyhow::bail!("Audio tunnel is closed");
+            }
+            return Ok(());
+        }
         
         self.socket.send_to(&bytes, self.server_addr).await
             .context("Failed to send audio packet")?;
@@ -263,17 +601,27 @@ impl AudioUdpClient {
         
         // println!("🎵 UdpClient: -> {} PCM bytes", pcm_data.len());
 
-        let sequence = self.sequence.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-        let packet = AudioPacket::audio(
-            user_id,
-            channel_id,
-            sequence,
-            Bytes::from(pcm_data),
-            sample_rate,
-            channels,
-        );
+        // Découper en packets acceptés par le serveur, sans couper de frame
+        let header_size = AudioHeader::new(PacketType::Audio, user_id, channel_id, 0, 0, sample_rate, channels)
+            .to_bytes()?
+            .len();
+        let frame_size = 2 * channels.max(1) as usize;
+        let chunk_size = (self.control.max_packet_size().saturating_sub(header_size) / frame_size).max(1) * frame_size;
 
-        self.send_audio_packet(packet).await
+        for chunk in pcm_data.chunks(chunk_size) {
+            let sequence = self.sequence.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let packet = AudioPacket::audio(
+                user_id,
+                channel_id,
+                sequence,
+                Bytes::copy_from_slice(chunk),
+                sample_rate,
+                channels,
+            );
+            self.send_audio_packet(packet).await?;
+        }
+
+        Ok(())
     }
 
     /// Obtient l'adresse locale du socket
@@ -293,15 +641,48 @@ impl AudioUdpClient {
         self.server_addr
     }
 
-    /// Envoie un packet de synchronisation/heartbeat au serveur
+    /// Envoie un packet de synchronisation/heartbeat au serveur. Il part toujours en UDP,
+    /// pour détecter le retour de l'UDP, et aussi par le tunnel actif pour mesurer l'aller-retour.
     pub async fn send_heartbeat(
         &self,
         user_id: Uuid,
         channel_id: Uuid,
     ) -> Result<()> {
-        let packet = AudioPacket::sync(user_id, channel_id, 0);
-        println!("💓 UdpClient: Sending heartbeat to register UDP address");
-        self.send_audio_packet(packet).await
+        let bytes = AudioPacket::sync(user_id, channel_id, 0, self.control.clock_estimate()).to_bytes()?;
+
+        if let Some(tunnel) = self.control.active_tunnel() {
+            if !tunnel.send_audio(bytes.clone()) {
+                println!("⚠️ UdpClient: Audio tunnel is closed, heartbeat sent over UDP only");
+            }
+        }
+
+        self.socket.send_to(&bytes, self.server_addr).await
+            .context("Failed to send heartbeat")?;
+        Ok(())
+    }
+
+    /// Répond à un défi d'adresse reçu par le WebSocket. La réponse part toujours en UDP :
+    /// c'est l'adresse d'où elle arrive que le serveur adopte.
+    pub async fn answer_address_challenge(
+        &self,
+        user_id: Uuid,
+        channel_id: Uuid,
+        nonce: Uuid,
+    ) -> Result<()> {
+        let bytes = AudioPacket::probe_reply(user_id, channel_id, nonce).to_bytes()?;
+        self.socket.send_to(&bytes, self.server_addr).await
+            .context("Failed to answer address challenge")?;
+        Ok(())
+    }
+
+    /// Envoie au serveur le rapport de réception depuis le précédent
+    pub async fn send_receiver_report(
+        &self,
+        user_id: Uuid,
+        channel_id: Uuid,
+    ) -> Result<()> {
+        let report = self.control.take_report();
+        self.send_audio_packet(AudioPacket::receiver_report(user_id, channel_id, &report)?).await
     }
 
     /// Démarre l'écoute des packets entrants sur le même socket
@@ -315,6 +696,7 @@ impl AudioUdpClient {
         
         let socket = Arc::clone(&self.socket);
         let server_addr = self.server_addr;
+        let control = self.control.clone();
         let mut buf = vec![0u8; 4096];
         
         loop {
@@ -355,7 +737,7 @@ impl AudioUdpClient {
                                             println!("🔊 UdpClient: Audio channel closed, stopping reception");
                                             break;
                                         }
-                                    } else {
+                                    } else if !control.handle(&packet) {
                                         println!("🔇 UdpClient: Ignoring non-audio packet type: {:?}", packet.header.packet_type);
                                     }
                                 } else {
@@ -415,6 +797,97 @@ mod tests {
         assert!(bytes.len() >= 32); // Au moins la taille du header
     }
 
+    #[tokio::test]
+    async fn test_audio_split_to_max_packet_size() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client = AudioUdpClient::new(server.local_addr().unwrap()).await.unwrap();
+        client.control().set_max_packet_size(1000);
+
+        // 20 ms de stéréo à 48 kHz : 3840 bytes de PCM, trop pour un seul packet
+        client.send_audio_data(Uuid::new_v4(), Uuid::new_v4(), vec![0.25; 960 * 2], 48000, 2).await.unwrap();
+
+        let mut buf = vec![0u8; 4096];
+        let mut payload = 0;
+        let mut sequences = Vec::new();
+        while payload < 960 * 2 * 2 {
+            let (size, _) = server.recv_from(&mut buf).await.unwrap();
+            assert!(size <= 1000);
+            let packet = AudioPacket::from_bytes(&buf[..size]).unwrap();
+            assert_eq!(packet.payload.len() % 4, 0);
+            payload += packet.payload.len();
+            sequences.push(packet.header.sequence);
+        }
+        assert_eq!(payload, 960 * 2 * 2);
+        assert_eq!(sequences, (0..sequences.len() as u32).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_control_packets() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client = AudioUdpClient::new(server.local_addr().unwrap()).await.unwrap();
+        let control = UdpControl::default();
+        let (user_id, channel_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // Le nonce reçu par le WebSocket est renvoyé en UDP ; un défi UDP n'est plus écouté
+        let nonce = Uuid::new_v4();
+        client.answer_address_challenge(user_id, channel_id, nonce).await.unwrap();
+        let mut buf = vec![0u8; 512];
+        let (size, _) = server.recv_from(&mut buf).await.unwrap();
+        let reply = AudioPacket::from_bytes(&buf[..size]).unwrap();
+        assert_eq!(reply.header.packet_type, PacketType::ProbeReply);
+        assert_eq!(&reply.payload[..], nonce.as_bytes());
+        let probe = AudioPacket::new(PacketType::Probe, user_id, channel_id, 0, Bytes::copy_from_slice(nonce.as_bytes()), 48000, 1);
+        assert!(!control.handle(&probe));
+
+        // Réponse au heartbeat : adresse publique et horodatages NTP
+        let echo = SyncEcho {
+            observed: "203.0.113.5:40000".parse().unwrap(),
+            client_sent_us: now_micros() - 30_000,
+            server_received_us: now_micros() + 5_000_000 - 15_000,
+        };
+        let mut reply = AudioPacket::new(PacketType::SyncReply, user_id, channel_id, 0, Bytes::from(bincode::serialize(&echo).unwrap()), 48000, 1);
+        reply.header.timestamp = echo.server_received_us;
+        assert!(control.handle(&reply));
+        assert_eq!(control.public_addr(), Some(echo.observed));
+        let clock = control.clock_estimate().unwrap();
+        assert!((clock.rtt_us as i64 - 30_000).abs() < 5_000);
+        assert!((clock.offset_us - 5_000_000).abs() < 5_000);
+        assert_eq!(AudioPacket::sync(user_id, channel_id, 0, Some(clock)).payload, bincode::serialize(&clock).unwrap());
+
+        let audio = AudioPacket::audio(user_id, channel_id, 0, Bytes::new(), 48000, 1);
+        assert!(!control.handle(&audio));
+
+        // Une réponse tunnelée met à jour l'horloge sans passer pour une réponse UDP
+        let control = UdpControl::default();
+        assert!(control.handle_tunneled(&reply));
+        assert!(control.clock_estimate().is_some());
+        assert!(control.last_sync_reply().is_none());
+        assert!(control.public_addr().is_none());
+        assert!(!control.handle_tunneled(&audio));
+    }
+
+    #[test]
+    fn test_receiver_report() {
+        let control = UdpControl::default();
+        let (sender, channel_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        // 10 packets émis, le 4e et le 7e perdus
+        for sequence in (0..10).filter(|seq| *seq != 3 && *seq != 6) {
+            control.record_audio(&AudioPacket::audio(sender, channel_id, sequence, Bytes::new(), 48000, 1));
+        }
+        let report = control.take_report();
+        assert_eq!(report.packets_expected, 10);
+        assert_eq!(report.packets_lost, 2);
+
+        let packet = AudioPacket::receiver_report(Uuid::new_v4(), channel_id, &report).unwrap();
+        let parsed = AudioPacket::from_bytes(&packet.to_bytes().unwrap()).unwrap();
+        assert_eq!(parsed.header.packet_type, PacketType::ReceiverReport);
+        assert_eq!(bincode::deserialize::<ReceiverReport>(&parsed.payload).unwrap(), report);
+
+        // Rien de nouveau depuis le rapport précédent
+        assert_eq!(control.take_report().packets_expected, 0);
+    }
+
     #[tokio::test]
     async fn test_udp_client_creation() {
         let server_addr: SocketAddr = "127.0.0.1:8082".parse().unwrap();
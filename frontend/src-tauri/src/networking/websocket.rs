@@ -1,103 +1,377 @@
-use anyhow::Result;
-use futures_util::{SinkExt, StreamExt};
+use anyhow::{Result, Context};
+use futures_util::{future::BoxFuture, SinkExt, StreamExt};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
-use tokio::sync::oneshot;
+use tauri::{AppHandle, Emitter, Manager};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
+use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::protocol::{ClientEnvelope, ClientMessage};
+use crate::audio::EncodingProfile;
+use crate::state::{AppState, ChannelInfo, ConnectionState};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebSocketMessage {
     #[serde(rename = "event")]
     pub message_type: String,
+    #[serde(default)]
     pub data: serde_json::Value,
+    /// Présent sur les réponses aux requêtes corrélées
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+}
+
+/// Délai maximal d'attente de la réponse à une requête
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+type PendingRequests = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<WebSocketMessage, String>>>>>;
+
+/// Destinataire des packets audio reçus en frames binaires (tunnel quand l'UDP est bloqué)
+type AudioSink = Arc<Mutex<Option<mpsc::UnboundedSender<Vec<u8>>>>>;
+
+/// Packets audio en attente d'envoi sur le tunnel (au-delà, ils sont perdus comme en UDP)
+const AUDIO_QUEUE_SIZE: usize = 64;
+
+/// Accès typé au WebSocket : envoie des `ClientMessage` et attend la réponse correspondante
+#[derive(Clone)]
+pub struct WebSocketClient {
+    outgoing: mpsc::UnboundedSender<String>,
+    pending: PendingRequests,
+    next_request_id: Arc<AtomicU64>,
+    audio_outgoing: mpsc::Sender<Vec<u8>>,
+    audio_sink: AudioSink,
+}
+
+impl WebSocketClient {
+    /// Envoie une requête et attend sa réponse (ou l'erreur renvoyée par le serveur)
+    pub async fn request(&self, message: ClientMessage) -> Result<WebSocketMessage> {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let text = serde_json::to_string(&ClientEnvelope { message: &message, request_id: Some(request_id) })
+            .context("Failed to serialize WebSocket request")?;
+
+        let (response_tx, response_rx) = oneshot::channel();
+        self.pending.lock().insert(request_id, response_tx);
+
+        if self.outgoing.send(text).is_err() {
+            self.pending.lock().remove(&request_id);
+            anyhow::bail!("WebSocket is not running");
+        }
+        println!("📤 WebSocket request #{} {}", request_id, message.action());
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, response_rx).await {
+            Ok(Ok(Ok(response))) => Ok(response),
+            Ok(Ok(Err(error))) => anyhow::bail!("{} failed: {}", message.action(), error),
+            Ok(Err(_)) => anyhow::bail!("{} failed: WebSocket closed", message.action()),
+            Err(_) => {
+                self.pending.lock().remove(&request_id);
+                anyhow::bail!("{} timed out", message.action())
+            }
+        }
+    }
+
+    /// Envoie un packet audio sérialisé en frame binaire ; retourne `false` si le WebSocket est arrêté
+    pub fn send_audio(&self, bytes: Vec<u8>) -> bool {
+        !matches!(self.audio_outgoing.try_send(bytes), Err(mpsc::error::TrySendError::Closed(_)))
+    }
+
+    /// Redirige les frames binaires reçues (audio tunnelé) vers ce canal
+    pub fn set_audio_sink(&self, sink: mpsc::UnboundedSender<Vec<u8>>) {
+        *self.audio_sink.lock() = Some(sink);
+    }
+
+    /// Transmet la réponse à la requête en attente ; retourne `false` si aucune ne correspond
+    fn complete(pending: &PendingRequests, response: &WebSocketMessage) -> bool {
+        let Some(response_tx) = response.request_id.and_then(|id| pending.lock().remove(&id)) else {
+            return false;
+        };
+        let result = if response.message_type == "Error" {
+            Err(response.data["message"].as_str().unwrap_or("Request failed").to_string())
+        } else {
+            Ok(response.clone())
+        };
+        let _ = response_tx.send(result);
+        true
+    }
+
+    /// Échoue toutes les requêtes en attente (connexion perdue)
+    fn fail_pending(pending: &PendingRequests, reason: &str) {
+        for (_, response_tx) in pending.lock().drain() {
+            let _ = response_tx.send(Err(reason.to_string()));
+        }
+    }
+}
+
+/// Délai avant la première tentative de reconnexion (doublé à chaque échec)
+const RECONNECT_BASE_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Tentatives consécutives avant d'abandonner
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+/// Intervalle des pings (le serveur ferme les sockets silencieux)
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+/// Sans aucun message du serveur pendant ce délai, la connexion est considérée morte
+const SERVER_TIMEOUT: Duration = Duration::from_secs(25);
+
+/// Étape asynchrone fournie par l'application autour d'une reconnexion
+pub type SessionHook<T> = Arc<dyn Fn() -> BoxFuture<'static, Result<T>> + Send + Sync>;
+
+/// Rétablissement de la session applicative après une coupure du WebSocket
+#[derive(Clone)]
+pub struct ReconnectHooks {
+    /// Avant une nouvelle tentative sans jeton de reprise valable : s'assure que l'utilisateur
+    /// existe côté serveur (il l'oublie à l'expiration de la session) et retourne son nom
+    pub reauthenticate: SessionHook<String>,
+    /// Après ré-authentification : rejoint le channel précédent et ré-enregistre l'adresse UDP
+    pub restore: SessionHook<()>,
+}
+
+/// Délai d'attente avant la tentative `attempt` (à partir de 1)
+fn reconnect_delay(attempt: u32) -> Duration {
+    RECONNECT_BASE_DELAY
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(RECONNECT_MAX_DELAY)
 }
 
 pub struct WebSocketManager {
     shutdown_tx: Option<oneshot::Sender<()>>,
+    client: Option<WebSocketClient>,
 }
 
 impl WebSocketManager {
     pub fn new() -> Self {
         Self {
             shutdown_tx: None,
+            client: None,
         }
     }
 
-    pub async fn start(&mut self, app_handle: AppHandle, server_url: String, username: String) -> Result<()> {
+    /// Client typé de la connexion en cours
+    pub fn client(&self) -> Option<WebSocketClient> {
+        self.client.clone()
+    }
+
+    pub async fn start(
+        &mut self,
+        app_handle: AppHandle,
+        app_state: AppState,
+        server_url: String,
+        username: String,
+        hooks: ReconnectHooks,
+    ) -> Result<()> {
         println!("🚀 Starting WebSocket connection to {}", server_url);
         
         let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
         self.shutdown_tx = Some(shutdown_tx);
 
+        // Requêtes typées : sérialisées par le client, écrites par la tâche WebSocket
+        let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+        let (audio_tx, mut audio_rx) = mpsc::channel::<Vec<u8>>(AUDIO_QUEUE_SIZE);
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+        let audio_sink: AudioSink = Arc::new(Mutex::new(None));
+        self.client = Some(WebSocketClient {
+            outgoing: outgoing_tx,
+            pending: pending.clone(),
+            next_request_id: Arc::new(AtomicU64::new(0)),
+            audio_outgoing: audio_tx,
+            audio_sink: audio_sink.clone(),
+        });
+
         let app_handle_clone = app_handle.clone();
         tokio::spawn(async move {
             println!("🔌 Starting WebSocket task");
             
             let ws_url = server_url.clone();
-            println!("🔗 Connecting to: {}", ws_url);
-            
-            let connection_result = connect_async(&ws_url).await;
-            
-            match connection_result {
-                Ok((ws_stream, _)) => {
-                    println!("✅ WebSocket connected successfully");
-                    
-                    let (mut write, mut read) = ws_stream.split();
-                    
-                    let auth_message = serde_json::json!({
-                        "action": "Authenticate",
-                        "payload": {
-                            "username": username
+            let mut username = username;
+            // Nombre d'échecs consécutifs ; 0 tant que la connexion tient
+            let mut attempt: u32 = 0;
+            // Une coupure après une session établie demande de la restaurer
+            let mut was_connected = false;
+            // Jeton reçu à l'authentification, valable pendant le délai de grâce du serveur
+            let mut resume_token: Option<Uuid> = None;
+
+            'session: loop {
+                if attempt > 0 {
+                    if attempt > MAX_RECONNECT_ATTEMPTS {
+                        println!("❌ WebSocket reconnection failed after {} attempts", MAX_RECONNECT_ATTEMPTS);
+                        app_state.set_connection_state(ConnectionState::Error);
+                        Self::emit_connection_state(&app_handle_clone, serde_json::json!({
+                            "state": "failed",
+                            "attempts": MAX_RECONNECT_ATTEMPTS,
+                        }));
+                        break;
+                    }
+
+                    let delay = reconnect_delay(attempt);
+                    println!("🔄 WebSocket reconnecting in {:?} (attempt {}/{})", delay, attempt, MAX_RECONNECT_ATTEMPTS);
+                    app_state.set_connection_state(ConnectionState::Reconnecting);
+                    Self::emit_connection_state(&app_handle_clone, serde_json::json!({
+                        "state": "reconnecting",
+                        "attempt": attempt,
+                        "maxAttempts": MAX_RECONNECT_ATTEMPTS,
+                        "delayMs": delay.as_millis() as u64,
+                    }));
+
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = &mut shutdown_rx => {
+                            println!("🛑 Received shutdown signal during reconnection");
+                            break 'session;
+                        }
+                    }
+
+                    if was_connected && resume_token.is_none() {
+                        match (hooks.reauthenticate)().await {
+                            Ok(name) => username = name,
+                            Err(e) => {
+                                println!("❌ Failed to restore user session: {}", e);
+                                attempt += 1;
+                                continue;
+                            }
                         }
-                    });
-                    
-                    if let Err(e) = write.send(Message::Text(auth_message.to_string())).await {
-                        println!("❌ Failed to send auth message: {}", e);
-                        return;
-                    }
-                    
-                    println!("🔐 Auth message sent");
-                    
-                    loop {
-                        tokio::select! {
-                            msg = read.next() => {
-                                match msg {
-                                    Some(Ok(Message::Text(text))) => {
-                                        println!("📩 Received WebSocket message: {}", text);
-                                        
-                                        if let Ok(ws_message) = serde_json::from_str::<WebSocketMessage>(&text) {
+                    }
+                }
+
+                println!("🔗 Connecting to: {}", ws_url);
+                let (ws_stream, _) = match connect_async(&ws_url).await {
+                    Ok(connection) => connection,
+                    Err(e) => {
+                        println!("❌ Failed to connect to WebSocket: {}", e);
+                        attempt += 1;
+                        continue;
+                    }
+                };
+                println!("✅ WebSocket connected successfully");
+                
+                let (mut write, mut read) = ws_stream.split();
+                
+                // Reprendre la session interrompue si possible, sinon s'authentifier
+                let resuming = attempt > 0 && resume_token.is_some();
+                let auth_message = match resume_token.filter(|_| resuming) {
+                    Some(resume_token) => ClientMessage::Resume { resume_token },
+                    None => ClientMessage::Authenticate { username: username.clone() },
+                };
+                let auth_text = serde_json::to_string(&ClientEnvelope { message: &auth_message, request_id: None })
+                    .unwrap_or_default();
+                
+                if let Err(e) = write.send(Message::Text(auth_text)).await {
+                    println!("❌ Failed to send auth message: {}", e);
+                    attempt += 1;
+                    continue;
+                }
+                
+                println!("🔐 {} message sent", if resuming { "Resume" } else { "Auth" });
+
+                let start = tokio::time::Instant::now();
+                let mut ping_interval = tokio::time::interval_at(start + PING_INTERVAL, PING_INTERVAL);
+                let mut last_received = start;
+                
+                loop {
+                    tokio::select! {
+                        msg = read.next() => {
+                            if let Some(Ok(_)) = &msg {
+                                last_received = tokio::time::Instant::now();
+                            }
+                            match msg {
+                                Some(Ok(Message::Text(text))) => {
+                                    println!("📩 Received WebSocket message: {}", text);
+                                    
+                                    if let Ok(ws_message) = serde_json::from_str::<WebSocketMessage>(&text) {
+                                        let authenticated = matches!(ws_message.message_type.as_str(), "Authenticated" | "Resumed");
+                                        if authenticated {
+                                            // Le serveur change de jeton à chaque reprise : garder le dernier
+                                            resume_token = ws_message.data["resume_token"].as_str()
+                                                .and_then(|token| Uuid::parse_str(token).ok());
+                                        }
+                                        if resuming && attempt > 0 && ws_message.message_type == "Error" {
+                                            // Session expirée côté serveur : reconnexion avec ré-authentification
+                                            println!("⚠️ Session resume rejected: {}", ws_message.data);
+                                            resume_token = None;
+                                            break;
+                                        }
+                                        // Réponse à une requête typée : débloquer l'appelant, qui gère lui-même l'erreur
+                                        let answered = WebSocketClient::complete(&pending, &ws_message);
+                                        if !(answered && ws_message.message_type == "Error") {
                                             // Traiter le message et émettre l'événement approprié
-                                            Self::handle_websocket_message(&app_handle_clone, ws_message).await;
-                                        } else {
-                                            println!("⚠️ Failed to parse WebSocket message as JSON");
+                                            Self::handle_websocket_message(&app_handle_clone, &app_state, ws_message).await;
                                         }
+
+                                        if authenticated {
+                                            if attempt > 0 {
+                                                Self::restore_session(app_handle_clone.clone(), app_state.clone(), hooks.clone(), attempt);
+                                            }
+                                            attempt = 0;
+                                            was_connected = true;
+                                        }
+                                    } else {
+                                        println!("⚠️ Failed to parse WebSocket message as JSON");
                                     }
-                                    Some(Ok(Message::Close(_))) => {
-                                        println!("🔌 WebSocket connection closed by server");
-                                        break;
-                                    }
-                                    Some(Err(e)) => {
-                                        println!("❌ WebSocket error: {}", e);
-                                        break;
-                                    }
-                                    None => {
-                                        println!("🔌 WebSocket stream ended");
-                                        break;
+                                }
+                                Some(Ok(Message::Binary(data))) => {
+                                    // Audio tunnelé, transmis tel quel à la lecture
+                                    if let Some(sink) = audio_sink.lock().as_ref() {
+                                        let _ = sink.send(data);
                                     }
-                                    _ => {}
                                 }
+                                Some(Ok(Message::Close(_))) => {
+                                    println!("🔌 WebSocket connection closed by server");
+                                    break;
+                                }
+                                Some(Err(e)) => {
+                                    println!("❌ WebSocket error: {}", e);
+                                    break;
+                                }
+                                None => {
+                                    println!("🔌 WebSocket stream ended");
+                                    break;
+                                }
+                                _ => {}
+                            }
+                        }
+                        Some(text) = outgoing_rx.recv() => {
+                            if let Err(e) = write.send(Message::Text(text)).await {
+                                println!("❌ Failed to send WebSocket request: {}", e);
+                                break;
                             }
-                            _ = &mut shutdown_rx => {
-                                println!("🛑 Received shutdown signal, closing WebSocket");
+                        }
+                        Some(bytes) = audio_rx.recv() => {
+                            if let Err(e) = write.send(Message::Binary(bytes)).await {
+                                println!("❌ Failed to send tunnelled audio: {}", e);
                                 break;
                             }
                         }
+                        _ = ping_interval.tick() => {
+                            // Un serveur arrêté ou un réseau coupé ne ferme pas toujours la connexion
+                            if last_received.elapsed() > SERVER_TIMEOUT {
+                                println!("💀 No message from server for {:?}, connection considered dead", last_received.elapsed());
+                                break;
+                            }
+                            let ping = serde_json::to_string(&ClientEnvelope { message: &ClientMessage::Ping, request_id: None })
+                                .unwrap_or_default();
+                            if let Err(e) = write.send(Message::Text(ping)).await {
+                                println!("❌ Failed to send ping: {}", e);
+                                break;
+                            }
+                        }
+                        _ = &mut shutdown_rx => {
+                            println!("🛑 Received shutdown signal, closing WebSocket");
+                            break 'session;
+                        }
                     }
                 }
-                Err(e) => {
-                    println!("❌ Failed to connect to WebSocket: {}", e);
-                }
+
+                // Connexion perdue : les requêtes en cours ne recevront pas de réponse
+                WebSocketClient::fail_pending(&pending, "WebSocket connection lost");
+                while outgoing_rx.try_recv().is_ok() {}
+                while audio_rx.try_recv().is_ok() {}
+
+                // Reprendre avec backoff
+                attempt += 1;
             }
+            WebSocketClient::fail_pending(&pending, "WebSocket closed");
             println!("🔌 WebSocket task ended");
         });
 
@@ -105,6 +379,31 @@ impl WebSocketManager {
         Ok(())
     }
 
+    /// Rejoint le channel précédent après une reconnexion et prévient le frontend.
+    /// Tourne à part : la restauration passe elle-même par des requêtes WebSocket
+    fn restore_session(app_handle: AppHandle, app_state: AppState, hooks: ReconnectHooks, attempts: u32) {
+        tokio::spawn(async move {
+            if let Err(e) = (hooks.restore)().await {
+                println!("⚠️ Failed to restore channel after reconnection: {}", e);
+            }
+
+            println!("✅ WebSocket session restored after {} attempt(s)", attempts);
+            app_state.set_connection_state(ConnectionState::Connected);
+            Self::emit_connection_state(&app_handle, serde_json::json!({
+                "state": "restored",
+                "attempts": attempts,
+                "user": app_state.get_user(),
+                "channelId": app_state.get_current_channel(),
+            }));
+        });
+    }
+
+    fn emit_connection_state(app_handle: &AppHandle, payload: serde_json::Value) {
+        if let Err(e) = app_handle.emit("connection-state", &payload) {
+            println!("❌ Failed to emit connection-state event: {}", e);
+        }
+    }
+
     pub async fn stop(&mut self) -> Result<()> {
         println!("🛑 Stopping WebSocket connection");
         
@@ -121,10 +420,75 @@ impl WebSocketManager {
         Ok(())
     }
 
-    async fn handle_websocket_message(app_handle: &AppHandle, message: WebSocketMessage) {
+    async fn handle_websocket_message(app_handle: &AppHandle, app_state: &AppState, message: WebSocketMessage) {
         println!("🔄 Processing WebSocket message: {}", message.message_type);
         
         match message.message_type.as_str() {
+            "ChannelList" => {
+                match serde_json::from_value::<Vec<ChannelInfo>>(message.data["channels"].clone()) {
+                    Ok(channels) => {
+                        println!("📋 Received channel snapshot ({} channels)", channels.len());
+                        app_state.update_channels(channels);
+                        Self::emit_channels_snapshot(app_handle, app_state);
+                    }
+                    Err(e) => println!("❌ Failed to parse channel snapshot: {}", e),
+                }
+            },
+            "ChannelCreated" | "ChannelUpdated" => {
+                match serde_json::from_value::<ChannelInfo>(message.data["channel"].clone()) {
+                    Ok(channel) => {
+                        println!("📋 Channel {} changed ({})", channel.name, message.message_type);
+                        app_state.upsert_channel(channel.clone());
+                        if let Err(e) = app_handle.emit("channel-update", &channel) {
+                            println!("❌ Failed to emit channel-update event: {}", e);
+                        }
+                    }
+                    Err(e) => println!("❌ Failed to parse channel update: {}", e),
+                }
+            },
+            "ChannelDeleted" => {
+                if let Some(channel_id) = message.data["channel_id"].as_str().and_then(|id| Uuid::parse_str(id).ok()) {
+                    println!("🗑️ Channel {} deleted", channel_id);
+                    app_state.remove_channel(channel_id);
+                    if app_state.get_current_channel() == Some(channel_id) {
+                        app_state.set_current_channel(None);
+                    }
+                    Self::emit_channels_snapshot(app_handle, app_state);
+                }
+            },
+            "UserSpeaking" => {
+                let channel_id = message.data["channel_id"].as_str().and_then(|id| Uuid::parse_str(id).ok());
+                let user_id = message.data["user_id"].as_str().and_then(|id| Uuid::parse_str(id).ok());
+                let is_speaking = message.data["is_speaking"].as_bool().unwrap_or(false);
+
+                if let (Some(channel_id), Some(user_id)) = (channel_id, user_id) {
+                    if let Some(channel) = app_state.set_user_speaking(channel_id, user_id, is_speaking) {
+                        if let Err(e) = app_handle.emit("channel-update", &channel) {
+                            println!("❌ Failed to emit channel-update event: {}", e);
+                        }
+                    }
+                    if let Err(e) = app_handle.emit("user-speaking", &message.data) {
+                        println!("❌ Failed to emit user-speaking event: {}", e);
+                    }
+                }
+            },
+            "VoiceStateChanged" => {
+                let channel_id = message.data["channel_id"].as_str().and_then(|id| Uuid::parse_str(id).ok());
+                let user_id = message.data["user_id"].as_str().and_then(|id| Uuid::parse_str(id).ok());
+                let muted = message.data["muted"].as_bool().unwrap_or(false);
+                let deafened = message.data["deafened"].as_bool().unwrap_or(false);
+
+                if let (Some(channel_id), Some(user_id)) = (channel_id, user_id) {
+                    if let Some(channel) = app_state.set_user_voice_state(channel_id, user_id, muted, deafened) {
+                        if let Err(e) = app_handle.emit("channel-update", &channel) {
+                            println!("❌ Failed to emit channel-update event: {}", e);
+                        }
+                    }
+                    if let Err(e) = app_handle.emit("voice-state-changed", &message.data) {
+                        println!("❌ Failed to emit voice-state-changed event: {}", e);
+                    }
+                }
+            },
             "UserJoined" => {
                 println!("👤 User joined channel - triggering UI refresh");
                 if let Err(e) = app_handle.emit("user-joined", &message.data) {
@@ -149,6 +513,66 @@ impl WebSocketManager {
                     println!("✅ Emitted channel_users event to frontend");
                 }
             },
+            "AudioConfigChanged" => {
+                println!("🎚️ Server audio parameters changed: {}", message.data["config"]);
+                if let Some(size) = message.data["config"]["max_packet_size"].as_u64() {
+                    if let Some(state) = app_handle.try_state::<crate::TauriAppState>() {
+                        if let Some(udp_client) = state.get_backend_manager().get_udp_client() {
+                            udp_client.control().set_max_packet_size(size as usize);
+                        }
+                    }
+                }
+                if let Err(e) = app_handle.emit("audio-config-changed", &message.data["config"]) {
+                    println!("❌ Failed to emit audio-config-changed event: {}", e);
+                }
+            },
+            "ChannelRoutingChanged" => {
+                println!("🎚️ Channel routing changed: {}", message.data);
+                if let Some(latency_target_ms) = message.data["config"]["latency_target_ms"].as_u64() {
+                    if let Some(state) = app_handle.try_state::<crate::TauriAppState>() {
+                        state.audio_playback_manager.set_latency_target(latency_target_ms);
+                    }
+                }
+                if let Err(e) = app_handle.emit("channel-routing-changed", &message.data) {
+                    println!("❌ Failed to emit channel-routing-changed event: {}", e);
+                }
+            },
+            "EncodingProfileChanged" => {
+                match serde_json::from_value::<EncodingProfile>(message.data["profile"].clone()) {
+                    Ok(profile) => {
+                        println!("🎚️ Encoding profile for channel: {:?}", profile);
+                        if let Some(state) = app_handle.try_state::<crate::TauriAppState>() {
+                            state.audio_capture_manager.set_encoding_profile(profile);
+                        }
+                        if let Err(e) = app_handle.emit("encoding-profile-changed", &message.data) {
+                            println!("❌ Failed to emit encoding-profile-changed event: {}", e);
+                        }
+                    }
+                    Err(e) => println!("❌ Failed to parse encoding profile: {}", e),
+                }
+            },
+            "AddressChallenge" => {
+                // Notre audio arrive d'une nouvelle adresse (rebinding NAT) : le nonce, reçu
+                // ici seulement, doit repartir en UDP pour que le serveur l'adopte
+                let nonce = message.data["nonce"].as_str().and_then(|nonce| Uuid::parse_str(nonce).ok());
+                let udp_client = app_handle.try_state::<crate::TauriAppState>()
+                    .and_then(|state| state.get_backend_manager().get_udp_client());
+                match (nonce, app_state.get_user(), app_state.get_current_channel(), udp_client) {
+                    (Some(nonce), Some(user), Some(channel_id), Some(udp_client)) => {
+                        println!("🔁 Answering address challenge from server");
+                        if let Err(e) = udp_client.answer_address_challenge(user.id, channel_id, nonce).await {
+                            println!("⚠️ Failed to answer address challenge: {}", e);
+                        }
+                    }
+                    _ => println!("⚠️ Ignoring address challenge: no UDP session"),
+                }
+            },
+            "ConnectionQuality" => {
+                // Périodique : pas de log
+                if let Err(e) = app_handle.emit("connection-quality", &message.data) {
+                    println!("❌ Failed to emit connection-quality event: {}", e);
+                }
+            },
             "Authenticated" => {
                 println!("🔐 WebSocket authenticated successfully");
                 if let Err(e) = app_handle.emit("websocket-authenticated", &message.data) {
@@ -157,6 +581,12 @@ impl WebSocketManager {
                     println!("✅ Emitted websocket-authenticated event to frontend");
                 }
             },
+            "Resumed" => {
+                println!("🔐 WebSocket session resumed (channel {})", message.data["channel_id"]);
+            },
+            "JoinedChannel" | "LeftChannel" | "Ack" | "Pong" => {
+                // Réponses aux requêtes typées, déjà transmises à l'appelant
+            },
             "Error" => {
                 println!("❌ WebSocket error received");
                 if let Err(e) = app_handle.emit("websocket-error", &message.data) {
@@ -176,4 +606,65 @@ impl WebSocketManager {
             }
         }
     }
+
+    /// Envoie la liste complète des channels au frontend
+    fn emit_channels_snapshot(app_handle: &AppHandle, app_state: &AppState) {
+        if let Err(e) = app_handle.emit("channels-snapshot", app_state.get_channels()) {
+            println!("❌ Failed to emit channels-snapshot event: {}", e);
+        } else {
+            println!("✅ Emitted channels-snapshot event to frontend");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backoff() {
+        assert_eq!(reconnect_delay(1), RECONNECT_BASE_DELAY);
+        assert_eq!(reconnect_delay(2), RECONNECT_BASE_DELAY * 2);
+        assert_eq!(reconnect_delay(4), RECONNECT_BASE_DELAY * 8);
+        assert_eq!(reconnect_delay(MAX_RECONNECT_ATTEMPTS), RECONNECT_MAX_DELAY);
+        assert_eq!(reconnect_delay(u32::MAX), RECONNECT_MAX_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_request_correlation() {
+        let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<String>();
+        let pending: PendingRequests = Arc::new(Mutex::new(HashMap::new()));
+        let (audio_outgoing, _audio_rx) = mpsc::channel(AUDIO_QUEUE_SIZE);
+        let client = WebSocketClient {
+            outgoing,
+            pending: pending.clone(),
+            next_request_id: Arc::new(AtomicU64::new(0)),
+            audio_outgoing,
+            audio_sink: Arc::new(Mutex::new(None)),
+        };
+
+        // Serveur simulé : acquitte les Ping, refuse le reste
+        let server_pending = pending.clone();
+        tokio::spawn(async move {
+            while let Some(text) = outgoing_rx.recv().await {
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                let request_id = request["request_id"].clone();
+                let response = if request["action"] == "Ping" {
+                    serde_json::json!({ "event": "Ack", "request_id": request_id })
+                } else {
+                    serde_json::json!({ "event": "Error", "data": { "message": "Cannot join channel" }, "request_id": request_id })
+                };
+                let response: WebSocketMessage = serde_json::from_value(response).unwrap();
+                assert!(WebSocketClient::complete(&server_pending, &response));
+            }
+        });
+
+        let response = client.request(ClientMessage::Ping).await.unwrap();
+        assert_eq!(response.message_type, "Ack");
+
+        let join = ClientMessage::JoinChannel { channel_id: Uuid::new_v4(), password: None };
+        let error = client.request(join).await.unwrap_err();
+        assert_eq!(error.to_string(), "JoinChannel failed: Cannot join channel");
+        assert!(pending.lock().is_empty());
+    }
 }
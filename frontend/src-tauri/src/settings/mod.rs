@@ -0,0 +1,391 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use parking_lot::RwLock;
+use anyhow::{Result, Context};
+use uuid::Uuid;
+use crate::audio::{InputGainSettings, NoiseSuppressionLevel, StreamSettings};
+
+/// Version du format du fichier de réglages
+pub const SETTINGS_VERSION: u32 = 1;
+/// Nom du fichier dans le répertoire de configuration de l'application
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// Profil client persistant (périphériques, traitements, serveurs...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientSettings {
+    pub version: u32,
+    pub username: Option<String>,
+    pub servers: Vec<ServerProfile>,
+    /// Serveur utilisé lors de la dernière connexion
+    pub active_server: Option<Uuid>,
+    pub audio: AudioSettings,
+    /// Volume de lecture par utilisateur (1.0 = inchangé)
+    pub user_volumes: HashMap<Uuid, f32>,
+    pub push_to_talk: PushToTalkSettings,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            username: None,
+            servers: Vec::new(),
+            active_server: None,
+            audio: AudioSettings::default(),
+            user_volumes: HashMap::new(),
+            push_to_talk: PushToTalkSettings::default(),
+        }
+    }
+}
+
+impl ClientSettings {
+    /// Enregistre (ou met à jour) le serveur utilisé et le rend actif
+    pub fn remember_server(&mut self, url: &str, username: &str) {
+        let id = match self.servers.iter_mut().find(|s| s.url == url) {
+            Some(server) => {
+                server.username = Some(username.to_string());
+                server.id
+            }
+            None => {
+                let server = ServerProfile {
+                    id: Uuid::new_v4(),
+                    name: url.to_string(),
+                    url: url.to_string(),
+                    username: Some(username.to_string()),
+                    last_channel: None,
+                };
+                let id = server.id;
+                self.servers.push(server);
+                id
+            }
+        };
+        self.active_server = Some(id);
+        self.username = Some(username.to_string());
+    }
+
+    pub fn active_server(&self) -> Option<&ServerProfile> {
+        let id = self.active_server?;
+        self.servers.iter().find(|s| s.id == id)
+    }
+
+    pub fn server(&self, id: Uuid) -> Option<&ServerProfile> {
+        self.servers.iter().find(|s| s.id == id)
+    }
+
+    /// Ajoute ou modifie un serveur enregistré (un id nul crée une nouvelle entrée)
+    pub fn save_server(&mut self, mut server: ServerProfile) -> ServerProfile {
+        match self.servers.iter_mut().find(|s| s.id == server.id && !server.id.is_nil()) {
+            Some(existing) => *existing = server.clone(),
+            None => {
+                server.id = Uuid::new_v4();
+                self.servers.push(server.clone());
+            }
+        }
+        server
+    }
+
+    pub fn remove_server(&mut self, id: Uuid) {
+        self.servers.retain(|s| s.id != id);
+        if self.active_server == Some(id) {
+            self.active_server = None;
+        }
+    }
+
+    /// Retient le channel du serveur actif, pour le rejoindre au prochain passage
+    pub fn set_last_channel(&mut self, channel_id: Option<Uuid>) {
+        let Some(id) = self.active_server else { return };
+        if let Some(server) = self.servers.iter_mut().find(|s| s.id == id) {
+            server.last_channel = channel_id;
+        }
+    }
+
+    /// Règle le volume d'un utilisateur ; 1.0 retire l'entrée
+    pub fn set_user_volume(&mut self, user_id: Uuid, volume: f32) {
+        if !volume.is_finite() || volume == 1.0 {
+            self.user_volumes.remove(&user_id);
+        } else {
+            self.user_volumes.insert(user_id, volume.clamp(0.0, 2.0));
+        }
+    }
+
+    /// Ramène les valeurs importées dans des bornes raisonnables
+    pub fn sanitized(mut self) -> Self {
+        self.version = SETTINGS_VERSION;
+        self.audio.gain = self.audio.gain.clamped();
+        self.user_volumes.retain(|_, volume| volume.is_finite());
+        for volume in self.user_volumes.values_mut() {
+            *volume = volume.clamp(0.0, 2.0);
+        }
+        self.push_to_talk.bindings.retain(|binding| !binding.trim().is_empty());
+        if self.active_server().is_none() {
+            self.active_server = None;
+        }
+        self
+    }
+}
+
+/// Serveur enregistré
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerProfile {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub username: Option<String>,
+    /// Channel rejoint en dernier sur ce serveur
+    #[serde(default)]
+    pub last_channel: Option<Uuid>,
+}
+
+/// Choix audio persistants
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Périphériques choisis par l'utilisateur (`None` = périphérique par défaut)
+    pub input_device: Option<String>,
+    pub output_device: Option<String>,
+    pub noise_suppression: NoiseSuppressionLevel,
+    pub echo_cancellation: bool,
+    pub gain: InputGainSettings,
+    pub input_stream: StreamSettings,
+    pub output_stream: StreamSettings,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            input_device: None,
+            output_device: None,
+            noise_suppression: NoiseSuppressionLevel::default(),
+            echo_cancellation: true,
+            gain: InputGainSettings::default(),
+            input_stream: StreamSettings::default(),
+            output_stream: StreamSettings::default(),
+        }
+    }
+}
+
+/// Push-to-talk : raccourcis clavier (ex. "Space", "Ctrl+Shift+T")
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushToTalkSettings {
+    pub enabled: bool,
+    pub bindings: Vec<String>,
+}
+
+impl PushToTalkSettings {
+    /// Sans raccourci, le push-to-talk couperait le micro pour de bon
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.bindings.is_empty()
+    }
+}
+
+/// Stockage des réglages sur disque (JSON dans le répertoire de configuration)
+#[derive(Debug, Clone, Default)]
+pub struct SettingsStore {
+    path: Arc<RwLock<Option<PathBuf>>>,
+    settings: Arc<RwLock<ClientSettings>>,
+}
+
+impl SettingsStore {
+    /// Store en mémoire, sans fichier tant que `load` n'a pas été appelé
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Charge le fichier de réglages ; un fichier absent donne les valeurs par défaut
+    /// et un fichier illisible est mis de côté (`.bak`) plutôt qu'écrasé silencieusement
+    pub fn load(&self, path: PathBuf) -> ClientSettings {
+        let settings = match std::fs::read_to_string(&path) {
+            Ok(json) => match Self::parse(&json) {
+                Ok(settings) => {
+                    println!("⚙️ Settings loaded from {}", path.display());
+                    settings
+                }
+                Err(e) => {
+                    eprintln!("❌ Invalid settings file {}: {}", path.display(), e);
+                    let _ = std::fs::rename(&path, path.with_extension("json.bak"));
+                    ClientSettings::default()
+                }
+            },
+            Err(_) => {
+                println!("⚙️ No settings file at {}, using defaults", path.display());
+                ClientSettings::default()
+            }
+        };
+
+        *self.path.write() = Some(path);
+        *self.settings.write() = settings.clone();
+        settings
+    }
+
+    pub fn get(&self) -> ClientSettings {
+        self.settings.read().clone()
+    }
+
+    /// Modifie les réglages puis les enregistre
+    pub fn update<F>(&self, change: F) -> Result<ClientSettings>
+    where
+        F: FnOnce(&mut ClientSettings),
+    {
+        let settings = {
+            let mut settings = self.settings.write();
+            change(&mut settings);
+            settings.clone()
+        };
+        self.save(&settings)?;
+        Ok(settings)
+    }
+
+    /// Remplace tous les réglages (mise à jour complète ou import)
+    pub fn replace(&self, settings: ClientSettings) -> Result<ClientSettings> {
+        let settings = settings.sanitized();
+        self.update(|current| *current = settings)
+    }
+
+    pub fn export_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&*self.settings.read())
+            .context("Failed to serialize settings")
+    }
+
+    pub fn import_json(&self, json: &str) -> Result<ClientSettings> {
+        let settings = Self::parse(json)?;
+        self.replace(settings)
+    }
+
+    fn parse(json: &str) -> Result<ClientSettings> {
+        let settings: ClientSettings = serde_json::from_str(json)
+            .context("Failed to parse settings")?;
+        if settings.version > SETTINGS_VERSION {
+            anyhow::bail!("Unsupported settings version: {}", settings.version);
+        }
+        Ok(settings.sanitized())
+    }
+
+    /// Écriture atomique : fichier temporaire puis renommage
+    fn save(&self, settings: &ClientSettings) -> Result<()> {
+        let Some(path) = self.path.read().clone() else {
+            return Ok(()); // Pas encore de fichier (avant le setup de l'application)
+        };
+        Self::write_file(&path, settings)
+    }
+
+    fn write_file(path: &Path, settings: &ClientSettings) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let json = serde_json::to_string_pretty(settings)
+            .context("Failed to serialize settings")?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("voice-chat-settings-{}-{}", name, Uuid::new_v4()))
+            .join(SETTINGS_FILE)
+    }
+
+    #[test]
+    fn test_partial_file_uses_defaults() {
+        let settings = SettingsStore::parse(r#"{ "username": "alice", "audio": { "echo_cancellation": false } }"#).unwrap();
+        assert_eq!(settings.username.as_deref(), Some("alice"));
+        assert!(!settings.audio.echo_cancellation);
+        assert_eq!(settings.audio.noise_suppression, NoiseSuppressionLevel::default());
+        assert_eq!(settings.version, SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn test_update_persists_and_reloads() {
+        let path = temp_path("persist");
+        let store = SettingsStore::new();
+        assert_eq!(store.load(path.clone()), ClientSettings::default());
+
+        store.update(|s| {
+            s.remember_server("http://localhost:8080", "alice");
+            s.audio.input_device = Some("USB Mic".to_string());
+            s.push_to_talk.bindings.push("Space".to_string());
+        }).unwrap();
+
+        let reloaded = SettingsStore::new().load(path.clone());
+        assert_eq!(reloaded, store.get());
+        assert_eq!(reloaded.active_server().map(|s| s.url.as_str()), Some("http://localhost:8080"));
+
+        // Même URL : pas de doublon
+        store.update(|s| s.remember_server("http://localhost:8080", "bob")).unwrap();
+        assert_eq!(store.get().servers.len(), 1);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_and_sanitizes() {
+        let store = SettingsStore::new();
+        assert!(store.import_json("not json").is_err());
+        assert!(store.import_json(r#"{ "version": 99 }"#).is_err());
+
+        let user = Uuid::new_v4();
+        let json = format!(r#"{{ "user_volumes": {{ "{}": 5.0 }}, "active_server": "{}" }}"#, user, Uuid::new_v4());
+        let settings = store.import_json(&json).unwrap();
+        assert_eq!(settings.user_volumes[&user], 2.0);
+        assert_eq!(settings.active_server, None);
+    }
+
+    #[test]
+    fn test_user_volume_and_push_to_talk() {
+        let mut settings = ClientSettings::default();
+        let user = Uuid::new_v4();
+        settings.set_user_volume(user, 3.0);
+        assert_eq!(settings.user_volumes[&user], 2.0);
+        settings.set_user_volume(user, f32::NAN);
+        assert!(settings.user_volumes.is_empty());
+        settings.set_user_volume(user, 0.5);
+        settings.set_user_volume(user, 1.0);
+        assert!(settings.user_volumes.is_empty());
+
+        // Activé sans raccourci, le push-to-talk ne coupe pas le micro
+        settings.push_to_talk.enabled = true;
+        settings.push_to_talk.bindings.push(" ".to_string());
+        let settings = settings.sanitized();
+        assert!(!settings.push_to_talk.is_active());
+    }
+
+    #[test]
+    fn test_server_list_management() {
+        let mut settings = ClientSettings::default();
+        let saved = settings.save_server(ServerProfile {
+            id: Uuid::nil(),
+            name: "Maison".to_string(),
+            url: "http://192.168.1.10:8080".to_string(),
+            username: Some("alice".to_string()),
+            last_channel: None,
+        });
+        assert!(!saved.id.is_nil());
+
+        settings.remember_server("http://192.168.1.10:8080", "alice");
+        assert_eq!(settings.active_server, Some(saved.id));
+        let channel = Uuid::new_v4();
+        settings.set_last_channel(Some(channel));
+        assert_eq!(settings.server(saved.id).and_then(|s| s.last_channel), Some(channel));
+
+        settings.save_server(ServerProfile { name: "Salon".to_string(), ..saved.clone() });
+        assert_eq!(settings.servers.len(), 1);
+        assert_eq!(settings.servers[0].name, "Salon");
+
+        settings.remove_server(saved.id);
+        assert!(settings.servers.is_empty());
+        assert_eq!(settings.active_server, None);
+    }
+}
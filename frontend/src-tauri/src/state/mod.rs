@@ -11,6 +11,7 @@ pub struct AppState {
     pub current_channel: Arc<RwLock<Option<Uuid>>>,
     pub audio_devices: Arc<RwLock<AudioDevices>>,
     pub connection_state: Arc<RwLock<ConnectionState>>,
+    pub voice_state: Arc<RwLock<VoiceState>>,
 }
 
 impl AppState {
@@ -21,6 +22,7 @@ impl AppState {
             current_channel: Arc::new(RwLock::new(None)),
             audio_devices: Arc::new(RwLock::new(AudioDevices::default())),
             connection_state: Arc::new(RwLock::new(ConnectionState::Disconnected)),
+            voice_state: Arc::new(RwLock::new(VoiceState::default())),
         }
     }
 
@@ -49,6 +51,39 @@ impl AppState {
         self.channels.read().clone()
     }
 
+    /// Ajoute ou remplace un channel (diff reçu par WebSocket)
+    pub fn upsert_channel(&self, channel: ChannelInfo) {
+        let mut channels = self.channels.write();
+        match channels.iter_mut().find(|c| c.id == channel.id) {
+            Some(existing) => *existing = channel,
+            None => channels.push(channel),
+        }
+    }
+
+    /// Supprime un channel de la liste
+    pub fn remove_channel(&self, channel_id: Uuid) {
+        self.channels.write().retain(|c| c.id != channel_id);
+    }
+
+    /// Met à jour l'état de parole d'un utilisateur et retourne le channel modifié
+    pub fn set_user_speaking(&self, channel_id: Uuid, user_id: Uuid, is_speaking: bool) -> Option<ChannelInfo> {
+        let mut channels = self.channels.write();
+        let channel = channels.iter_mut().find(|c| c.id == channel_id)?;
+        let user = channel.users.iter_mut().find(|u| u.id == user_id)?;
+        user.is_speaking = is_speaking;
+        Some(channel.clone())
+    }
+
+    /// Met à jour le micro et le son d'un utilisateur et retourne le channel modifié
+    pub fn set_user_voice_state(&self, channel_id: Uuid, user_id: Uuid, muted: bool, deafened: bool) -> Option<ChannelInfo> {
+        let mut channels = self.channels.write();
+        let channel = channels.iter_mut().find(|c| c.id == channel_id)?;
+        let user = channel.users.iter_mut().find(|u| u.id == user_id)?;
+        user.mic_enabled = !(muted || deafened);
+        user.speaker_enabled = !deafened;
+        Some(channel.clone())
+    }
+
     /// Définit le channel actuel
     pub fn set_current_channel(&self, channel_id: Option<Uuid>) {
         *self.current_channel.write() = channel_id;
@@ -68,6 +103,16 @@ impl AppState {
     pub fn get_connection_state(&self) -> ConnectionState {
         *self.connection_state.read()
     }
+
+    /// Définit l'état micro/son choisi par l'utilisateur
+    pub fn set_voice_state(&self, voice_state: VoiceState) {
+        *self.voice_state.write() = voice_state;
+    }
+
+    /// Obtient l'état micro/son choisi par l'utilisateur
+    pub fn get_voice_state(&self) -> VoiceState {
+        *self.voice_state.read()
+    }
 }
 
 impl Default for AppState {
@@ -108,6 +153,20 @@ pub struct UserInfo {
     pub speaker_enabled: bool,
 }
 
+/// Micro et son coupés par l'utilisateur lui-même (la sourdine coupe aussi le micro)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoiceState {
+    pub muted: bool,
+    pub deafened: bool,
+}
+
+impl VoiceState {
+    /// La capture du micro doit tourner
+    pub fn capture_enabled(&self) -> bool {
+        !(self.muted || self.deafened)
+    }
+}
+
 /// Périphériques audio disponibles
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioDevices {
@@ -128,12 +187,46 @@ impl Default for AudioDevices {
     }
 }
 
+impl AudioDevices {
+    /// Reprend les capacités déjà connues des périphériques toujours présents
+    pub fn keep_capabilities(&mut self, previous: &AudioDevices) {
+        let pairs = self.input_devices.iter_mut().map(|d| (d, &previous.input_devices))
+            .chain(self.output_devices.iter_mut().map(|d| (d, &previous.output_devices)));
+        for (device, known) in pairs {
+            if let Some(known) = known.iter().find(|k| k.id == device.id) {
+                device.capabilities = known.capabilities.clone();
+            }
+        }
+    }
+}
+
 /// Informations d'un périphérique audio
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioDevice {
     pub name: String,
     pub id: String,
     pub is_default: bool,
+    /// Configurations supportées (remplies lors d'un scan explicite)
+    #[serde(default)]
+    pub capabilities: DeviceCapabilities,
+}
+
+/// Configurations de stream supportées par un périphérique
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceCapabilities {
+    pub sample_rates: Vec<u32>,
+    pub channel_counts: Vec<u16>,
+    /// Formats gérés, par nom ("f32", "i16"...)
+    pub sample_formats: Vec<String>,
+    /// Tailles de buffer acceptées, en frames (inconnues pour certains backends)
+    pub buffer_size: Option<BufferSizeRange>,
+}
+
+/// Plage de tailles de buffer en frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferSizeRange {
+    pub min: u32,
+    pub max: u32,
 }
 
 /// État de la connexion au backend
@@ -142,6 +235,8 @@ pub enum ConnectionState {
     Disconnected,
     Connecting,
     Connected,
+    /// Connexion perdue, reconnexion automatique en cours
+    Reconnecting,
     Error,
 }
 
@@ -158,6 +253,10 @@ impl ConnectionState {
         matches!(self, ConnectionState::Disconnected)
     }
 
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(self, ConnectionState::Reconnecting)
+    }
+
     pub fn is_error(&self) -> bool {
         matches!(self, ConnectionState::Error)
     }
@@ -191,6 +290,39 @@ mod tests {
         assert_eq!(retrieved_user.unwrap().username, user.username);
     }
 
+    #[test]
+    fn test_channel_diffs() {
+        let state = AppState::new();
+        let user_id = Uuid::new_v4();
+        let channel = ChannelInfo {
+            id: Uuid::new_v4(),
+            name: "General".to_string(),
+            description: None,
+            user_count: 1,
+            users: vec![UserInfo {
+                id: user_id,
+                username: "test_user".to_string(),
+                is_speaking: false,
+                mic_enabled: true,
+                speaker_enabled: true,
+            }],
+        };
+
+        state.upsert_channel(channel.clone());
+        state.upsert_channel(channel.clone());
+        assert_eq!(state.get_channels().len(), 1);
+
+        let updated = state.set_user_speaking(channel.id, user_id, true).unwrap();
+        assert!(updated.users[0].is_speaking);
+
+        let updated = state.set_user_voice_state(channel.id, user_id, false, true).unwrap();
+        assert!(!updated.users[0].mic_enabled);
+        assert!(!updated.users[0].speaker_enabled);
+
+        state.remove_channel(channel.id);
+        assert!(state.get_channels().is_empty());
+    }
+
     #[test]
     fn test_connection_state() {
         let state = ConnectionState::Disconnected;
@@ -200,5 +332,9 @@ mod tests {
         let state = ConnectionState::Connected;
         assert!(state.is_connected());
         assert!(!state.is_disconnected());
+
+        let state = ConnectionState::Reconnecting;
+        assert!(state.is_reconnecting());
+        assert!(!state.is_connected());
     }
 }
\ No newline at end of file
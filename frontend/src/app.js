@@ -25,48 +25,12 @@ class VoiceChatApp {
     
     this.pages = new Map();
     this.eventListeners = new Map();
-    
-    this.initialize();
-  }
 
-  // Méthode pour gérer les événements de mise à jour des channels
-  async handleChannelUpdated(eventData) {
-    console.log('🔄 Channel updated event received:', eventData);
-    console.log('🔄 [DEBUG] handleChannelUpdated called with:', JSON.stringify(eventData, null, 2));
-    
-    // Adapter les noms de propriétés du backend (snake_case) vers frontend (camelCase)
-    const channelId = eventData.channel_id || eventData.channelId;
-    const userId = eventData.user_id || eventData.userId;
+    // Push-to-talk bindings from the settings, and whether one is held down
+    this.pushToTalk = { enabled: false, bindings: [] };
+    this.talkKeyPressed = false;
     
-    console.log(`📊 Event details - Channel: ${channelId}, User: ${userId}`);
-    console.log('� [DEBUG] Current channel:', this.appState.currentChannel);
-    console.log('🔍 [DEBUG] Current channel ID:', this.appState.currentChannel?.id);
-    console.log('🔍 [DEBUG] Event channel ID:', channelId);
-    console.log('🔍 [DEBUG] IDs match?', this.appState.currentChannel?.id === channelId);
-    console.log('�🔄 [DEBUG] About to call refreshChannelsList...');
-    
-    // Toujours rafraîchir la liste des channels pour mettre à jour les comptes d'utilisateurs
-    console.log('📡 Refreshing channels list to update user counts...');
-    await this.refreshChannelsList();
-    console.log('✅ [DEBUG] refreshChannelsList completed');
-    
-    // Si on est dans le channel qui a été mis à jour, on recharge aussi ses informations
-    if (this.appState.currentChannel && channelId === this.appState.currentChannel.id) {
-      console.log('📡 Also refreshing current channel data...');
-      
-      const mainPage = this.pages.get('main');
-      if (mainPage && mainPage.refreshChannelData) {
-        console.log('🔄 [DEBUG] Calling refreshChannelData...');
-        await mainPage.refreshChannelData();
-        console.log('✅ Current channel data refreshed successfully');
-      } else {
-        console.log('ℹ️ Main page not available or no refresh method');
-      }
-    } else {
-      console.log('ℹ️ Event not for current channel, but channels list updated');
-    }
-    
-    console.log('✅ [DEBUG] handleChannelUpdated completed');
+    this.initialize();
   }
 
   /**
@@ -151,6 +115,10 @@ class VoiceChatApp {
       this.handleLogin(e.detail);
     });
 
+    document.addEventListener('app:switch-server', (e) => {
+      this.handleSwitchServer(e.detail.serverId);
+    });
+
     document.addEventListener('app:logout', () => {
       this.handleLogout();
     });
@@ -171,6 +139,12 @@ class VoiceChatApp {
       this.handleRefreshChannels();
     });
 
+    // Push-to-talk keys (the capture only transmits while one is held)
+    document.addEventListener('keydown', (e) => this.handleTalkKey(e, true));
+    document.addEventListener('keyup', (e) => this.handleTalkKey(e, false));
+    window.addEventListener('blur', () => this.setTalkKeyPressed(false));
+    this.loadPushToTalk();
+
     // Listen for Tauri events
     this.setupTauriEventListeners();
 
@@ -200,16 +174,18 @@ class VoiceChatApp {
           this.handleChannelUpdate(event.payload);
         });
 
+        await listen('channels-snapshot', (event) => {
+          console.log('📡 Event: channels-snapshot', event.payload);
+          this.handleChannelsSnapshot(event.payload);
+        });
+
+        // Membership changes are applied from the ChannelUpdated push (channel-update)
         await listen('user-joined', (event) => {
           console.log('📡 Event: user-joined', event.payload);
-          console.log('🔄 Calling handleChannelUpdated from user-joined event');
-          this.handleChannelUpdated(event.payload);
         });
 
         await listen('user-left', (event) => {
           console.log('📡 Event: user-left', event.payload);
-          console.log('🔄 Calling handleChannelUpdated from user-left event');
-          this.handleChannelUpdated(event.payload);
         });
 
         await listen('channel_users', (event) => {
@@ -217,11 +193,36 @@ class VoiceChatApp {
           this.handleChannelUsers(event.payload);
         });
 
+        // Microphone levels (~30 Hz): no logging
         await listen('audio-level', (event) => {
-          console.log('📡 Event: audio-level', event.payload);
           this.handleAudioLevel(event.payload);
         });
 
+        // Connection quality of channel members (every few seconds): no logging
+        await listen('connection-quality', (event) => {
+          this.handleConnectionQuality(event.payload);
+        });
+
+        await listen('voice-state-changed', (event) => {
+          console.log('📡 Event: voice-state-changed', event.payload);
+          this.handleVoiceStateChanged(event.payload);
+        });
+
+        await listen('audio-devices-changed', (event) => {
+          console.log('📡 Event: audio-devices-changed', event.payload);
+          this.handleAudioDevicesChanged(event.payload);
+        });
+
+        await listen('connection-state', (event) => {
+          console.log('📡 Event: connection-state', event.payload);
+          this.handleConnectionState(event.payload);
+        });
+
+        await listen('audio-device-switched', (event) => {
+          console.log('📡 Event: audio-device-switched', event.payload);
+          this.handleAudioDeviceSwitched(event.payload);
+        });
+
         console.log('✅ Tauri event listeners setup complete');
       } else {
         console.warn('⚠️ Tauri not available (running in browser mode)');
@@ -319,6 +320,40 @@ class VoiceChatApp {
     }
   }
 
+  /**
+   * Switch to a saved server (the backend tears down the previous session)
+   */
+  async handleSwitchServer(serverId) {
+    try {
+      this.setConnectionStatus('connecting');
+      this.appState.currentChannel = null;
+      
+      const result = await window.tauriAPI.switchServer(serverId);
+      if (!result.success) {
+        throw new Error(result.error || 'Server switch failed');
+      }
+      
+      this.appState.user = result.user;
+      this.appState.channels = result.channels || [];
+      localStorage.setItem('lastServerUrl', result.server.url);
+      await this.saveUserSession(result.user, result.server.url);
+      
+      this.setConnectionStatus('connected');
+      this.showNotification(`Connected to ${result.server.name}`, 'success');
+      await this.navigateTo('main');
+      
+      // Rejoin the channel last used on this server
+      const lastChannel = this.appState.channels.find(c => c.id === result.lastChannel);
+      if (lastChannel) {
+        await this.handleJoinChannel(lastChannel);
+      }
+    } catch (error) {
+      console.error('❌ Server switch failed:', error);
+      this.setConnectionStatus('error');
+      this.showNotification(`Connection failed: ${error.message}`, 'error');
+    }
+  }
+
   /**
    * Handle user logout
    */
@@ -373,18 +408,11 @@ class VoiceChatApp {
       console.log('📡 Join channel result:', result);
       
       if (result.success) {
-        console.log('✅ Successfully joined channel, refreshing data...');
+        console.log('✅ Successfully joined channel');
         this.showNotification(`Joined ${channelData.name}`, 'success');
         
-        // Petit délai pour permettre au backend de mettre à jour ses données
-        console.log('⏳ Waiting for backend to update...');
-        await new Promise(resolve => setTimeout(resolve, 100));
-        
-        // Refresh channels to get updated user counts
-        console.log('🔄 Refreshing channels list to get updated user counts...');
-        await this.refreshChannelsList();
-        
-        // Find the updated channel data
+        // The backend pushes ChannelUpdated with the new member list; until it
+        // arrives, show the channel as we last knew it
         const updatedChannel = this.appState.channels.find(c => c.id === channelData.id);
         if (updatedChannel) {
           console.log('📋 Using updated channel data:', updatedChannel);
@@ -439,10 +467,6 @@ class VoiceChatApp {
         this.appState.currentChannel = null;
         this.showNotification(`Left ${channelName}`, 'info');
         
-        // Refresh channels list to update user counts
-        console.log('🔄 Refreshing channels list after leaving channel...');
-        await this.refreshChannelsList();
-        
         // Update main page
         const mainPage = this.pages.get('main');
         if (mainPage) {
@@ -473,7 +497,58 @@ class VoiceChatApp {
   }
 
   /**
-   * Handle channel updates
+   * Handle automatic WebSocket reconnection progress
+   */
+  handleConnectionState(data) {
+    switch (data.state) {
+      case 'reconnecting':
+        this.setConnectionStatus('reconnecting');
+        if (data.attempt === 1) {
+          this.showNotification('Connection lost, reconnecting...', 'warning');
+        }
+        break;
+      case 'restored':
+        // The server may have recreated our user with a new id
+        if (data.user) {
+          this.appState.user = data.user;
+        }
+        if (!data.channelId) {
+          this.appState.currentChannel = null;
+        }
+        this.setConnectionStatus('connected');
+        this.showNotification('Connection restored', 'success');
+        break;
+      case 'failed':
+        this.setConnectionStatus('error');
+        this.showNotification('Unable to reconnect to the server', 'error');
+        break;
+    }
+  }
+
+  /**
+   * Handle the full channel list pushed by the backend
+   */
+  handleChannelsSnapshot(channels) {
+    console.log('Channels snapshot received:', channels);
+
+    this.appState.channels = channels;
+
+    // Le channel courant a pu être supprimé ou modifié
+    if (this.appState.currentChannel) {
+      this.appState.currentChannel = channels.find(c => c.id === this.appState.currentChannel.id) || null;
+    }
+
+    const mainPage = this.pages.get('main');
+    if (mainPage) {
+      mainPage.updateChannels(this.appState.channels);
+      if (this.appState.currentChannel) {
+        mainPage.updateChannel(this.appState.currentChannel);
+      }
+    }
+  }
+
+  /**
+   * Handle a single channel update pushed by the backend
    */
   handleChannelUpdate(channelData) {
     console.log('Channel update received:', channelData);
@@ -547,6 +622,30 @@ class VoiceChatApp {
     }
   }
 
+  /**
+   * Handle periodic connection quality of the current channel's members
+   */
+  handleConnectionQuality(data) {
+    if (data.channel_id !== this.appState.currentChannel?.id) return;
+
+    const mainPage = this.pages.get('main');
+    if (mainPage) {
+      mainPage.updateConnectionQuality(data.users || []);
+    }
+  }
+
+  /**
+   * Keep our own audio controls in sync with the state the server broadcast
+   */
+  handleVoiceStateChanged(data) {
+    if (data.user_id !== this.appState.user?.id) return;
+
+    const mainPage = this.pages.get('main');
+    if (mainPage) {
+      mainPage.applyVoiceState(data);
+    }
+  }
+
   /**
    * Handle audio level updates
    */
@@ -558,6 +657,69 @@ class VoiceChatApp {
     }
   }
 
+  async loadPushToTalk() {
+    const settings = await window.tauriAPI.getSettings();
+    if (settings?.push_to_talk) {
+      this.pushToTalk = settings.push_to_talk;
+    }
+  }
+
+  async setPushToTalk(settings) {
+    const result = await window.tauriAPI.setPushToTalk(settings);
+    if (result.success) {
+      this.pushToTalk = result.settings;
+      this.setTalkKeyPressed(false);
+    }
+    return result;
+  }
+
+  /**
+   * Match a key event against the push-to-talk bindings ("Space", "Ctrl+Shift+T")
+   */
+  handleTalkKey(event, pressed) {
+    if (!this.pushToTalk.enabled) return;
+    if (pressed && event.target.closest?.('input, textarea, select')) return;
+
+    const key = event.code.replace(/^(Key|Digit)/, '').toLowerCase();
+    const matches = this.pushToTalk.bindings.some(binding => {
+      const parts = binding.split('+').map(part => part.trim().toLowerCase());
+      if (parts.pop() !== key) return false;
+      // Modifiers are often released first, so only the key itself ends the transmission
+      if (!pressed) return true;
+      return ['ctrl', 'alt', 'shift', 'meta'].every(modifier => parts.includes(modifier) === event[`${modifier}Key`]);
+    });
+
+    if (matches) {
+      event.preventDefault();
+      this.setTalkKeyPressed(pressed);
+    }
+  }
+
+  setTalkKeyPressed(pressed) {
+    if (this.talkKeyPressed === pressed) return;
+    this.talkKeyPressed = pressed;
+    window.tauriAPI.setPushToTalkPressed(pressed);
+  }
+
+  /**
+   * Handle audio devices plugged in / unplugged
+   */
+  handleAudioDevicesChanged(data) {
+    this.appState.audioDevices = data.devices;
+  }
+
+  /**
+   * Handle automatic audio device failover / restore
+   */
+  handleAudioDeviceSwitched(data) {
+    const kind = data.direction === 'input' ? 'Microphone' : 'Output';
+    if (data.reason === 'unplugged') {
+      this.showNotification(`${kind} "${data.from}" disconnected, using default device`, 'warning');
+    } else {
+      this.showNotification(`${kind} "${data.to}" reconnected`, 'info');
+    }
+  }
+
   /**
    * Set connection status
    */
@@ -25,7 +25,8 @@ export default class LoginPage {
     document.body.classList.add('app-ready');
     
     this.bindEvents();
-    this.loadSavedData();
+    await this.loadSavedData();
+    this.loadSavedServers();
     
     // Pre-fill form if params provided
     if (params.username) {
@@ -85,6 +86,9 @@ export default class LoginPage {
             </div>
           </form>
           
+          <!-- Saved servers -->
+          <div class="saved-servers" id="savedServers"></div>
+          
           <!-- Status -->
           <div class="login-status" id="loginStatus"></div>
         </div>
@@ -186,6 +190,49 @@ export default class LoginPage {
     }
   }
 
+  async loadSavedServers() {
+    const container = $('#savedServers');
+    if (!container) return;
+    
+    const servers = await tauriAPI.listServers();
+    if (servers.length === 0) {
+      container.innerHTML = '';
+      return;
+    }
+    
+    container.innerHTML = `
+      <h4 class="section-title">Saved Servers</h4>
+      ${servers.map(server => `
+        <div class="device-item saved-server" data-server-id="${server.id}">
+          <span class="status-indicator server-status" title="Checking..."></span>
+          <span class="device-name">${server.name}${server.username ? ` · ${server.username}` : ''}</span>
+          <button type="button" class="btn btn-sm btn-outline" data-action="connect">Connect</button>
+          <button type="button" class="btn btn-sm" data-action="remove" title="Remove">✕</button>
+        </div>
+      `).join('')}
+    `;
+    
+    container.querySelectorAll('.saved-server').forEach(item => {
+      const serverId = item.getAttribute('data-server-id');
+      addListener(item.querySelector('[data-action="connect"]'), 'click', () => {
+        document.dispatchEvent(new CustomEvent('app:switch-server', { detail: { serverId } }));
+      });
+      addListener(item.querySelector('[data-action="remove"]'), 'click', async () => {
+        await tauriAPI.removeServer(serverId);
+        this.loadSavedServers();
+      });
+    });
+    
+    // Status dots from /health probes
+    const probes = await tauriAPI.probeServers();
+    probes.forEach(({ serverId, status }) => {
+      const dot = container.querySelector(`.saved-server[data-server-id="${serverId}"] .server-status`);
+      if (!dot) return;
+      dot.classList.add(status.online ? 'status-online' : 'status-offline');
+      dot.title = status.online ? `Online (${status.latency_ms} ms)` : `Offline: ${status.error}`;
+    });
+  }
+
   validateUsername(username) {
     if (!username) return false;
     
@@ -240,9 +287,12 @@ export default class LoginPage {
     }
   }
 
-  loadSavedData() {
-    const lastUsername = storage.get('lastUsername');
-    const lastServerUrl = storage.get('lastServerUrl');
+  async loadSavedData() {
+    // Persisted settings profile first, browser storage as fallback
+    const settings = await tauriAPI.getSettings();
+    const activeServer = settings?.servers.find(server => server.id === settings.active_server);
+    const lastUsername = settings?.username || storage.get('lastUsername');
+    const lastServerUrl = activeServer?.url || storage.get('lastServerUrl');
     
     if (lastUsername) {
       const usernameInput = $('#username');
@@ -277,6 +327,44 @@ export default class LoginPage {
               </div>
             </div>
             
+            <div class="form-group">
+              <label for="noiseSuppressionSelect" class="form-label">Noise Suppression</label>
+              <select id="noiseSuppressionSelect" class="form-input">
+                <option value="off">Off</option>
+                <option value="low">Low</option>
+                <option value="medium">Medium</option>
+                <option value="high">High</option>
+              </select>
+            </div>
+            
+            <div class="form-group">
+              <label for="inputGainRange" class="form-label">Input Gain: <span id="inputGainValue">0</span> dB</label>
+              <input type="range" id="inputGainRange" min="-30" max="30" step="1" value="0">
+              <label class="form-label">
+                <input type="checkbox" id="agcToggle">
+                Automatic Gain Control
+              </label>
+            </div>
+            
+            <div class="form-group">
+              <label class="form-label">
+                <input type="checkbox" id="echoCancellationToggle">
+                Echo Cancellation
+              </label>
+            </div>
+            
+            <div class="form-group" id="streamSettings"></div>
+            
+            <div class="form-group">
+              <label class="form-label">Settings Profile</label>
+              <div class="audio-test-controls">
+                <button type="button" class="btn btn-secondary" id="exportSettingsBtn">Export</button>
+                <button type="button" class="btn btn-secondary" id="importSettingsBtn">Import</button>
+                <button type="button" class="btn btn-secondary" id="resetSettingsBtn">Reset</button>
+                <input type="file" id="importSettingsFile" accept="application/json,.json" hidden>
+              </div>
+            </div>
+            
             <div class="audio-devices" id="audioDevicesList">
               <p>Loading audio devices...</p>
             </div>
@@ -291,6 +379,11 @@ export default class LoginPage {
     const modal = $('#audioSettingsModal');
     const closeBtn = $('#closeSettingsBtn');
     const testBtn = $('#testAudioBtn');
+    const noiseSelect = $('#noiseSuppressionSelect');
+    const echoToggle = $('#echoCancellationToggle');
+    const gainRange = $('#inputGainRange');
+    const gainValue = $('#inputGainValue');
+    const agcToggle = $('#agcToggle');
     
     // Bind events
     if (closeBtn) {
@@ -301,6 +394,36 @@ export default class LoginPage {
       addListener(testBtn, 'click', () => this.testAudio());
     }
     
+    if (noiseSelect) {
+      noiseSelect.value = await tauriAPI.getNoiseSuppression();
+      addListener(noiseSelect, 'change', () => this.setNoiseSuppression(noiseSelect.value));
+    }
+    
+    if (echoToggle) {
+      echoToggle.checked = await tauriAPI.getEchoCancellation();
+      addListener(echoToggle, 'change', () => this.setEchoCancellation(echoToggle.checked));
+    }
+    
+    if (gainRange && agcToggle) {
+      const gainSettings = await tauriAPI.getInputGainSettings();
+      gainRange.value = gainSettings.manual_gain_db;
+      if (gainValue) gainValue.textContent = gainSettings.manual_gain_db;
+      agcToggle.checked = gainSettings.agc_enabled;
+      
+      const applyGain = () => {
+        if (gainValue) gainValue.textContent = gainRange.value;
+        tauriAPI.setInputGainSettings({
+          ...gainSettings,
+          manual_gain_db: Number(gainRange.value),
+          agc_enabled: agcToggle.checked
+        });
+      };
+      addListener(gainRange, 'input', applyGain);
+      addListener(agcToggle, 'change', applyGain);
+    }
+    
+    this.bindSettingsProfile();
+    
     // Close on outside click
     if (modal) {
       addListener(modal, 'click', (e) => {
@@ -333,6 +456,7 @@ export default class LoginPage {
             ${devices.input.map(device => `
               <div class="device-item">
                 <span class="device-name">${device.name}</span>
+                <span class="device-capabilities">${this.formatCapabilities(device.capabilities)}</span>
                 <button class="btn btn-sm btn-outline" data-device-id="${device.id}" data-device-type="input">
                   Select
                 </button>
@@ -350,6 +474,7 @@ export default class LoginPage {
             ${devices.output.map(device => `
               <div class="device-item">
                 <span class="device-name">${device.name}</span>
+                <span class="device-capabilities">${this.formatCapabilities(device.capabilities)}</span>
                 <button class="btn btn-sm btn-outline" data-device-id="${device.id}" data-device-type="output">
                   Select
                 </button>
@@ -365,6 +490,9 @@ export default class LoginPage {
       
       devicesList.innerHTML = devicesHTML;
       
+      await this.loadStreamSettings('input', devices.input);
+      await this.loadStreamSettings('output', devices.output);
+      
       // Bind device selection events
       const deviceButtons = devicesList.querySelectorAll('button[data-device-id]');
       deviceButtons.forEach(button => {
@@ -390,6 +518,114 @@ export default class LoginPage {
     }
   }
 
+  formatCapabilities(capabilities) {
+    if (!capabilities || capabilities.sample_rates.length === 0) return '';
+    const rates = capabilities.sample_rates.map(rate => `${rate / 1000}k`).join('/');
+    const buffer = capabilities.buffer_size
+      ? ` · ${capabilities.buffer_size.min}-${capabilities.buffer_size.max} frames`
+      : '';
+    return `${rates} Hz · ${capabilities.channel_counts.join('/')} ch · ${capabilities.sample_formats.join('/')}${buffer}`;
+  }
+
+  // Stream config selectors (sample rate, format, buffer), options taken from device capabilities
+  async loadStreamSettings(direction, devices) {
+    const container = $('#streamSettings');
+    if (!container) return;
+    
+    const settings = await tauriAPI.getStreamSettings(direction);
+    const union = (key) => [...new Set(devices.flatMap(d => d.capabilities?.[key] || []))];
+    const rates = union('sample_rates').sort((a, b) => a - b);
+    const formats = union('sample_formats');
+    const option = (value, label, selected) =>
+      `<option value="${value}" ${selected ? 'selected' : ''}>${label}</option>`;
+    const buffer = settings.low_latency ? 'low' : (settings.buffer_size ?? '');
+    
+    container.insertAdjacentHTML('beforeend', `
+      <label class="form-label">${direction === 'input' ? 'Input' : 'Output'} Stream</label>
+      <div class="stream-settings" data-direction="${direction}">
+        <select class="form-input" data-key="sample_rate">
+          ${option('', 'Default rate', settings.sample_rate == null)}
+          ${rates.map(rate => option(rate, `${rate} Hz`, settings.sample_rate === rate)).join('')}
+        </select>
+        <select class="form-input" data-key="sample_format">
+          ${option('', 'Default format', settings.sample_format == null)}
+          ${formats.map(format => option(format, format, settings.sample_format === format)).join('')}
+        </select>
+        <select class="form-input" data-key="buffer_size">
+          ${option('', 'Default buffer', buffer === '')}
+          ${option('low', 'Low latency', buffer === 'low')}
+          ${[256, 512, 1024, 2048].map(size => option(size, `${size} frames`, buffer === size)).join('')}
+        </select>
+      </div>
+    `);
+    
+    const group = container.querySelector(`.stream-settings[data-direction="${direction}"]`);
+    group.querySelectorAll('select').forEach(select => {
+      addListener(select, 'change', () => {
+        const value = (key) => group.querySelector(`[data-key="${key}"]`).value;
+        const bufferValue = value('buffer_size');
+        tauriAPI.setStreamSettings(direction, {
+          ...settings,
+          sample_rate: value('sample_rate') ? Number(value('sample_rate')) : null,
+          sample_format: value('sample_format') || null,
+          buffer_size: bufferValue && bufferValue !== 'low' ? Number(bufferValue) : null,
+          low_latency: bufferValue === 'low'
+        });
+      });
+    });
+  }
+
+  bindSettingsProfile() {
+    const exportBtn = $('#exportSettingsBtn');
+    const importBtn = $('#importSettingsBtn');
+    const importFile = $('#importSettingsFile');
+    const resetBtn = $('#resetSettingsBtn');
+    
+    if (exportBtn) {
+      addListener(exportBtn, 'click', async () => {
+        const result = await tauriAPI.exportSettings();
+        if (!result.success) {
+          this.showStatus('Failed to export settings: ' + result.error, 'error');
+          return;
+        }
+        const url = URL.createObjectURL(new Blob([result.json], { type: 'application/json' }));
+        const link = document.createElement('a');
+        link.href = url;
+        link.download = 'voice-chat-settings.json';
+        link.click();
+        URL.revokeObjectURL(url);
+      });
+    }
+    
+    if (importBtn && importFile) {
+      addListener(importBtn, 'click', () => importFile.click());
+      addListener(importFile, 'change', async () => {
+        const file = importFile.files[0];
+        if (!file) return;
+        const result = await tauriAPI.importSettings(await file.text());
+        this.afterSettingsChange(result, 'Settings imported');
+      });
+    }
+    
+    if (resetBtn) {
+      addListener(resetBtn, 'click', async () => {
+        this.afterSettingsChange(await tauriAPI.resetSettings(), 'Settings reset');
+      });
+    }
+  }
+
+  // Reopen the modal so every control reflects the new profile
+  async afterSettingsChange(result, message) {
+    if (!result.success) {
+      this.showStatus(`${message} failed: ${result.error}`, 'error');
+      return;
+    }
+    this.closeAudioSettings();
+    await this.loadSavedData();
+    await this.showAudioSettings();
+    this.showStatus(message, 'success');
+  }
+
   closeAudioSettings() {
     const modal = $('#audioSettingsModal');
     if (modal) modal.remove();
@@ -434,6 +670,24 @@ export default class LoginPage {
     }
   }
 
+  async setNoiseSuppression(level) {
+    const result = await tauriAPI.setNoiseSuppression(level);
+    if (result.success) {
+      this.showStatus(`Noise suppression: ${level}`, 'success');
+    } else {
+      this.showStatus('Failed to set noise suppression: ' + result.error, 'error');
+    }
+  }
+
+  async setEchoCancellation(enabled) {
+    const result = await tauriAPI.setEchoCancellation(enabled);
+    if (result.success) {
+      this.showStatus(`Echo cancellation ${enabled ? 'enabled' : 'disabled'}`, 'success');
+    } else {
+      this.showStatus('Failed to set echo cancellation: ' + result.error, 'error');
+    }
+  }
+
   async selectOutputDevice(deviceId) {
     try {
       console.log('Selecting output device:', deviceId);
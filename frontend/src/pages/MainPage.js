@@ -9,6 +9,11 @@ export default class MainPage {
     this.app = app;
     this.currentChannel = null;
     this.unsubscribeState = null;
+    this.latencyTimer = null;
+    // user id -> latest connection quality pushed by the server
+    this.connectionQuality = new Map();
+    // user id -> saved playback volume (1.0 when absent)
+    this.userVolumes = new Map();
   }
 
   /**
@@ -28,6 +33,8 @@ export default class MainPage {
     this.bindEvents();
     this.setupStateListeners();
     this.loadInitialData();
+    this.loadServerSwitcher();
+    this.loadUserVolumes();
   }
 
   /**
@@ -51,6 +58,7 @@ export default class MainPage {
             <div class="sidebar-header">
               <div class="server-info">
                 <h2 class="server-name">Voice Chat Server</h2>
+                <select class="form-input server-switcher" id="serverSwitcher" title="Switch server"></select>
                 <div class="user-info" id="userInfo"></div>
               </div>
             </div>
@@ -90,6 +98,35 @@ export default class MainPage {
     }
   }
 
+  // Quick switch between saved servers
+  async loadServerSwitcher() {
+    const switcher = $('#serverSwitcher');
+    if (!switcher) return;
+    
+    const [servers, settings] = await Promise.all([tauriAPI.listServers(), tauriAPI.getSettings()]);
+    if (servers.length < 2) {
+      switcher.style.display = 'none';
+      return;
+    }
+    
+    const activeId = settings?.active_server;
+    switcher.innerHTML = servers.map(server => `
+      <option value="${server.id}" ${server.id === activeId ? 'selected' : ''}>${server.name}</option>
+    `).join('');
+    
+    addListener(switcher, 'change', () => {
+      document.dispatchEvent(new CustomEvent('app:switch-server', { detail: { serverId: switcher.value } }));
+    });
+  }
+
+  async loadUserVolumes() {
+    const settings = await tauriAPI.getSettings();
+    this.userVolumes = new Map(Object.entries(settings?.user_volumes || {}));
+    if (this.currentChannel) {
+      this.updateUsersList(this.currentChannel.users || []);
+    }
+  }
+
   unbindEvents() {
     // Remove event listeners if needed
     // DOM elements will be removed anyway
@@ -100,6 +137,10 @@ export default class MainPage {
       this.unsubscribeState();
       this.unsubscribeState = null;
     }
+    if (this.latencyTimer) {
+      clearInterval(this.latencyTimer);
+      this.latencyTimer = null;
+    }
   }
 
   async loadInitialData() {
@@ -170,10 +211,11 @@ export default class MainPage {
     
     const appState = this.app.getState();
     const audioSettings = appState.audioSettings;
+    const pushToTalk = this.app.pushToTalk;
     
     audioControlsDiv.innerHTML = `
       <div class="audio-controls-grid">
-        <button class="btn btn-icon audio-control ${audioSettings.micEnabled ? 'active' : 'muted'}" 
+        <button class="btn btn-icon audio-control ${audioSettings.micEnabled && audioSettings.speakerEnabled ? 'active' : 'muted'}" 
                 id="micToggle" title="Toggle Microphone">
           🎤
         </button>
@@ -181,16 +223,24 @@ export default class MainPage {
                 id="speakerToggle" title="Toggle Speaker">
           🔊
         </button>
+        <button class="btn btn-icon audio-control ${pushToTalk.enabled ? 'active' : ''}" 
+                id="pushToTalkToggle" title="Push-to-talk (${pushToTalk.bindings.join(', ') || 'Space'})">
+          🗣️
+        </button>
         <button class="btn btn-icon audio-control" id="settingsToggle" title="Audio Settings">
           ⚙️
         </button>
       </div>
+      <div class="input-level-meter" title="Microphone level">
+        <div class="input-level-bar" id="inputLevelBar"></div>
+      </div>
     `;
     
     // Bind audio control events
     const micToggle = $('#micToggle');
     const speakerToggle = $('#speakerToggle');
     const settingsToggle = $('#settingsToggle');
+    const pushToTalkToggle = $('#pushToTalkToggle');
     
     if (micToggle) {
       addListener(micToggle, 'click', () => {
@@ -204,6 +254,12 @@ export default class MainPage {
       });
     }
     
+    if (pushToTalkToggle) {
+      addListener(pushToTalkToggle, 'click', () => {
+        this.togglePushToTalk();
+      });
+    }
+    
     if (settingsToggle) {
       addListener(settingsToggle, 'click', () => {
         const audioSettingsEvent = new CustomEvent('app:audio-settings');
@@ -288,6 +344,16 @@ export default class MainPage {
       });
     }
     
+    // Volume sliders are re-rendered with the list, so listen on the container
+    const usersList = $('#usersList');
+    if (usersList) {
+      addListener(usersList, 'change', (e) => {
+        if (e.target.classList.contains('user-volume')) {
+          this.setUserVolume(e.target.dataset.userId, Number(e.target.value));
+        }
+      });
+    }
+    
     // Update users list
     this.updateUsersList(channel.users || []);
     
@@ -314,6 +380,8 @@ export default class MainPage {
           <div class="user-status text-muted">${user.isSpeaking ? 'Speaking...' : 'Connected'}</div>
         </div>
         <div class="user-actions">
+          ${user.id === currentUser?.id ? '' : this.renderVolumeSlider(user.id)}
+          ${this.renderSignalBars(user.id)}
           ${user.micEnabled ? '🎤' : '🔇'}
           ${user.speakerEnabled ? '🔊' : '🔇'}
         </div>
@@ -321,6 +389,45 @@ export default class MainPage {
     `).join('');
   }
 
+  renderVolumeSlider(userId) {
+    const percent = Math.round((this.userVolumes.get(userId) ?? 1) * 100);
+    return `
+      <input type="range" class="user-volume" data-user-id="${userId}" min="0" max="200" step="5"
+             value="${percent}" title="Volume ${percent}%">
+    `;
+  }
+
+  async setUserVolume(userId, percent) {
+    const result = await tauriAPI.setUserVolume(userId, percent / 100);
+    if (result.success) {
+      this.userVolumes.set(userId, result.volume);
+    } else {
+      console.error('❌ Failed to set user volume:', result.error);
+    }
+  }
+
+  renderSignalBars(userId) {
+    const quality = this.connectionQuality.get(userId);
+    if (!quality) return '';
+
+    // rtt_ms is 0 when unknown (tunnelled clients send no heartbeat)
+    const rtt = quality.rtt_ms > 0 ? `${Math.round(quality.rtt_ms)} ms` : 'n/a';
+    const title = `RTT ${rtt} · loss ${quality.loss_percent.toFixed(1)}% · jitter ${Math.round(quality.jitter_ms)} ms`
+      + ` · ${quality.codec} ${quality.sample_rate / 1000} kHz · ${Math.round(quality.bitrate_kbps)} kbps`;
+    return `
+      <div class="signal-bars signal-${quality.signal}" data-user-id="${userId}" title="${title}">
+        <span></span><span></span><span></span><span></span>
+      </div>
+    `;
+  }
+
+  updateConnectionQuality(users) {
+    this.connectionQuality = new Map(users.map(quality => [quality.user_id, quality]));
+    if (this.currentChannel) {
+      this.updateUsersList(this.currentChannel.users || []);
+    }
+  }
+
   handleJoinChannel(channel) {
     const joinEvent = new CustomEvent('app:join-channel', {
       detail: channel
@@ -336,65 +443,53 @@ export default class MainPage {
 
   async toggleMicrophone() {
     const currentState = this.app.getState().audioSettings;
-    const newMicState = !currentState.micEnabled;
+    const muted = currentState.micEnabled;
     
-    console.log(`🎤 Toggling microphone: ${currentState.micEnabled} -> ${newMicState}`);
+    console.log(`🎤 Toggling self-mute: ${!muted}`);
     
-    try {
-      if (newMicState) {
-        // Démarrer la capture audio
-        console.log('🎤 Starting audio capture...');
-        const result = await window.tauriAPI.startAudioCapture();
-        if (result.success) {
-          console.log('✅ Audio capture started successfully');
-          this.app.updateState({
-            audioSettings: {
-              ...currentState,
-              micEnabled: true
-            }
-          });
-        } else {
-          console.error('❌ Failed to start audio capture:', result.error);
-          alert('Failed to start microphone: ' + (result.error || 'Unknown error'));
-          return;
-        }
-      } else {
-        // Arrêter la capture audio
-        console.log('🎤 Stopping audio capture...');
-        const result = await window.tauriAPI.stopAudioCapture();
-        if (result.success) {
-          console.log('✅ Audio capture stopped successfully');
-          this.app.updateState({
-            audioSettings: {
-              ...currentState,
-              micEnabled: false
-            }
-          });
-        } else {
-          console.error('❌ Failed to stop audio capture:', result.error);
-          // Continuer quand même avec le changement d'état
-          this.app.updateState({
-            audioSettings: {
-              ...currentState,
-              micEnabled: false
-            }
-          });
-        }
-      }
-      
+    const result = await window.tauriAPI.setSelfMute(muted);
+    if (result.success) {
+      this.applyVoiceState(result.voiceState);
+    } else {
+      console.error('❌ Failed to toggle microphone:', result.error);
+      alert('Failed to toggle microphone: ' + (result.error || 'Unknown error'));
+    }
+  }
+
+  async togglePushToTalk() {
+    const current = this.app.pushToTalk;
+    const bindings = current.bindings.length ? current.bindings : ['Space'];
+    
+    const result = await this.app.setPushToTalk({ enabled: !current.enabled, bindings });
+    if (result.success) {
       this.renderAudioControls();
-    } catch (error) {
-      console.error('❌ Error toggling microphone:', error);
-      alert('Error with microphone: ' + error.message);
+    } else {
+      console.error('❌ Failed to toggle push-to-talk:', result.error);
     }
   }
 
-  toggleSpeaker() {
+  async toggleSpeaker() {
     const currentState = this.app.getState().audioSettings;
+    const deafened = currentState.speakerEnabled;
+    
+    console.log(`🔊 Toggling self-deafen: ${!deafened}`);
+    
+    const result = await window.tauriAPI.setSelfDeafen(deafened);
+    if (result.success) {
+      this.applyVoiceState(result.voiceState);
+    } else {
+      console.error('❌ Failed to toggle speaker:', result.error);
+      alert('Failed to toggle speaker: ' + (result.error || 'Unknown error'));
+    }
+  }
+
+  // Deafen also silences the microphone, so the mic button follows both flags
+  applyVoiceState(voiceState) {
     this.app.updateState({
       audioSettings: {
-        ...currentState,
-        speakerEnabled: !currentState.speakerEnabled
+        ...this.app.getState().audioSettings,
+        micEnabled: !voiceState.muted,
+        speakerEnabled: !voiceState.deafened
       }
     });
     this.renderAudioControls();
@@ -403,6 +498,23 @@ export default class MainPage {
   setupStateListeners() {
     // Listen for state changes and update UI accordingly
     // This would be implemented based on the state management system
+
+    // Round-trip time to the server, refreshed at the UDP heartbeat pace
+    this.latencyTimer = setInterval(() => this.refreshNetworkLatency(), 5000);
+  }
+
+  async refreshNetworkLatency() {
+    const latency = await tauriAPI.getNetworkLatency();
+    const status = $('#userInfo .user-status');
+    if (!status) return;
+
+    if (latency) {
+      status.textContent = `Online · ${Math.round(latency.rtt_ms)} ms`;
+      status.title = `Round trip ${latency.rtt_ms.toFixed(1)} ms, clock offset ${latency.clock_offset_ms.toFixed(1)} ms`;
+    } else {
+      status.textContent = 'Online';
+      status.title = '';
+    }
   }
 
   updateConnectionStatus(status) {
@@ -415,8 +527,13 @@ export default class MainPage {
   }
 
   updateAudioLevels(levelData) {
-    // Update audio level indicators
-    // This would show speaking indicators for users
+    // Microphone VU meter: map -60..0 dBFS to 0..100%
+    const levelBar = $('#inputLevelBar');
+    if (!levelBar || !levelData) return;
+    
+    const percent = Math.max(0, Math.min(100, (levelData.rms_db + 60) / 60 * 100));
+    levelBar.style.width = `${percent}%`;
+    levelBar.classList.toggle('clipping', !!levelData.clipping);
   }
 
   addUser(userData) {
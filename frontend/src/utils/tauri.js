@@ -188,6 +188,19 @@ export const tauriAPI = {
     }
   },
 
+  // Typed WebSocket request (action: JoinChannel, LeaveChannel, SetStatus, StartAudio, StopAudio, Ping)
+  async websocketRequest(action, payload) {
+    try {
+      if (!isTauri) return { success: true };
+      
+      const response = await invoke('websocket_request', { message: { action, payload } });
+      return { success: true, response };
+    } catch (error) {
+      console.error(`WebSocket request ${action} failed:`, error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
   // Audio device management
   async getAudioDevices() {
     try {
@@ -305,6 +318,329 @@ export const tauriAPI = {
       console.error('Failed to stop audio capture:', error);
       return { success: false, error: error.toString() };
     }
+  },
+
+  // Self-mute/deafen: stops capture/playback locally and tells the channel
+  async setSelfMute(muted) {
+    try {
+      if (!isTauri) {
+        console.log('Setting self mute (mock):', muted);
+        return { success: true, voiceState: { muted, deafened: false } };
+      }
+      
+      const voiceState = await invoke('set_self_mute', { muted });
+      return { success: true, voiceState };
+    } catch (error) {
+      console.error('Failed to set self mute:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async setSelfDeafen(deafened) {
+    try {
+      if (!isTauri) {
+        console.log('Setting self deafen (mock):', deafened);
+        return { success: true, voiceState: { muted: false, deafened } };
+      }
+      
+      const voiceState = await invoke('set_self_deafen', { deafened });
+      return { success: true, voiceState };
+    } catch (error) {
+      console.error('Failed to set self deafen:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  // Per-user playback volume (1.0 = unchanged, 0.0..2.0), persisted in the settings
+  async setUserVolume(userId, volume) {
+    try {
+      if (!isTauri) return { success: true, volume };
+      
+      const saved = await invoke('set_user_volume', { userId, volume });
+      return { success: true, volume: saved };
+    } catch (error) {
+      console.error('Failed to set user volume:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  // Push-to-talk: { enabled, bindings: ['Space', 'Ctrl+Shift+T'] }, persisted in the settings
+  async setPushToTalk(settings) {
+    try {
+      if (!isTauri) return { success: true, settings };
+      
+      const saved = await invoke('set_push_to_talk', { settings });
+      return { success: true, settings: saved };
+    } catch (error) {
+      console.error('Failed to set push-to-talk:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async setPushToTalkPressed(pressed) {
+    try {
+      if (!isTauri) return { success: true };
+      
+      await invoke('set_push_to_talk_pressed', { pressed });
+      return { success: true };
+    } catch (error) {
+      console.error('Failed to report push-to-talk key:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  // Noise suppression: 'off' | 'low' | 'medium' | 'high'
+  async setNoiseSuppression(level) {
+    try {
+      if (!isTauri) {
+        console.log('Setting noise suppression (mock):', level);
+        return { success: true };
+      }
+      
+      await invoke('set_noise_suppression', { level });
+      return { success: true };
+    } catch (error) {
+      console.error('Failed to set noise suppression:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async getNoiseSuppression() {
+    try {
+      if (!isTauri) return 'medium';
+      
+      return await invoke('get_noise_suppression');
+    } catch (error) {
+      console.error('Failed to get noise suppression:', error);
+      return 'medium';
+    }
+  },
+
+  // Echo cancellation (speakers picked up by the microphone)
+  async setEchoCancellation(enabled) {
+    try {
+      if (!isTauri) {
+        console.log('Setting echo cancellation (mock):', enabled);
+        return { success: true };
+      }
+      
+      await invoke('set_echo_cancellation', { enabled });
+      return { success: true };
+    } catch (error) {
+      console.error('Failed to set echo cancellation:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async getEchoCancellation() {
+    try {
+      if (!isTauri) return true;
+      
+      return await invoke('get_echo_cancellation');
+    } catch (error) {
+      console.error('Failed to get echo cancellation:', error);
+      return true;
+    }
+  },
+
+  // Input gain: { manual_gain_db, agc_enabled, agc_target_db, agc_max_gain_db }
+  async setInputGainSettings(settings) {
+    try {
+      if (!isTauri) {
+        console.log('Setting input gain (mock):', settings);
+        return { success: true };
+      }
+      
+      await invoke('set_input_gain_settings', { settings });
+      return { success: true };
+    } catch (error) {
+      console.error('Failed to set input gain settings:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async getInputGainSettings() {
+    const defaults = { manual_gain_db: 0, agc_enabled: true, agc_target_db: -20, agc_max_gain_db: 20 };
+    try {
+      if (!isTauri) return defaults;
+      
+      return await invoke('get_input_gain_settings');
+    } catch (error) {
+      console.error('Failed to get input gain settings:', error);
+      return defaults;
+    }
+  },
+
+  // { rtt_ms, clock_offset_ms } measured on UDP heartbeats, or null before the first reply
+  async getNetworkLatency() {
+    try {
+      if (!isTauri) return null;
+      
+      return await invoke('get_network_latency');
+    } catch (error) {
+      console.error('Failed to get network latency:', error);
+      return null;
+    }
+  },
+
+  // direction: 'input' | 'output'
+  async setStreamSettings(direction, settings) {
+    try {
+      if (!isTauri) {
+        console.log(`Setting ${direction} stream settings (mock):`, settings);
+        return { success: true };
+      }
+      
+      await invoke(`set_${direction}_stream_settings`, { settings });
+      return { success: true };
+    } catch (error) {
+      console.error(`Failed to set ${direction} stream settings:`, error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async getStreamSettings(direction) {
+    const defaults = { sample_rate: null, channels: null, sample_format: null, buffer_size: null, low_latency: false };
+    try {
+      if (!isTauri) return defaults;
+      
+      return await invoke(`get_${direction}_stream_settings`);
+    } catch (error) {
+      console.error(`Failed to get ${direction} stream settings:`, error);
+      return defaults;
+    }
+  },
+
+  // Saved servers
+  async listServers() {
+    try {
+      if (!isTauri) return [];
+      
+      return await invoke('list_servers');
+    } catch (error) {
+      console.error('Failed to list servers:', error);
+      return [];
+    }
+  },
+
+  async saveServer(server) {
+    try {
+      if (!isTauri) return { success: true, server };
+      
+      const saved = await invoke('save_server', { server });
+      return { success: true, server: saved };
+    } catch (error) {
+      console.error('Failed to save server:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async removeServer(serverId) {
+    try {
+      if (!isTauri) return { success: true };
+      
+      await invoke('remove_server', { serverId });
+      return { success: true };
+    } catch (error) {
+      console.error('Failed to remove server:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  // /health status of every saved server: [{ serverId, status: { online, latency_ms, error } }]
+  async probeServers() {
+    try {
+      if (!isTauri) return [];
+      
+      return await invoke('probe_servers');
+    } catch (error) {
+      console.error('Failed to probe servers:', error);
+      return [];
+    }
+  },
+
+  async switchServer(serverId) {
+    try {
+      console.log('🔀 Switching to server:', serverId);
+      
+      if (!isTauri) return { success: false, error: 'Tauri not available' };
+      
+      const result = await invoke('switch_server', { serverId });
+      
+      if (result.success) {
+        const wsUrl = result.server.url
+          .replace('http://', 'ws://').replace('https://', 'wss://').replace(/:\d+/, ':8080/ws');
+        const ws = await this.startWebSocket(wsUrl);
+        if (!ws.success) {
+          console.warn('⚠️ WebSocket connection failed after server switch:', ws.error);
+        }
+      }
+      
+      return result;
+    } catch (error) {
+      console.error('❌ Server switch error:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  // Persisted client settings (app config dir)
+  async getSettings() {
+    try {
+      if (!isTauri) return null;
+      
+      return await invoke('get_settings');
+    } catch (error) {
+      console.error('Failed to get settings:', error);
+      return null;
+    }
+  },
+
+  async updateSettings(settings) {
+    try {
+      if (!isTauri) return { success: true, settings };
+      
+      const saved = await invoke('update_settings', { settings });
+      return { success: true, settings: saved };
+    } catch (error) {
+      console.error('Failed to update settings:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async resetSettings() {
+    try {
+      if (!isTauri) return { success: true };
+      
+      const settings = await invoke('reset_settings');
+      return { success: true, settings };
+    } catch (error) {
+      console.error('Failed to reset settings:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async exportSettings() {
+    try {
+      if (!isTauri) return { success: false, error: 'Tauri not available' };
+      
+      const json = await invoke('export_settings');
+      return { success: true, json };
+    } catch (error) {
+      console.error('Failed to export settings:', error);
+      return { success: false, error: error.toString() };
+    }
+  },
+
+  async importSettings(json) {
+    try {
+      if (!isTauri) return { success: false, error: 'Tauri not available' };
+      
+      const settings = await invoke('import_settings', { json });
+      return { success: true, settings };
+    } catch (error) {
+      console.error('Failed to import settings:', error);
+      return { success: false, error: error.toString() };
+    }
   }
 };
 